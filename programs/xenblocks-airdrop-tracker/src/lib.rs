@@ -1,7 +1,81 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, AssociatedToken, Create};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use solana_keccak_hasher as keccak;
+use solana_secp256k1_recover::secp256k1_recover;
 
 declare_id!("xen8pjUWEnRbm1eML9CGtHvmmQfruXMKUybqGjn3chv");
 
+/// Maximum number of entries in a single batch instruction. Bounded by compute
+/// and by the u32 result bitmaps; transaction size usually limits batches to
+/// fewer entries unless address lookup tables are used.
+pub const MAX_BATCH_SIZE: usize = 20;
+
+/// Maximum number of SubRun partitions per run. rollup_subruns and
+/// finalize_run take every sub-run as a remaining account, so this is bounded
+/// by the accounts that fit in one transaction.
+pub const MAX_SUBRUNS: usize = 16;
+
+/// Maximum entries in batch_initialize_and_update. Each entry costs a PDA
+/// derivation, the blacklist lookup and a create-account CPI (roughly 30k
+/// compute units), so eight new records fit the default compute budget. Each
+/// entry also carries about 110 bytes of instruction data and five account
+/// keys, so a legacy transaction only holds about three; larger batches need
+/// an address lookup table.
+pub const MAX_BATCH_INIT_SIZE: usize = 8;
+
+/// Accounts passed per entry of batch_initialize_and_update
+const BATCH_INIT_ACCOUNTS_PER_ENTRY: usize = 5;
+
+/// Ceiling on the value accepted by reconcile_record_count. Far above any
+/// real cohort, it only stops a mistyped count from being stored.
+pub const MAX_RECONCILED_RECORD_COUNT: u64 = 100_000_000;
+
+/// Maximum number of co-signers in the GlobalStateV2 signer set
+pub const MAX_SIGNERS: usize = 5;
+
+/// Largest value accepted by set_decimals; 10^18 is the highest power of ten
+/// that fits in a u64
+pub const MAX_DECIMALS: u8 = 18;
+
+/// Confirmation that must be passed to `retire_program`
+pub const RETIRE_CONFIRMATION: [u8; 16] = *b"RETIRE_XENBLOCKS";
+
+/// First line of the message signed as an ETH ownership proof (see
+/// eth_proof_message)
+pub const ETH_PROOF_DOMAIN: &str = "XenBlocks airdrop: link ETH address";
+
+/// Byte offset of `bump` in an AirdropRecordV2 account (present in every layout)
+const RECORD_BUMP_OFFSET: usize = 8 + 42 + 8 * 4 + 32 + 8;
+
+/// Byte offset of `authority` in a GlobalStateV2 account
+const STATE_AUTHORITY_OFFSET: usize = 8 + 1;
+
+/// Schema version written to new AirdropRecordV2 accounts. Records created
+/// before the field existed read 0 until migrated by migrate_record or
+/// batch_stamp_version.
+///
+/// - 1: `version` field present
+/// - 2: former reserved slots hold the per-token claimed amounts
+pub const RECORD_VERSION: u8 = 2;
+
+/// `GlobalStateV2::cluster_mode` value for devnet/testnet deployments: any
+/// eth_address is accepted, including placeholder fixtures.
+pub const CLUSTER_MODE_DEVNET: u8 = 0;
+
+/// `GlobalStateV2::cluster_mode` value for mainnet deployments: new records
+/// with placeholder eth_addresses (see `is_test_eth_address`) are rejected.
+pub const CLUSTER_MODE_MAINNET: u8 = 1;
+
+/// Placeholder addresses used in devnet fixtures, as lowercase hex without the
+/// 0x prefix. All-same-character addresses are caught separately.
+const KNOWN_TEST_ETH_ADDRESSES: [&[u8; 40]; 3] = [
+    b"1234567890123456789012345678901234567890",
+    b"deadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
+    b"000000000000000000000000000000000000dead",
+];
+
 #[program]
 pub mod xenblocks_airdrop_tracker {
     use super::*;
@@ -16,38 +90,103 @@ pub mod xenblocks_airdrop_tracker {
         state.xblk_airdropped = 0;
         state.xuni_airdropped = 0;
         state.native_airdropped = 0;
-        state.reserved = [0u64; 4];
+        state.pending_authority = Pubkey::default();
         state.bump = ctx.bumps.state;
+        state.record_count = 0;
+        state.current_season = 0;
+        state.season_xnm_airdropped = 0;
+        state.season_xblk_airdropped = 0;
+        state.season_xuni_airdropped = 0;
+        state.season_native_airdropped = 0;
+        state.cluster_mode = CLUSTER_MODE_DEVNET;
+        state.max_total_xnm = 0;
+        state.max_total_xblk = 0;
+        state.max_total_xuni = 0;
+        state.max_total_native = 0;
+        state.dedup_per_run = false;
+        state.require_ack = false;
+        state.wallet_allowlist_enabled = false;
+        state.require_reference = false;
+        state.treasury = ctx.accounts.authority.key();
+        state.swept_xnm = 0;
+        state.swept_xblk = 0;
+        state.swept_xuni = 0;
+        state.swept_native = 0;
+        state.max_lifetime_per_record = u64::MAX;
+        state.merkle_root = [0u8; 32];
+        state.min_interval_between_runs = 0;
+        state.last_run_date = 0;
+        state.reject_zero_updates = false;
+        state.latest_run = Pubkey::default();
+        state.max_runs = 0;
+        state.max_runs_exempt_dry = false;
+        state.enforce_system_wallet = false;
+        state.respect_preference = false;
+        state.require_snapshot_block = false;
+        state.decimals = 9;
+        state.distribution_start = 0;
+        state.distribution_end = 0;
+        state.retired = false;
+        state.allow_self_close = false;
+        state.enforce_monotonic_run_dates = false;
+        state.latest_run_date = 0;
+        state.split_bps_xnm = 0;
+        state.split_bps_xblk = 0;
+        state.split_bps_xuni = 0;
+        state.split_bps_native = 0;
+        state.paused = false;
+        state.token_mints = [Pubkey::default(); 4];
+        state.signers = [Pubkey::default(); MAX_SIGNERS];
+        state.threshold = 0;
+        state.operator_count = 0;
+        state.min_update_interval = 0;
+        state.require_eth_proof = false;
+        state.allocation_root = [0u8; 32];
 
-        msg!("Initialized GlobalStateV2 with authority {}", state.authority);
+        msg!(
+            "Initialized GlobalStateV2 with authority {}",
+            state.authority
+        );
         Ok(())
     }
 
-    /// Create a new airdrop run (V2 with per-token totals)
-    pub fn create_run_v2(ctx: Context<CreateRunV2>, dry_run: bool) -> Result<()> {
+    /// Create a new airdrop run (V2 with per-token totals). `reference_hash`
+    /// links it to its published announcement (see set_run_reference).
+    pub fn create_run_v2(
+        ctx: Context<CreateRunV2>,
+        dry_run: bool,
+        snapshot_block: u64,
+        max_recipients: u32,
+        max_amount: u64,
+        reference_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
         let state = &mut ctx.accounts.state;
         let run = &mut ctx.accounts.airdrop_run;
 
-        state.run_counter += 1;
-
-        run.version = 1;
-        run.run_id = state.run_counter;
-        run.run_date = Clock::get()?.unix_timestamp;
-        run.total_recipients = 0;
-        run.total_amount = 0;
-        run.total_xnm_amount = 0;
-        run.total_xblk_amount = 0;
-        run.total_xuni_amount = 0;
-        run.total_native_amount = 0;
-        run.dry_run = dry_run;
-        run.reserved = [0u64; 4];
-        run.bump = ctx.bumps.airdrop_run;
+        let now = Clock::get()?.unix_timestamp;
+        let run_id = state.start_run(dry_run, snapshot_block, now)?;
+        run.initialize(run_id, dry_run, ctx.bumps.airdrop_run, now);
+        run.snapshot_block = snapshot_block;
+        run.max_recipients = max_recipients;
+        run.max_amount = max_amount;
+        run.reference_hash = reference_hash.unwrap_or_default();
+        state.latest_run = run.key();
+
+        emit!(RunCreated {
+            run_id: run.run_id,
+            run_date: run.run_date,
+            dry_run,
+            reference_hash: run.reference_hash,
+        });
 
         msg!("Created airdrop run v2 #{}", run.run_id);
         Ok(())
     }
 
-    /// Update run totals after completion (V2 with per-token amounts)
+    /// Update run totals after completion (V2 with per-token amounts).
+    /// Co-signers, when a threshold is set, are passed as signing
+    /// remaining_accounts.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_run_totals_v2(
         ctx: Context<UpdateRunTotalsV2>,
         total_recipients: u32,
@@ -56,8 +195,28 @@ pub mod xenblocks_airdrop_tracker {
         total_xblk_amount: u64,
         total_xuni_amount: u64,
         total_native_amount: u64,
+        auto_finalize: bool,
     ) -> Result<()> {
+        // The headline total must cover every per-token total. Legacy callers
+        // that only report total_amount leave the per-token fields at zero.
+        let token_sum = (total_xnm_amount as u128)
+            + (total_xblk_amount as u128)
+            + (total_xuni_amount as u128)
+            + (total_native_amount as u128);
+        require!(
+            token_sum == 0 || (total_amount as u128) >= token_sum,
+            ErrorCode::InconsistentRunTotals
+        );
+
+        ctx.accounts.state.check_signers(ctx.remaining_accounts)?;
+
         let run = &mut ctx.accounts.airdrop_run;
+        run.check_active()?;
+        run.check_caps(total_recipients, total_amount)?;
+        if ctx.accounts.state.require_reference && !run.dry_run {
+            require!(run.reference_hash != [0u8; 32], ErrorCode::MissingReference);
+        }
+
         run.total_recipients = total_recipients;
         run.total_amount = total_amount;
         run.total_xnm_amount = total_xnm_amount;
@@ -65,6 +224,15 @@ pub mod xenblocks_airdrop_tracker {
         run.total_xuni_amount = total_xuni_amount;
         run.total_native_amount = total_native_amount;
 
+        emit!(RunTotalsUpdated {
+            run_id: run.run_id,
+            total_recipients,
+            total_amount,
+        });
+        if auto_finalize {
+            run.finalize();
+        }
+
         msg!(
             "Updated run v2 #{}: recipients={}, total={}, xnm={}, xblk={}, xuni={}, native={}",
             run.run_id,
@@ -78,75 +246,202 @@ pub mod xenblocks_airdrop_tracker {
         Ok(())
     }
 
-    /// Initialize a new airdrop record keyed by ETH address
+    /// Initialize a new airdrop record keyed by ETH address and linked to
+    /// `sol_wallet`, which must not be blacklisted.
+    ///
+    /// `eth_signature` is the recipient's personal_sign signature over
+    /// eth_proof_message(eth_address, sol_wallet) and, when given, must
+    /// recover to `eth_address`. While require_eth_proof is on, only the
+    /// authority may omit it.
     pub fn initialize_record_v2(
         ctx: Context<InitializeRecordV2>,
         eth_address: [u8; 42],
+        sol_wallet: Pubkey,
+        score: u64,
+        eth_signature: Option<[u8; 65]>,
     ) -> Result<()> {
+        ctx.accounts.state.check_new_record(&eth_address)?;
+        ctx.accounts.state.check_eth_proof(
+            &ctx.accounts.authority.key(),
+            &eth_address,
+            eth_signature
+                .as_ref()
+                .map(|signature| (signature, &sol_wallet)),
+        )?;
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        ctx.accounts.state.check_wallet(
+            &sol_wallet,
+            ctx.accounts.wallet.as_deref(),
+            ctx.accounts.allowed_wallet.as_deref(),
+        )?;
+        create_eth_registry(
+            &ctx.accounts.eth_registry,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &eth_address,
+            ctx.accounts.airdrop_record.key(),
+            sol_wallet,
+        )?;
+
         let record = &mut ctx.accounts.airdrop_record;
-        record.eth_address = eth_address;
-        record.xnm_airdropped = 0;
-        record.xblk_airdropped = 0;
-        record.xuni_airdropped = 0;
-        record.native_airdropped = 0;
-        record.reserved = [0u64; 4];
-        record.last_updated = Clock::get()?.unix_timestamp;
-        record.bump = ctx.bumps.airdrop_record;
+        record.initialize(
+            eth_address,
+            ctx.bumps.airdrop_record,
+            Clock::get()?.unix_timestamp,
+        );
+        record.created_in_run = run_id_or_zero(ctx.accounts.airdrop_run.as_deref());
+        record.score = score;
+        record.sol_wallet = sol_wallet;
 
-        msg!("Initialized V2 airdrop record for eth: {:?}", &eth_address[..6]);
+        ctx.accounts.state.increment_record_count()?;
+
+        msg!(
+            "Initialized V2 airdrop record for eth: {:?}",
+            &eth_address[..6]
+        );
         Ok(())
     }
 
-    /// Update an existing airdrop record after a successful transfer
+    /// Update an existing airdrop record after a successful transfer.
+    ///
+    /// Deprecated in favor of `update_record_struct`, which names each token
+    /// amount instead of relying on argument order.
+    ///
+    /// `expected_nonce` must equal the record's current nonce, so a replayed
+    /// instruction fails once the first copy has landed.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_record_v2(
+        ctx: Context<WriteRecordV2>,
+        xnm_amount: u64,
+        xblk_amount: u64,
+        xuni_amount: u64,
+        native_amount: u64,
+        allow_recredit: bool,
+        batch_id: [u8; 16],
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_record.check_nonce(expected_nonce)?;
+        let amounts = TokenAmounts {
+            xnm: xnm_amount,
+            xblk: xblk_amount,
+            xuni: xuni_amount,
+            native: native_amount,
+        };
+        update_record_amounts(
+            &mut ctx.accounts.state,
+            &mut ctx.accounts.airdrop_record,
+            ctx.accounts.airdrop_run.as_deref(),
+            &ctx.accounts.blacklist,
+            &amounts,
+            allow_recredit,
+            batch_id,
+        )
+    }
+
+    /// Update an existing airdrop record with named token amounts.
+    /// `expected_nonce` must equal the record's current nonce.
+    pub fn update_record_struct(
         ctx: Context<UpdateRecordV2>,
+        amounts: TokenAmounts,
+        allow_recredit: bool,
+        batch_id: [u8; 16],
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_record.check_nonce(expected_nonce)?;
+        update_record_amounts(
+            &mut ctx.accounts.state,
+            &mut ctx.accounts.airdrop_record,
+            ctx.accounts.airdrop_run.as_deref(),
+            &ctx.accounts.blacklist,
+            &amounts,
+            allow_recredit,
+            batch_id,
+        )
+    }
+
+    /// Initialize a record and immediately set amounts (for new wallets during airdrop).
+    /// When an allocation root is set, `proof` must show that
+    /// `(sol_wallet, eth_address, sum of amounts)` is in the allocation tree.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_and_update_v2(
+        ctx: Context<InitializeAndUpdateV2>,
+        eth_address: [u8; 42],
         xnm_amount: u64,
         xblk_amount: u64,
         xuni_amount: u64,
         native_amount: u64,
+        score: u64,
+        sol_wallet: Pubkey,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
-        let record = &mut ctx.accounts.airdrop_record;
+        ctx.accounts.state.check_new_record(&eth_address)?;
+        ctx.accounts
+            .state
+            .check_eth_proof(&ctx.accounts.authority.key(), &eth_address, None)?;
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        ctx.accounts.state.check_wallet(
+            &sol_wallet,
+            ctx.accounts.wallet.as_deref(),
+            ctx.accounts.allowed_wallet.as_deref(),
+        )?;
+        create_eth_registry(
+            &ctx.accounts.eth_registry,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &eth_address,
+            ctx.accounts.airdrop_record.key(),
+            sol_wallet,
+        )?;
 
-        record.xnm_airdropped = record
-            .xnm_airdropped
-            .checked_add(xnm_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        record.xblk_airdropped = record
-            .xblk_airdropped
-            .checked_add(xblk_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        record.xuni_airdropped = record
-            .xuni_airdropped
-            .checked_add(xuni_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        record.native_airdropped = record
-            .native_airdropped
-            .checked_add(native_amount)
-            .ok_or(ErrorCode::Overflow)?;
+        // Once an allocation root is published, only allocations in the tree
+        // can be written
+        let root = ctx.accounts.state.allocation_root;
+        if root != [0u8; 32] {
+            let total = [xnm_amount, xblk_amount, xuni_amount, native_amount]
+                .iter()
+                .try_fold(0u64, |total, amount| total.checked_add(*amount))
+                .ok_or(ErrorCode::Overflow)?;
+            let leaf = allocation_leaf(&sol_wallet, &eth_address, total);
+            require!(
+                verify_merkle_proof(&proof, root, leaf),
+                ErrorCode::InvalidProof
+            );
+        }
 
-        record.last_updated = Clock::get()?.unix_timestamp;
+        if let Some(run) = &ctx.accounts.airdrop_run {
+            run.check_credit(&TokenAmounts {
+                xnm: xnm_amount,
+                xblk: xblk_amount,
+                xuni: xuni_amount,
+                native: native_amount,
+            })?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let record = &mut ctx.accounts.airdrop_record;
+        record.initialize(eth_address, ctx.bumps.airdrop_record, now);
+        record.created_in_run = run_id_or_zero(ctx.accounts.airdrop_run.as_deref());
+        record.score = score;
+        record.sol_wallet = sol_wallet;
 
-        // Increment global totals
         let state = &mut ctx.accounts.state;
-        state.xnm_airdropped = state
-            .xnm_airdropped
-            .checked_add(xnm_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        state.xblk_airdropped = state
-            .xblk_airdropped
-            .checked_add(xblk_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        state.xuni_airdropped = state
-            .xuni_airdropped
-            .checked_add(xuni_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        state.native_airdropped = state
-            .native_airdropped
-            .checked_add(native_amount)
-            .ok_or(ErrorCode::Overflow)?;
+        credit_record(
+            state,
+            record,
+            &TokenAmounts {
+                xnm: xnm_amount,
+                xblk: xblk_amount,
+                xuni: xuni_amount,
+                native: native_amount,
+            },
+            ctx.accounts.airdrop_run.as_deref(),
+            [0u8; 16],
+            now,
+        )?;
+        state.increment_record_count()?;
 
         msg!(
-            "Updated V2 airdrop record: xnm={}, xblk={}, xuni={}, native={}",
+            "Initialized and updated V2 airdrop record: xnm={}, xblk={}, xuni={}, native={}",
             xnm_amount,
             xblk_amount,
             xuni_amount,
@@ -155,408 +450,5994 @@ pub mod xenblocks_airdrop_tracker {
         Ok(())
     }
 
-    /// Initialize a record and immediately set amounts (for new wallets during airdrop)
-    pub fn initialize_and_update_v2(
-        ctx: Context<InitializeRecordV2>,
+    /// Apply amount updates to many existing records in one transaction.
+    ///
+    /// Each record and the Blacklist PDA of its sol_wallet are passed as a
+    /// pair via remaining_accounts, in the same order as `updates`.
+    /// In strict mode any failing entry aborts the whole batch. With
+    /// `best_effort`, failing entries are skipped and their indexes returned.
+    /// `batch_id` is the off-chain distribution batch (zero = unspecified).
+    pub fn batch_update_records<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchUpdateRecords<'info>>,
+        updates: Vec<RecordUpdate>,
+        best_effort: bool,
+        batch_id: [u8; 16],
+    ) -> Result<BatchUpdateResult> {
+        require!(
+            updates.len() * 2 == ctx.remaining_accounts.len(),
+            ErrorCode::BatchLengthMismatch
+        );
+        require!(updates.len() <= MAX_BATCH_SIZE, ErrorCode::BatchTooLarge);
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut result = BatchUpdateResult {
+            applied: 0,
+            failed: Vec::new(),
+        };
+
+        for (index, (update, pair)) in updates
+            .iter()
+            .zip(ctx.remaining_accounts.chunks(2))
+            .enumerate()
+        {
+            match apply_record_update(
+                &mut ctx.accounts.state,
+                &pair[0],
+                &pair[1],
+                update,
+                batch_id,
+                now,
+            ) {
+                Ok(()) => result.applied += 1,
+                Err(err) if best_effort => {
+                    msg!("Skipping batch entry {}: {:?}", index, err);
+                    result.failed.push(index as u8);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        msg!(
+            "Batch updated {} records ({} failed)",
+            result.applied,
+            result.failed.len()
+        );
+        Ok(result)
+    }
+
+    /// Create and credit many new records in one transaction.
+    ///
+    /// Each entry passes five remaining_accounts, in the same order as
+    /// `records`: the record PDA, the EthRegistry PDA of its eth_address, and
+    /// for its sol_wallet the Blacklist PDA, the wallet account and the
+    /// AllowedWallet PDA (passed whether or not it exists). Every wallet gets
+    /// the same checks as in initialize_and_update_v2.
+    ///
+    /// Records that already exist, or whose address is already registered
+    /// under another spelling, are skipped and their indexes returned, so a
+    /// partially landed batch can be resent as-is. Not available while an
+    /// allocation root is published, since entries carry no proofs; use
+    /// initialize_and_update_v2 instead.
+    pub fn batch_initialize_and_update<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchInitializeAndUpdate<'info>>,
+        records: Vec<NewRecord>,
+    ) -> Result<BatchInitResult> {
+        require!(
+            records.len() * BATCH_INIT_ACCOUNTS_PER_ENTRY == ctx.remaining_accounts.len(),
+            ErrorCode::BatchLengthMismatch
+        );
+        require!(
+            records.len() <= MAX_BATCH_INIT_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+        require!(
+            ctx.accounts.state.allocation_root == [0u8; 32],
+            ErrorCode::InvalidProof
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let run_id = ctx.accounts.airdrop_run.run_id;
+        let signer = ctx.accounts.authority.key();
+        let mut result = BatchInitResult {
+            created: 0,
+            skipped: Vec::new(),
+        };
+
+        for (index, (entry, accounts)) in records
+            .iter()
+            .zip(
+                ctx.remaining_accounts
+                    .chunks_exact(BATCH_INIT_ACCOUNTS_PER_ENTRY),
+            )
+            .enumerate()
+        {
+            let (info, registry) = (&accounts[0], &accounts[1]);
+            let state = &mut ctx.accounts.state;
+            state.check_new_record(&entry.eth_address)?;
+            state.check_eth_proof(&signer, &entry.eth_address, None)?;
+            check_blacklist_entry(&entry.sol_wallet, &accounts[2])?;
+            let allowed = load_allowed_wallet(&accounts[4])?;
+            state.check_wallet(&entry.sol_wallet, Some(&accounts[3]), allowed.as_deref())?;
+            ctx.accounts.airdrop_run.check_credit(&TokenAmounts {
+                xnm: entry.xnm_amount,
+                xblk: entry.xblk_amount,
+                xuni: entry.xuni_amount,
+                native: entry.native_amount,
+            })?;
+
+            if *registry.owner == crate::ID {
+                msg!("Skipping batch entry {}: eth_address registered", index);
+                result.skipped.push(index as u8);
+                continue;
+            }
+            let bump = match create_record_account(
+                info,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                &entry.eth_address,
+            )? {
+                Some(bump) => bump,
+                None => {
+                    msg!("Skipping batch entry {}: record exists", index);
+                    result.skipped.push(index as u8);
+                    continue;
+                }
+            };
+            create_eth_registry(
+                registry,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                &entry.eth_address,
+                info.key(),
+                entry.sol_wallet,
+            )?;
+
+            let mut record: Account<'info, AirdropRecordV2> = Account::try_from_unchecked(info)?;
+            record.initialize(entry.eth_address, bump, now);
+            record.created_in_run = run_id;
+            record.score = entry.score;
+            record.sol_wallet = entry.sol_wallet;
+            credit_record(
+                state,
+                &mut record,
+                &TokenAmounts {
+                    xnm: entry.xnm_amount,
+                    xblk: entry.xblk_amount,
+                    xuni: entry.xuni_amount,
+                    native: entry.native_amount,
+                },
+                Some(&ctx.accounts.airdrop_run),
+                [0u8; 16],
+                now,
+            )?;
+            state.increment_record_count()?;
+            record.exit(&crate::ID)?;
+            result.created += 1;
+        }
+
+        msg!(
+            "Batch initialized {} records ({} skipped)",
+            result.created,
+            result.skipped.len()
+        );
+        Ok(result)
+    }
+
+    /// Import a record decoded from the xnm-airdrop-tracker program.
+    ///
+    /// The caller decodes the source record off-chain and passes its fields; the
+    /// source account is closed by the other program separately.
+    pub fn import_external_record(
+        ctx: Context<ImportExternalRecord>,
+        sol_wallet: Pubkey,
         eth_address: [u8; 42],
         xnm_amount: u64,
         xblk_amount: u64,
-        xuni_amount: u64,
-        native_amount: u64,
     ) -> Result<()> {
+        ctx.accounts.state.check_new_record(&eth_address)?;
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        ctx.accounts.state.check_wallet(
+            &sol_wallet,
+            ctx.accounts.wallet.as_deref(),
+            ctx.accounts.allowed_wallet.as_deref(),
+        )?;
+        create_eth_registry(
+            &ctx.accounts.eth_registry,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            &eth_address,
+            ctx.accounts.airdrop_record.key(),
+            sol_wallet,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
         let record = &mut ctx.accounts.airdrop_record;
-        record.eth_address = eth_address;
-        record.xnm_airdropped = xnm_amount;
-        record.xblk_airdropped = xblk_amount;
-        record.xuni_airdropped = xuni_amount;
-        record.native_airdropped = native_amount;
-        record.reserved = [0u64; 4];
-        record.last_updated = Clock::get()?.unix_timestamp;
-        record.bump = ctx.bumps.airdrop_record;
+        record.initialize(eth_address, ctx.bumps.airdrop_record, now);
+        record.imported = true;
+        record.sol_wallet = sol_wallet;
 
-        // Increment global totals
+        // Imported amounts count toward the global totals like any other credit
         let state = &mut ctx.accounts.state;
-        state.xnm_airdropped = state
-            .xnm_airdropped
-            .checked_add(xnm_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        state.xblk_airdropped = state
-            .xblk_airdropped
-            .checked_add(xblk_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        state.xuni_airdropped = state
-            .xuni_airdropped
-            .checked_add(xuni_amount)
-            .ok_or(ErrorCode::Overflow)?;
-        state.native_airdropped = state
-            .native_airdropped
-            .checked_add(native_amount)
-            .ok_or(ErrorCode::Overflow)?;
+        credit_record(
+            state,
+            record,
+            &TokenAmounts {
+                xnm: xnm_amount,
+                xblk: xblk_amount,
+                xuni: 0,
+                native: 0,
+            },
+            None,
+            [0u8; 16],
+            now,
+        )?;
+        state.increment_record_count()?;
 
-        msg!(
-            "Initialized and updated V2 airdrop record: xnm={}, xblk={}, xuni={}, native={}",
+        emit!(RecordImported {
+            sol_wallet,
+            eth_address,
             xnm_amount,
             xblk_amount,
-            xuni_amount,
-            native_amount
+            timestamp: record.last_updated,
+        });
+
+        msg!(
+            "Imported airdrop record from {}: xnm={}, xblk={}",
+            sol_wallet,
+            xnm_amount,
+            xblk_amount
         );
         Ok(())
     }
 
-    /// Transfer authority to a new public key (current authority only)
-    pub fn update_authority(ctx: Context<UpdateAuthority>, new_authority: Pubkey) -> Result<()> {
+    /// Close an airdrop record and its EthRegistry entry, reclaiming the rent
+    /// to the authority. The reason and final balances are emitted in a
+    /// RecordClosed event.
+    pub fn close_record_v2(ctx: Context<CloseRecordV2>, reason: CloseReason) -> Result<()> {
+        let record = &ctx.accounts.airdrop_record;
+        record.check_unlocked(Clock::get()?.unix_timestamp)?;
+        emit_record_closed(record, reason);
+
         let state = &mut ctx.accounts.state;
-        msg!("Authority updated from {} to {}", state.authority, new_authority);
-        state.authority = new_authority;
+        state.record_count = state.record_count.saturating_sub(1);
+
+        msg!(
+            "Closed V2 airdrop record for eth: {:?}",
+            &ctx.accounts.airdrop_record.eth_address[..6]
+        );
         Ok(())
     }
 
-    /// Initialize the airdrop lock PDA (one-time setup)
-    pub fn initialize_lock(ctx: Context<InitializeLock>) -> Result<()> {
-        let lock = &mut ctx.accounts.lock;
-        lock.lock_holder = Pubkey::default();
-        lock.locked_at = 0;
-        lock.timeout_seconds = 0;
-        lock.run_id = 0;
-        lock.bump = ctx.bumps.lock;
-        msg!("Initialized airdrop lock");
+    /// Close a completed or aborted run and reclaim its rent
+    pub fn close_run(ctx: Context<CloseRun>) -> Result<()> {
+        let run = &ctx.accounts.airdrop_run;
+        require!(run.status != RunStatus::Active, ErrorCode::RunStillActive);
+
+        msg!("Closed airdrop run v2 #{} ({:?})", run.run_id, run.status);
         Ok(())
     }
 
-    /// Acquire the airdrop lock (or override if expired)
-    pub fn acquire_lock(ctx: Context<AcquireLock>, timeout_seconds: i64) -> Result<()> {
+    /// Overwrite record_count with the true active count computed off-chain.
+    ///
+    /// Repair path for closes that bypassed GlobalState, which leave the
+    /// counter too high, and for GlobalState grown in place by grow_state,
+    /// whose zero-filled counter misses every legacy record. The count may be
+    /// raised or lowered, up to MAX_RECONCILED_RECORD_COUNT.
+    pub fn reconcile_record_count(
+        ctx: Context<ReconcileRecordCount>,
+        record_count: u64,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
         require!(
-            (60..=3600).contains(&timeout_seconds),
-            ErrorCode::InvalidTimeout
+            record_count <= MAX_RECONCILED_RECORD_COUNT,
+            ErrorCode::RecordCountTooHigh
         );
 
-        let lock = &mut ctx.accounts.lock;
+        let old_count = state.record_count;
+        state.record_count = record_count;
 
-        // If lock is held, check if it has expired
-        if lock.lock_holder != Pubkey::default() {
-            let now = Clock::get()?.unix_timestamp;
-            require!(
-                now >= lock.locked_at + lock.timeout_seconds,
-                ErrorCode::LockHeld
-            );
-            msg!("Overriding expired lock held by {}", lock.lock_holder);
-        }
+        emit!(RecordCountReconciled {
+            old_count,
+            new_count: record_count,
+        });
 
-        lock.lock_holder = ctx.accounts.authority.key();
-        lock.locked_at = Clock::get()?.unix_timestamp;
-        lock.timeout_seconds = timeout_seconds;
-        lock.run_id = 0;
-        msg!("Lock acquired by {}", lock.lock_holder);
+        msg!("Reconciled record_count: {} -> {}", old_count, record_count);
         Ok(())
     }
 
-    /// Release the airdrop lock (holder only)
-    pub fn release_lock(ctx: Context<ReleaseLock>) -> Result<()> {
-        let lock = &mut ctx.accounts.lock;
-        require!(
-            lock.lock_holder == ctx.accounts.authority.key(),
-            ErrorCode::LockNotHeld
+    /// Return the program configuration as a typed view (read-only).
+    ///
+    /// Intended for simulated calls: the Borsh-serialized `ConfigView` is
+    /// written to return_data so clients don't parse raw GlobalState bytes.
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<ConfigView> {
+        let state = &ctx.accounts.state;
+        Ok(ConfigView {
+            view_version: CONFIG_VIEW_VERSION,
+            state_version: state.version,
+            authority: state.authority,
+            pending_authority: state.pending_authority,
+            paused: state.paused,
+            max_total_xnm: state.max_total_xnm,
+            max_total_xblk: state.max_total_xblk,
+            max_total_xuni: state.max_total_xuni,
+            max_total_native: state.max_total_native,
+            distribution_start: state.distribution_start,
+            distribution_end: state.distribution_end,
+            cluster_mode: state.cluster_mode,
+            decimals: state.decimals,
+            token_mints: state.token_mints,
+            signers: state.signers,
+            threshold: state.threshold,
+            merkle_root: state.merkle_root,
+            allocation_root: state.allocation_root,
+        })
+    }
+
+    /// Return every cap, interval and the distribution window (read-only).
+    ///
+    /// The token caps and window are also part of get_config; this adds the
+    /// per-record and per-run limits and the lump-sum split.
+    pub fn get_limits(ctx: Context<GetConfig>) -> Result<LimitsView> {
+        let state = &ctx.accounts.state;
+        Ok(LimitsView {
+            max_total_xnm: state.max_total_xnm,
+            max_total_xblk: state.max_total_xblk,
+            max_total_xuni: state.max_total_xuni,
+            max_total_native: state.max_total_native,
+            max_lifetime_per_record: state.max_lifetime_per_record,
+            max_runs: state.max_runs,
+            min_interval_between_runs: state.min_interval_between_runs,
+            min_update_interval: state.min_update_interval,
+            distribution_start: state.distribution_start,
+            distribution_end: state.distribution_end,
+            split_bps: [
+                state.split_bps_xnm,
+                state.split_bps_xblk,
+                state.split_bps_xuni,
+                state.split_bps_native,
+            ],
+        })
+    }
+
+    /// Close the current season: snapshot its totals into a SeasonSummary PDA
+    /// and reset the per-season counters. Lifetime totals are untouched.
+    pub fn advance_season(ctx: Context<AdvanceSeason>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let summary = &mut ctx.accounts.season_summary;
+
+        summary.season = state.current_season;
+        summary.xnm_airdropped = state.season_xnm_airdropped;
+        summary.xblk_airdropped = state.season_xblk_airdropped;
+        summary.xuni_airdropped = state.season_xuni_airdropped;
+        summary.native_airdropped = state.season_native_airdropped;
+        summary.ended_at = Clock::get()?.unix_timestamp;
+        summary.bump = ctx.bumps.season_summary;
+
+        state.current_season = state
+            .current_season
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        state.season_xnm_airdropped = 0;
+        state.season_xblk_airdropped = 0;
+        state.season_xuni_airdropped = 0;
+        state.season_native_airdropped = 0;
+
+        msg!(
+            "Closed season {}, now in season {}",
+            summary.season,
+            state.current_season
         );
+        Ok(())
+    }
 
-        msg!("Lock released by {}", lock.lock_holder);
-        lock.lock_holder = Pubkey::default();
-        lock.locked_at = 0;
-        lock.timeout_seconds = 0;
-        lock.run_id = 0;
+    /// Succeed only if the signer is the current authority (no state change).
+    ///
+    /// Lets tooling run a cheap pre-flight check before a destructive operation.
+    pub fn prove_authority(ctx: Context<ProveAuthority>) -> Result<()> {
+        msg!("Authority confirmed: {}", ctx.accounts.authority.key());
         Ok(())
     }
 
-}
+    /// Record the lamports spent on rent and fees for a run (for accounting).
+    /// Only allowed while the run is active, so finalized figures stay fixed.
+    pub fn record_run_fees(ctx: Context<UpdateRun>, lamports: u64) -> Result<()> {
+        let run = &mut ctx.accounts.airdrop_run;
+        run.check_active()?;
+        run.fees_spent = lamports;
 
-// ============================================================================
-// State Accounts
-// ============================================================================
+        emit!(RunFeesRecorded {
+            run_id: run.run_id,
+            fees_spent: lamports,
+        });
 
-#[derive(Accounts)]
-pub struct CreateRunV2<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        msg!(
+            "Recorded fees for run #{}: {} lamports",
+            run.run_id,
+            lamports
+        );
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+    /// Set the cluster mode (`CLUSTER_MODE_DEVNET` or `CLUSTER_MODE_MAINNET`).
+    ///
+    /// In mainnet mode, record creation rejects placeholder eth_addresses so
+    /// devnet fixtures can't be imported into a mainnet deployment. Switch back
+    /// to devnet mode to disable the check.
+    pub fn set_cluster_mode(ctx: Context<UpdateConfig>, cluster_mode: u8) -> Result<()> {
+        require!(
+            cluster_mode == CLUSTER_MODE_DEVNET || cluster_mode == CLUSTER_MODE_MAINNET,
+            ErrorCode::InvalidClusterMode
+        );
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + AirdropRunV2::INIT_SPACE,
-        seeds = [b"run_v2", (state.run_counter + 1).to_le_bytes().as_ref()],
-        bump
-    )]
-    pub airdrop_run: Account<'info, AirdropRunV2>,
+        let state = &mut ctx.accounts.state;
+        state.cluster_mode = cluster_mode;
 
-    pub system_program: Program<'info, System>,
-}
+        msg!("Cluster mode set to {}", cluster_mode);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct UpdateRunTotalsV2<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Create a run and assign its budget (plus optional label, schedule and
+    /// reference hash) in one instruction, so a run never exists without its
+    /// budget.
+    pub fn create_run_with_budget(
+        ctx: Context<CreateRunV2>,
+        dry_run: bool,
+        snapshot_block: u64,
+        budget: u64,
+        label: Option<[u8; 32]>,
+        scheduled_at: Option<i64>,
+        reference_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        require!(dry_run || budget > 0, ErrorCode::ZeroBudget);
 
-    #[account(
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+        let state = &mut ctx.accounts.state;
+        let run = &mut ctx.accounts.airdrop_run;
 
-    #[account(
-        mut,
-        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
-        bump = airdrop_run.bump
-    )]
-    pub airdrop_run: Account<'info, AirdropRunV2>,
-}
+        let now = Clock::get()?.unix_timestamp;
+        let run_id = state.start_run(dry_run, snapshot_block, now)?;
+        run.initialize(run_id, dry_run, ctx.bumps.airdrop_run, now);
+        run.snapshot_block = snapshot_block;
+        state.latest_run = run.key();
+        run.budget = budget;
+        run.label = label.unwrap_or_default();
+        run.scheduled_at = scheduled_at.unwrap_or_default();
+        run.reference_hash = reference_hash.unwrap_or_default();
 
-#[derive(Accounts)]
-#[instruction(eth_address: [u8; 42])]
-pub struct InitializeRecordV2<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        emit!(RunCreated {
+            run_id: run.run_id,
+            run_date: run.run_date,
+            dry_run,
+            reference_hash: run.reference_hash,
+        });
 
-    #[account(
-        mut,
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+        msg!(
+            "Created airdrop run v2 #{} with budget {}",
+            run.run_id,
+            budget
+        );
+        Ok(())
+    }
+
+    /// Create the record sol_wallet's associated token account for `mint` if
+    /// it does not exist yet, and mark the record as ATA-ready. Idempotent.
+    /// ata_ready is a single flag, so it only records that some mint's
+    /// account was ensured, not which.
+    pub fn ensure_ata(ctx: Context<EnsureAta>) -> Result<()> {
+        associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.authority.to_account_info(),
+                associated_token: ctx.accounts.associated_token.to_account_info(),
+                authority: ctx.accounts.wallet.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
+        let record = &mut ctx.accounts.airdrop_record;
+        record.ata_ready = true;
+
+        msg!(
+            "ATA ready for {} (mint {})",
+            ctx.accounts.wallet.key(),
+            ctx.accounts.mint.key()
+        );
+        Ok(())
+    }
+
+    /// Set the lifetime per-token distribution caps (0 = uncapped).
+    ///
+    /// Every credit is checked against these, so the caps act as a
+    /// program-wide supply guarantee per token.
+    pub fn set_token_caps(
+        ctx: Context<UpdateConfig>,
+        max_total_xnm: u64,
+        max_total_xblk: u64,
+        max_total_xuni: u64,
+        max_total_native: u64,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.max_total_xnm = max_total_xnm;
+        state.max_total_xblk = max_total_xblk;
+        state.max_total_xuni = max_total_xuni;
+        state.max_total_native = max_total_native;
+
+        msg!(
+            "Token caps set: xnm={} xblk={} xuni={} native={}",
+            max_total_xnm,
+            max_total_xblk,
+            max_total_xuni,
+            max_total_native
+        );
+        Ok(())
+    }
+
+    /// Return how much of each token can still be distributed under the caps
+    /// (read-only; u64::MAX for uncapped tokens)
+    pub fn remaining_budget(ctx: Context<GetConfig>) -> Result<RemainingBudget> {
+        let state = &ctx.accounts.state;
+        Ok(RemainingBudget {
+            xnm: remaining_under_cap(state.xnm_airdropped, state.max_total_xnm),
+            xblk: remaining_under_cap(state.xblk_airdropped, state.max_total_xblk),
+            xuni: remaining_under_cap(state.xuni_airdropped, state.max_total_xuni),
+            native: remaining_under_cap(state.native_airdropped, state.max_total_native),
+        })
+    }
+
+    /// Finish a record left empty by an interrupted initialize_and_update_v2.
+    ///
+    /// Only a record created in `airdrop_run` that has never been credited
+    /// (nonce 0, every amount zero) can be repaired, so a populated record
+    /// can never be overwritten. The credit goes through the same run checks
+    /// as update_record_and_run and is added to the run's totals; at least
+    /// one amount must be non-zero, so a repaired record can't be repaired
+    /// again. `expected_nonce` must equal the record's current nonce.
+    pub fn repair_record(
+        ctx: Context<UpdateRecordAndRun>,
+        xnm_amount: u64,
+        xblk_amount: u64,
+        xuni_amount: u64,
+        native_amount: u64,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        let record = &ctx.accounts.airdrop_record;
+        record.check_nonce(expected_nonce)?;
+        require!(
+            record.nonce == 0 && record.is_empty(),
+            ErrorCode::RecordAlreadyPopulated
+        );
+        require!(
+            record.created_in_run == ctx.accounts.airdrop_run.run_id,
+            ErrorCode::NotCreatedInRun
+        );
+        let amounts = TokenAmounts {
+            xnm: xnm_amount,
+            xblk: xblk_amount,
+            xuni: xuni_amount,
+            native: native_amount,
+        };
+        require!(
+            xnm_amount != 0 || xblk_amount != 0 || xuni_amount != 0 || native_amount != 0,
+            ErrorCode::ZeroAmountUpdate
+        );
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        ctx.accounts.airdrop_run.check_active()?;
+        require!(
+            ctx.accounts.airdrop_run.subrun_count == 0,
+            ErrorCode::RunHasSubRuns
+        );
+        ctx.accounts.airdrop_run.check_credit(&amounts)?;
+
+        credit_record(
+            &mut ctx.accounts.state,
+            &mut ctx.accounts.airdrop_record,
+            &amounts,
+            Some(&ctx.accounts.airdrop_run),
+            [0u8; 16],
+            Clock::get()?.unix_timestamp,
+        )?;
+        ctx.accounts.airdrop_run.add_credit(&amounts)?;
+
+        msg!(
+            "Repaired V2 airdrop record in run #{}: xnm={}, xblk={}, xuni={}, native={}",
+            ctx.accounts.airdrop_run.run_id,
+            xnm_amount,
+            xblk_amount,
+            xuni_amount,
+            native_amount
+        );
+        Ok(())
+    }
+
+    /// Fail with ReservedNotZeroed if the record's reserved space holds any
+    /// non-zero data (read-only audit before repurposing reserved bytes)
+    pub fn assert_reserved_clean(ctx: Context<ReadRecord>) -> Result<()> {
+        let record = &ctx.accounts.airdrop_record;
+        let reserved = [
+            record.xnm_claimed,
+            record.xblk_claimed,
+            record.xuni_claimed,
+            record.native_claimed,
+        ];
+        if record.version < 2 && reserved.iter().any(|word| *word != 0) {
+            msg!("Reserved not zeroed for {}: {:?}", record.key(), reserved);
+            return err!(ErrorCode::ReservedNotZeroed);
+        }
+        Ok(())
+    }
+
+    /// Enable or disable the per-run duplicate credit guard in update_record_v2
+    pub fn set_dedup_per_run(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.dedup_per_run = enabled;
+
+        msg!("Per-run dedup guard enabled: {}", enabled);
+        Ok(())
+    }
+
+    /// Return the sum of all token amounts on a record (read-only)
+    pub fn record_grand_total(ctx: Context<ReadRecord>) -> Result<u128> {
+        ctx.accounts.airdrop_record.grand_total()
+    }
+
+    /// Link a Solana wallet to a record; this wallet signs `acknowledge`
+    pub fn set_record_wallet(ctx: Context<SetRecordWallet>) -> Result<()> {
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        let wallet = &ctx.accounts.wallet;
+        ctx.accounts.state.check_wallet(
+            &wallet.key(),
+            Some(&wallet.to_account_info()),
+            ctx.accounts.allowed_wallet.as_deref(),
+        )?;
+
+        let record = &mut ctx.accounts.airdrop_record;
+        record.sol_wallet = ctx.accounts.wallet.key();
+        ctx.accounts.eth_registry.sol_wallet = record.sol_wallet;
+
+        msg!("Record wallet set to {}", record.sol_wallet);
+        Ok(())
+    }
+
+    /// Accept the campaign terms for a record (signed by its sol_wallet)
+    pub fn acknowledge(ctx: Context<RecipientUpdate>) -> Result<()> {
+        let record = &mut ctx.accounts.airdrop_record;
+        record.acknowledged = true;
+
+        msg!("Terms acknowledged by {}", ctx.accounts.wallet.key());
+        Ok(())
+    }
+
+    /// Require records to be acknowledged before they can be credited
+    pub fn set_require_ack(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.require_ack = enabled;
+
+        msg!("Require acknowledgement: {}", enabled);
+        Ok(())
+    }
+
+    /// Enable or disable the destination wallet allowlist. When enabled,
+    /// set_record_wallet and every record-creating instruction that links a
+    /// wallet only accept wallets with an AllowedWallet PDA.
+    pub fn set_wallet_allowlist(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.wallet_allowlist_enabled = enabled;
+
+        msg!("Wallet allowlist enabled: {}", enabled);
+        Ok(())
+    }
+
+    /// Add a wallet to the destination allowlist
+    pub fn add_allowed_wallet(ctx: Context<AddAllowedWallet>) -> Result<()> {
+        let allowed = &mut ctx.accounts.allowed_wallet;
+        allowed.wallet = ctx.accounts.wallet.key();
+        allowed.bump = ctx.bumps.allowed_wallet;
+
+        msg!("Allowed wallet {}", allowed.wallet);
+        Ok(())
+    }
+
+    /// Remove a wallet from the destination allowlist, returning rent to the authority
+    pub fn remove_allowed_wallet(ctx: Context<RemoveAllowedWallet>) -> Result<()> {
+        msg!(
+            "Removed allowed wallet {}",
+            ctx.accounts.allowed_wallet.wallet
+        );
+        Ok(())
+    }
+
+    /// Set the hash of the run's published announcement so anyone can verify
+    /// the published terms match the on-chain run. Only allowed while the run
+    /// is active, so the reference of a closed run stays fixed.
+    pub fn set_run_reference(ctx: Context<UpdateRun>, reference_hash: [u8; 32]) -> Result<()> {
+        let run = &mut ctx.accounts.airdrop_run;
+        run.check_active()?;
+        run.reference_hash = reference_hash;
+
+        msg!("Set reference hash for run #{}", run.run_id);
+        Ok(())
+    }
+
+    /// Require non-dry runs to carry a reference hash before their totals
+    /// can be recorded
+    pub fn set_require_reference(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.require_reference = enabled;
+
+        msg!("Require run reference: {}", enabled);
+        Ok(())
+    }
+
+    /// Set the treasury that receives swept record rent
+    pub fn set_treasury(ctx: Context<UpdateConfig>, treasury: Pubkey) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.treasury = treasury;
+
+        msg!("Treasury set to {}", treasury);
+        Ok(())
+    }
+
+    /// Mark a record as forfeited so it can be swept with close_and_sweep
+    pub fn forfeit_record(ctx: Context<UpdateRecordV2>) -> Result<()> {
+        let record = &mut ctx.accounts.airdrop_record;
+        record.forfeited = true;
+
+        msg!(
+            "Forfeited V2 airdrop record for eth: {:?}",
+            &record.eth_address[..6]
+        );
+        Ok(())
+    }
+
+    /// Close a forfeited record in one step: add its unclaimed amounts to the
+    /// treasury sweep tallies and send its rent, and its EthRegistry entry's,
+    /// to the treasury
+    pub fn close_and_sweep(ctx: Context<CloseAndSweep>) -> Result<()> {
+        let record = &ctx.accounts.airdrop_record;
+        record.check_unlocked(Clock::get()?.unix_timestamp)?;
+        emit_record_closed(record, CloseReason::Forfeit);
+        let state = &mut ctx.accounts.state;
+        // Legacy GlobalState accounts grown in place have a zeroed treasury
+        require_keys_neq!(state.treasury, Pubkey::default(), ErrorCode::TreasuryNotSet);
+
+        let swept_xnm = state
+            .swept_xnm
+            .checked_add(record.unclaimed(TokenType::Xnm))
+            .ok_or(ErrorCode::Overflow)?;
+        let swept_xblk = state
+            .swept_xblk
+            .checked_add(record.unclaimed(TokenType::Xblk))
+            .ok_or(ErrorCode::Overflow)?;
+        let swept_xuni = state
+            .swept_xuni
+            .checked_add(record.unclaimed(TokenType::Xuni))
+            .ok_or(ErrorCode::Overflow)?;
+        let swept_native = state
+            .swept_native
+            .checked_add(record.unclaimed(TokenType::Native))
+            .ok_or(ErrorCode::Overflow)?;
+
+        state.swept_xnm = swept_xnm;
+        state.swept_xblk = swept_xblk;
+        state.swept_xuni = swept_xuni;
+        state.swept_native = swept_native;
+        state.record_count = state.record_count.saturating_sub(1);
+
+        msg!(
+            "Swept V2 airdrop record for eth: {:?}",
+            &record.eth_address[..6]
+        );
+        Ok(())
+    }
+
+    /// Set the ceiling on any single record's lifetime total across all tokens
+    /// (u64::MAX disables the cap)
+    pub fn set_max_lifetime_per_record(
+        ctx: Context<UpdateConfig>,
+        max_lifetime_per_record: u64,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.max_lifetime_per_record = max_lifetime_per_record;
+
+        msg!("Max lifetime per record set to {}", max_lifetime_per_record);
+        Ok(())
+    }
+
+    /// Return a run's PDA, bump and status in one simulated call (read-only,
+    /// permissionless). `exists` is false when the PDA has not been created.
+    pub fn run_info(ctx: Context<GetRunInfo>, _run_id: u64) -> Result<RunInfo> {
+        let info = ctx.accounts.airdrop_run.to_account_info();
+        let mut view = RunInfo {
+            pda: info.key(),
+            bump: ctx.bumps.airdrop_run,
+            exists: false,
+            status: RunStatus::Active,
+            dry_run: false,
+        };
+
+        if info.owner == &crate::ID && !info.data_is_empty() {
+            let run = AirdropRunV2::try_deserialize(&mut &info.data.borrow()[..])?;
+            view.exists = true;
+            view.status = run.status;
+            view.dry_run = run.dry_run;
+        }
+        Ok(view)
+    }
+
+    /// Set the eligibility merkle root (all zeros clears it)
+    pub fn set_merkle_root(ctx: Context<UpdateConfig>, merkle_root: [u8; 32]) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.merkle_root = merkle_root;
+
+        msg!("Merkle root set to {:?}", merkle_root);
+        Ok(())
+    }
+
+    /// Set the allocation merkle root that gates initialize_and_update_v2
+    /// (all zeros clears it). Leaves are allocation_leaf, unlike the
+    /// eth_address-only leaves under merkle_root.
+    pub fn set_allocation_root(
+        ctx: Context<UpdateConfig>,
+        allocation_root: [u8; 32],
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.allocation_root = allocation_root;
+
+        msg!("Allocation root set to {:?}", allocation_root);
+        Ok(())
+    }
+
+    /// Verify many records against the eligibility merkle root (read-only).
+    ///
+    /// Records are passed via remaining_accounts in the same order as
+    /// `proofs`. Returns a bitmap with bit i set when entry i verified;
+    /// mismatches and invalid accounts leave their bit clear.
+    pub fn batch_verify_membership<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetConfig<'info>>,
+        proofs: Vec<Vec<[u8; 32]>>,
+    ) -> Result<u32> {
+        require!(
+            proofs.len() == ctx.remaining_accounts.len(),
+            ErrorCode::BatchLengthMismatch
+        );
+        require!(proofs.len() <= MAX_BATCH_SIZE, ErrorCode::BatchTooLarge);
+
+        let root = ctx.accounts.state.merkle_root;
+        require!(root != [0u8; 32], ErrorCode::MerkleRootNotSet);
+
+        let mut verified = 0u32;
+        for (index, (proof, info)) in proofs.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+            match load_record(info) {
+                Ok(record) => {
+                    if verify_merkle_proof(proof, root, eligibility_leaf(&record.eth_address)) {
+                        verified |= 1 << index;
+                    }
+                }
+                Err(err) => msg!("Skipping batch entry {}: {:?}", index, err),
+            }
+        }
+
+        msg!(
+            "Verified {} of {} records",
+            verified.count_ones(),
+            proofs.len()
+        );
+        Ok(verified)
+    }
+
+    /// Set the minimum number of seconds between credits to the same
+    /// existing record (0 disables)
+    pub fn set_min_update_interval(
+        ctx: Context<UpdateConfig>,
+        min_update_interval: i64,
+    ) -> Result<()> {
+        require!(min_update_interval >= 0, ErrorCode::InvalidInterval);
+
+        let state = &mut ctx.accounts.state;
+        state.min_update_interval = min_update_interval;
+
+        msg!("Min update interval set to {}s", min_update_interval);
+        Ok(())
+    }
+
+    /// Set the minimum number of seconds between non-dry runs (0 disables)
+    pub fn set_min_interval_between_runs(
+        ctx: Context<UpdateConfig>,
+        min_interval_between_runs: i64,
+    ) -> Result<()> {
+        require!(min_interval_between_runs >= 0, ErrorCode::InvalidInterval);
+
+        let state = &mut ctx.accounts.state;
+        state.min_interval_between_runs = min_interval_between_runs;
+
+        msg!(
+            "Min interval between runs set to {}s",
+            min_interval_between_runs
+        );
+        Ok(())
+    }
+
+    /// Undo the most recent credit to a record, subtracting its amounts from
+    /// the record and the global totals. Only one level of undo is kept.
+    /// `expected_nonce` must equal the record's current nonce.
+    pub fn rescind_last_update(ctx: Context<UpdateRecordV2>, expected_nonce: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let record = &mut ctx.accounts.airdrop_record;
+        record.check_nonce(expected_nonce)?;
+        record.check_unlocked(Clock::get()?.unix_timestamp)?;
+
+        let (xnm, xblk, xuni, native) = (
+            record.last_delta_xnm,
+            record.last_delta_xblk,
+            record.last_delta_xuni,
+            record.last_delta_native,
+        );
+        require!(
+            xnm != 0 || xblk != 0 || xuni != 0 || native != 0,
+            ErrorCode::NoUpdateToRescind
+        );
+
+        record.sub_amounts(xnm, xblk, xuni, native)?;
+        // Season totals only include the credit if its season is still open
+        let same_season = record.last_credited_season == state.current_season;
+        state.sub_totals(xnm, xblk, xuni, native, same_season)?;
+
+        record.last_delta_xnm = 0;
+        record.last_delta_xblk = 0;
+        record.last_delta_xuni = 0;
+        record.last_delta_native = 0;
+        record.advance_nonce()?;
+        let now = Clock::get()?.unix_timestamp;
+        record.last_updated = now;
+
+        emit!(UpdateRescinded {
+            eth_address: record.eth_address,
+            xnm_amount: xnm,
+            xblk_amount: xblk,
+            xuni_amount: xuni,
+            native_amount: native,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Subtract arbitrary amounts from a record to correct an over-credit.
+    /// Co-signers, when a threshold is set, are passed as signing
+    /// remaining_accounts. `expected_nonce` must equal the record's current
+    /// nonce. The correction supersedes the last credit, which can no longer
+    /// be rescinded.
+    pub fn correct_record(
+        ctx: Context<UpdateRecordV2>,
+        xnm_sub: u64,
+        xblk_sub: u64,
+        xuni_sub: u64,
+        native_sub: u64,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.state.check_signers(ctx.remaining_accounts)?;
+
+        let state = &mut ctx.accounts.state;
+        let record = &mut ctx.accounts.airdrop_record;
+        record.check_nonce(expected_nonce)?;
+        let now = Clock::get()?.unix_timestamp;
+        record.check_unlocked(now)?;
+
+        record.sub_amounts(xnm_sub, xblk_sub, xuni_sub, native_sub)?;
+        let same_season = record.last_credited_season == state.current_season;
+        state.sub_totals(xnm_sub, xblk_sub, xuni_sub, native_sub, same_season)?;
+        record.last_delta_xnm = 0;
+        record.last_delta_xblk = 0;
+        record.last_delta_xuni = 0;
+        record.last_delta_native = 0;
+        record.advance_nonce()?;
+        record.last_updated = now;
+
+        msg!(
+            "Corrected V2 airdrop record for eth: {:?} by -{} XNM, -{} XBLK, -{} XUNI, -{} native",
+            &record.eth_address[..6],
+            xnm_sub,
+            xblk_sub,
+            xuni_sub,
+            native_sub
+        );
+        Ok(())
+    }
+
+    /// Reject update_record_v2 and batch entries whose amounts are all zero
+    pub fn set_reject_zero_updates(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.reject_zero_updates = enabled;
+
+        msg!("Reject zero updates: {}", enabled);
+        Ok(())
+    }
+
+    /// Credit a record with `score * rate_bps / 10_000` of one token and
+    /// store the resulting amount alongside the score. `expected_nonce` must
+    /// equal the record's current nonce.
+    pub fn distribute_by_score(
+        ctx: Context<UpdateRecordV2>,
+        token: TokenType,
+        rate_bps: u64,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_record.check_nonce(expected_nonce)?;
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        let amount = apply_bps(ctx.accounts.airdrop_record.score, rate_bps)?;
+        let amounts = preferred_amounts(
+            &ctx.accounts.state,
+            &ctx.accounts.airdrop_record,
+            &TokenAmounts::single(token, amount),
+        );
+
+        credit_record(
+            &mut ctx.accounts.state,
+            &mut ctx.accounts.airdrop_record,
+            &amounts,
+            None,
+            [0u8; 16],
+            Clock::get()?.unix_timestamp,
+        )?;
+        ctx.accounts.airdrop_record.score_amount = amount;
+
+        msg!(
+            "Distributed {} of {:?} for score {}",
+            amount,
+            token,
+            ctx.accounts.airdrop_record.score
+        );
+        Ok(())
+    }
+
+    /// Cap the total number of runs (0 = unlimited). run_counter counts dry
+    /// runs too; with `exempt_dry` set, dry runs can still be created once
+    /// the cap is reached.
+    pub fn set_max_runs(ctx: Context<UpdateConfig>, max_runs: u64, exempt_dry: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.max_runs = max_runs;
+        state.max_runs_exempt_dry = exempt_dry;
+
+        msg!(
+            "Max runs set to {} (dry runs exempt: {})",
+            max_runs,
+            exempt_dry
+        );
+        Ok(())
+    }
+
+    /// Credit a record and add the same amounts to its run's totals in one
+    /// instruction, so record and run accounting can't drift apart. Runs
+    /// with sub-runs are rejected: their totals are rebuilt by rollup_subruns,
+    /// which would discard credits added here. `expected_nonce` must equal
+    /// the record's current nonce.
+    pub fn update_record_and_run(
+        ctx: Context<UpdateRecordAndRun>,
+        amounts: TokenAmounts,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_record.check_nonce(expected_nonce)?;
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        ctx.accounts.airdrop_run.check_active()?;
+        require!(
+            ctx.accounts.airdrop_run.subrun_count == 0,
+            ErrorCode::RunHasSubRuns
+        );
+        ctx.accounts.airdrop_run.check_credit(&amounts)?;
+        let state = &mut ctx.accounts.state;
+        let amounts = preferred_amounts(state, &ctx.accounts.airdrop_record, &amounts);
+        state.check_update_amounts(amounts.xnm, amounts.xblk, amounts.xuni, amounts.native)?;
+        credit_record(
+            state,
+            &mut ctx.accounts.airdrop_record,
+            &amounts,
+            Some(&ctx.accounts.airdrop_run),
+            [0u8; 16],
+            Clock::get()?.unix_timestamp,
+        )?;
+        ctx.accounts.airdrop_run.add_credit(&amounts)?;
+
+        msg!(
+            "Updated record and run #{}: xnm={}, xblk={}, xuni={}, native={}",
+            ctx.accounts.airdrop_run.run_id,
+            amounts.xnm,
+            amounts.xblk,
+            amounts.xuni,
+            amounts.native
+        );
+        Ok(())
+    }
+
+    /// Sum per-token totals over records passed via remaining_accounts, all of
+    /// which must belong to `eth_address` and be distinct (read-only)
+    pub fn sum_by_eth<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetConfig<'info>>,
+        eth_address: [u8; 42],
+    ) -> Result<EthTotals> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let mut totals = EthTotals {
+            records: 0,
+            xnm: 0,
+            xblk: 0,
+            xuni: 0,
+            native: 0,
+        };
+        for (index, info) in ctx.remaining_accounts.iter().enumerate() {
+            require!(
+                ctx.remaining_accounts[..index]
+                    .iter()
+                    .all(|other| other.key != info.key),
+                ErrorCode::DuplicateAccount
+            );
+            let record = load_record(info)?;
+            require!(
+                record.eth_address == eth_address,
+                ErrorCode::EthAddressMismatch
+            );
+
+            totals.records += 1;
+            totals.xnm = totals
+                .xnm
+                .checked_add(record.xnm_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+            totals.xblk = totals
+                .xblk
+                .checked_add(record.xblk_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+            totals.xuni = totals
+                .xuni
+                .checked_add(record.xuni_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+            totals.native = totals
+                .native
+                .checked_add(record.native_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        Ok(totals)
+    }
+
+    /// Require record wallets to be system-owned (or empty) accounts, so
+    /// tokens aren't sent to PDAs or program accounts
+    pub fn set_enforce_system_wallet(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.enforce_system_wallet = enabled;
+
+        msg!("Enforce system-owned wallets: {}", enabled);
+        Ok(())
+    }
+
+    /// Require new records to carry a signature from their ETH address,
+    /// except when the authority seeds them (opt-in)
+    pub fn set_require_eth_proof(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.require_eth_proof = enabled;
+
+        msg!("Require ETH ownership proof: {}", enabled);
+        Ok(())
+    }
+
+    /// Grow a record created with an older, shorter layout to the current
+    /// size. The authority pays the extra rent and new fields read as zero.
+    /// Idempotent; never shrinks an account.
+    pub fn grow_record(ctx: Context<GrowRecord>) -> Result<()> {
+        let info = ctx.accounts.airdrop_record.to_account_info();
+        verify_raw_record(&info)?;
+
+        grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            8 + AirdropRecordV2::INIT_SPACE,
+        )
+    }
+
+    /// Grow a GlobalState created with an older, shorter layout to the
+    /// current size, so it deserializes again. Idempotent; never shrinks.
+    pub fn grow_state(ctx: Context<GrowState>) -> Result<()> {
+        let info = ctx.accounts.state.to_account_info();
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() >= STATE_AUTHORITY_OFFSET + 32
+                    && data[..8] == *GlobalStateV2::DISCRIMINATOR,
+                ErrorCode::Unauthorized
+            );
+            let authority = &data[STATE_AUTHORITY_OFFSET..STATE_AUTHORITY_OFFSET + 32];
+            require!(
+                authority == ctx.accounts.authority.key().as_ref(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            8 + GlobalStateV2::INIT_SPACE,
+        )
+    }
+
+    /// Return whether the program is accepting writes, folding the pause and
+    /// retirement flags into one read-only, permissionless call
+    pub fn health_check(ctx: Context<GetConfig>) -> Result<HealthView> {
+        let state = &ctx.accounts.state;
+        Ok(HealthView {
+            paused: state.paused,
+            retired: state.retired,
+            version: state.version,
+        })
+    }
+
+    /// Elect to receive a single token (signed by the record's sol_wallet).
+    /// `None` restores the default of receiving every token.
+    pub fn set_preference(
+        ctx: Context<RecipientUpdate>,
+        preferred_token: Option<TokenType>,
+    ) -> Result<()> {
+        let record = &mut ctx.accounts.airdrop_record;
+        record.preferred_token = preferred_token;
+
+        msg!("Preferred token set to {:?}", preferred_token);
+        Ok(())
+    }
+
+    /// Honor recipients' preferred_token when crediting
+    pub fn set_respect_preference(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.respect_preference = enabled;
+
+        msg!("Respect recipient preference: {}", enabled);
+        Ok(())
+    }
+
+    /// Return the rent-exempt minimum for a record at the current layout size
+    /// (read-only)
+    pub fn rent_for_record(_ctx: Context<GetConfig>) -> Result<u64> {
+        Ok(Rent::get()?.minimum_balance(8 + AirdropRecordV2::INIT_SPACE))
+    }
+
+    /// Require non-dry runs to record the ETH snapshot block they used
+    pub fn set_require_snapshot_block(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.require_snapshot_block = enabled;
+
+        msg!("Require snapshot block: {}", enabled);
+        Ok(())
+    }
+
+    /// Bump last_updated on records passed via remaining_accounts without
+    /// changing balances (liveness heartbeat)
+    pub fn batch_touch_records<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchUpdateRecords<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        for info in ctx.remaining_accounts.iter() {
+            require!(info.is_writable, ErrorCode::InvalidRecordAccount);
+            let mut record = load_record(info)?;
+            record.last_updated = now;
+            record.exit(&crate::ID)?;
+        }
+
+        emit!(BatchTouched {
+            count: ctx.remaining_accounts.len() as u8,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Fail with DuplicateEthAddress if two distinct records hold the same
+    /// eth_address, compared case-insensitively since the PDA seeds are not.
+    /// Only records created before the EthRegistry can collide this way.
+    pub fn assert_eth_unique(ctx: Context<AssertEthUnique>) -> Result<()> {
+        let a = &ctx.accounts.record_a;
+        let b = &ctx.accounts.record_b;
+        if a.key() != b.key() && a.eth_address.eq_ignore_ascii_case(&b.eth_address) {
+            msg!("Duplicate eth_address in {} and {}", a.key(), b.key());
+            return err!(ErrorCode::DuplicateEthAddress);
+        }
+        Ok(())
+    }
+
+    /// Create the EthRegistry entry for a record created before the registry
+    /// existed. Fails with DuplicateEthAddress if another spelling of the
+    /// address was registered first.
+    pub fn register_eth_address(ctx: Context<RegisterEthAddress>) -> Result<()> {
+        let record = &ctx.accounts.airdrop_record;
+        create_eth_registry(
+            &ctx.accounts.eth_registry,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &record.eth_address,
+            record.key(),
+            record.sol_wallet,
+        )?;
+
+        msg!("Registered eth_address of record {}", record.key());
+        Ok(())
+    }
+
+    /// Set the token decimals used to scale whole-token amounts (at most
+    /// MAX_DECIMALS)
+    pub fn set_decimals(ctx: Context<UpdateConfig>, decimals: u8) -> Result<()> {
+        require!(decimals <= MAX_DECIMALS, ErrorCode::InvalidDecimals);
+
+        let state = &mut ctx.accounts.state;
+        state.decimals = decimals;
+
+        msg!("Decimals set to {}", decimals);
+        Ok(())
+    }
+
+    /// Like update_record_struct, but amounts are whole tokens scaled by
+    /// 10^decimals on-chain. Meant for manual corrections; automated callers
+    /// should keep using base units. Fails with DecimalsNotSet until
+    /// set_decimals has been called. `expected_nonce` must equal the
+    /// record's current nonce.
+    pub fn update_record_whole(
+        ctx: Context<UpdateRecordV2>,
+        whole_amounts: TokenAmounts,
+        allow_recredit: bool,
+        batch_id: [u8; 16],
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_record.check_nonce(expected_nonce)?;
+        // Legacy state accounts read 0 here; scaling by 10^0 would credit
+        // whole tokens as base units
+        require!(ctx.accounts.state.decimals != 0, ErrorCode::DecimalsNotSet);
+        let amounts = whole_amounts.to_base_units(ctx.accounts.state.decimals)?;
+        update_record_amounts(
+            &mut ctx.accounts.state,
+            &mut ctx.accounts.airdrop_record,
+            ctx.accounts.airdrop_run.as_deref(),
+            &ctx.accounts.blacklist,
+            &amounts,
+            allow_recredit,
+            batch_id,
+        )
+    }
+
+    /// Set the program-wide window in which records can be credited
+    /// (0 = unbounded on that side). Dry runs never credit records, so they
+    /// are unaffected.
+    pub fn set_distribution_window(
+        ctx: Context<UpdateConfig>,
+        distribution_start: i64,
+        distribution_end: i64,
+    ) -> Result<()> {
+        require!(
+            distribution_start == 0
+                || distribution_end == 0
+                || distribution_start <= distribution_end,
+            ErrorCode::InvalidDistributionWindow
+        );
+
+        let state = &mut ctx.accounts.state;
+        state.distribution_start = distribution_start;
+        state.distribution_end = distribution_end;
+
+        msg!(
+            "Distribution window set to [{}, {}]",
+            distribution_start,
+            distribution_end
+        );
+        Ok(())
+    }
+
+    /// Return the run counter and lifetime token totals (read-only,
+    /// permissionless). The Borsh-serialized `StateSummary` is written to
+    /// return_data; see the struct for the byte layout.
+    pub fn get_state_summary(ctx: Context<GetConfig>) -> Result<StateSummary> {
+        let state = &ctx.accounts.state;
+        Ok(StateSummary {
+            run_counter: state.run_counter,
+            xnm_airdropped: state.xnm_airdropped,
+            xblk_airdropped: state.xblk_airdropped,
+            xuni_airdropped: state.xuni_airdropped,
+            native_airdropped: state.native_airdropped,
+        })
+    }
+
+    /// Return a run's per-token totals as a typed view (read-only,
+    /// permissionless)
+    pub fn get_run_token_totals(ctx: Context<GetRun>, _run_id: u64) -> Result<RunTokenTotals> {
+        let run = &ctx.accounts.airdrop_run;
+        Ok(RunTokenTotals {
+            xnm_total: run.total_xnm_amount,
+            xblk_total: run.total_xblk_amount,
+            xuni_total: run.total_xuni_amount,
+            native_total: run.total_native_amount,
+            recipients: run.total_recipients,
+            status: run.status,
+        })
+    }
+
+    /// Permanently retire the program: no further credits, records or runs.
+    ///
+    /// Irreversible, so the caller must pass `RETIRE_CONFIRMATION` as an
+    /// explicit second confirmation alongside the authority signature. When a
+    /// co-signer set is configured, at least `max(2, threshold)` co-signers
+    /// must also sign via remaining_accounts.
+    pub fn retire_program(ctx: Context<UpdateConfig>, confirmation: [u8; 16]) -> Result<()> {
+        require!(
+            confirmation == RETIRE_CONFIRMATION,
+            ErrorCode::RetireNotConfirmed
+        );
+        let state = &ctx.accounts.state;
+        if state.threshold > 0 {
+            let required = state.threshold.max(2) as usize;
+            require!(
+                state.signer_approvals(ctx.remaining_accounts) >= required,
+                ErrorCode::InsufficientSignersForRetire
+            );
+        }
+
+        let state = &mut ctx.accounts.state;
+        state.retired = true;
+
+        msg!("Program retired by {}", ctx.accounts.authority.key());
+        Ok(())
+    }
+
+    /// Create a fully configured record in one instruction: wallet, initial
+    /// amounts, tag and claim deadline are set atomically with creation. The
+    /// creating run, if any, is taken from the airdrop_run account.
+    pub fn initialize_full(
+        ctx: Context<InitializeRecordV2>,
+        eth_address: [u8; 42],
+        sol_wallet: Pubkey,
+        amounts: TokenAmounts,
+        tag: [u8; 32],
+        claim_deadline: i64,
+    ) -> Result<()> {
+        ctx.accounts.state.check_new_record(&eth_address)?;
+        ctx.accounts
+            .state
+            .check_eth_proof(&ctx.accounts.authority.key(), &eth_address, None)?;
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        ctx.accounts.state.check_wallet(
+            &sol_wallet,
+            ctx.accounts.wallet.as_deref(),
+            ctx.accounts.allowed_wallet.as_deref(),
+        )?;
+        create_eth_registry(
+            &ctx.accounts.eth_registry,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &eth_address,
+            ctx.accounts.airdrop_record.key(),
+            sol_wallet,
+        )?;
+
+        if let Some(run) = &ctx.accounts.airdrop_run {
+            run.check_credit(&amounts)?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(claim_deadline > now, ErrorCode::InvalidClaimDeadline);
+
+        let record = &mut ctx.accounts.airdrop_record;
+        record.initialize(eth_address, ctx.bumps.airdrop_record, now);
+        record.created_in_run = run_id_or_zero(ctx.accounts.airdrop_run.as_deref());
+        record.sol_wallet = sol_wallet;
+        record.tag = tag;
+        record.claim_deadline = claim_deadline;
+
+        let state = &mut ctx.accounts.state;
+        credit_record(
+            state,
+            record,
+            &amounts,
+            ctx.accounts.airdrop_run.as_deref(),
+            [0u8; 16],
+            now,
+        )?;
+        state.increment_record_count()?;
+
+        msg!(
+            "Initialized full V2 airdrop record for eth: {:?}",
+            &eth_address[..6]
+        );
+        Ok(())
+    }
+
+    /// Let recipients close their own records once claimed
+    pub fn set_allow_self_close(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.allow_self_close = enabled;
+
+        msg!("Allow self close: {}", enabled);
+        Ok(())
+    }
+
+    /// Close a claimed record and its EthRegistry entry, signed by its
+    /// sol_wallet, returning the rent to that wallet. Only available while
+    /// allow_self_close is on.
+    pub fn self_close_record(ctx: Context<SelfCloseRecord>) -> Result<()> {
+        let record = &ctx.accounts.airdrop_record;
+        record.check_unlocked(Clock::get()?.unix_timestamp)?;
+        emit_record_closed(record, CloseReason::Recipient);
+
+        let state = &mut ctx.accounts.state;
+        require!(state.allow_self_close, ErrorCode::SelfCloseDisabled);
+        state.record_count = state.record_count.saturating_sub(1);
+
+        msg!(
+            "Self-closed V2 airdrop record for eth: {:?}",
+            &ctx.accounts.airdrop_record.eth_address[..6]
+        );
+        Ok(())
+    }
+
+    /// Open a totals reconciliation: create the accumulator that
+    /// reconcile_totals sums records into across paginated calls
+    pub fn begin_reconcile(ctx: Context<BeginReconcile>) -> Result<()> {
+        let acc = &mut ctx.accounts.accumulator;
+        acc.cursor = Pubkey::default();
+        acc.records = 0;
+        acc.xnm_airdropped = 0;
+        acc.xblk_airdropped = 0;
+        acc.xuni_airdropped = 0;
+        acc.native_airdropped = 0;
+        acc.bump = ctx.bumps.accumulator;
+
+        msg!("Totals reconciliation started");
+        Ok(())
+    }
+
+    /// Add a page of records (remaining_accounts) to the accumulator.
+    ///
+    /// Records must be passed in strictly ascending key order, continuing past
+    /// the cursor left by the previous page, so no record is counted twice.
+    pub fn reconcile_totals<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReconcileTotals<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let acc = &mut ctx.accounts.accumulator;
+        for info in ctx.remaining_accounts.iter() {
+            require!(
+                acc.records == 0 || info.key() > acc.cursor,
+                ErrorCode::ReconcileOutOfOrder
+            );
+            let record = load_record(info)?;
+            acc.xnm_airdropped = acc
+                .xnm_airdropped
+                .checked_add(record.xnm_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+            acc.xblk_airdropped = acc
+                .xblk_airdropped
+                .checked_add(record.xblk_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+            acc.xuni_airdropped = acc
+                .xuni_airdropped
+                .checked_add(record.xuni_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+            acc.native_airdropped = acc
+                .native_airdropped
+                .checked_add(record.native_airdropped)
+                .ok_or(ErrorCode::Overflow)?;
+            acc.records = acc.records.checked_add(1).ok_or(ErrorCode::Overflow)?;
+            acc.cursor = info.key();
+        }
+
+        msg!("Reconciled {} records so far", acc.records);
+        Ok(())
+    }
+
+    /// Overwrite GlobalState's lifetime totals and record_count with the
+    /// accumulated sums and close the accumulator. Season totals are left
+    /// untouched.
+    ///
+    /// The scan must cover at least record_count records, so a partial scan
+    /// of a correctly counted program is never committed; use
+    /// cancel_reconcile to discard one. A counter that is lower than the
+    /// scan, such as the zero-filled one left by grow_state, is raised to the
+    /// scanned count. Lower a counter left too high by bypassed closes with
+    /// reconcile_record_count first.
+    pub fn finish_reconcile(ctx: Context<FinishReconcile>) -> Result<()> {
+        let acc = &ctx.accounts.accumulator;
+        let state = &mut ctx.accounts.state;
+        require!(
+            acc.records > 0 && acc.records >= state.record_count,
+            ErrorCode::ReconcileIncomplete
+        );
+
+        emit!(TotalsReconciled {
+            records: acc.records,
+            old_xnm: state.xnm_airdropped,
+            old_xblk: state.xblk_airdropped,
+            old_xuni: state.xuni_airdropped,
+            old_native: state.native_airdropped,
+            new_xnm: acc.xnm_airdropped,
+            new_xblk: acc.xblk_airdropped,
+            new_xuni: acc.xuni_airdropped,
+            new_native: acc.native_airdropped,
+            old_record_count: state.record_count,
+        });
+
+        state.xnm_airdropped = acc.xnm_airdropped;
+        state.xblk_airdropped = acc.xblk_airdropped;
+        state.xuni_airdropped = acc.xuni_airdropped;
+        state.native_airdropped = acc.native_airdropped;
+        state.record_count = acc.records;
+
+        msg!("Reconciled totals from {} records", acc.records);
+        Ok(())
+    }
+
+    /// Close the accumulator without touching GlobalState, discarding a
+    /// partial or aborted scan so a new one can begin
+    pub fn cancel_reconcile(ctx: Context<FinishReconcile>) -> Result<()> {
+        msg!(
+            "Totals reconciliation cancelled after {} records",
+            ctx.accounts.accumulator.records
+        );
+        Ok(())
+    }
+
+    /// Reject run dates earlier than the previous run's
+    pub fn set_enforce_monotonic_run_dates(
+        ctx: Context<UpdateConfig>,
+        enabled: bool,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.enforce_monotonic_run_dates = enabled;
+
+        msg!("Enforce monotonic run dates: {}", enabled);
+        Ok(())
+    }
+
+    /// Correct a run's run_date. The date can never be in the future.
+    ///
+    /// While monotonic dates are enforced the run must also stay between the
+    /// previous run (passed as previous_run) and, unless it is the latest
+    /// run, the next one (passed as next_run).
+    pub fn set_run_date(ctx: Context<SetRunDate>, run_date: i64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let run = &mut ctx.accounts.airdrop_run;
+        require!(
+            run_date <= Clock::get()?.unix_timestamp,
+            ErrorCode::RunDateInFuture
+        );
+
+        if state.enforce_monotonic_run_dates {
+            if let Some(previous) = &ctx.accounts.previous_run {
+                require!(
+                    run_date >= previous.run_date,
+                    ErrorCode::NonMonotonicRunDate
+                );
+            } else {
+                require!(run.run_id == 1, ErrorCode::NonMonotonicRunDate);
+            }
+            if run.run_id != state.run_counter {
+                let next = ctx
+                    .accounts
+                    .next_run
+                    .as_ref()
+                    .ok_or(ErrorCode::NonMonotonicRunDate)?;
+                require!(run_date <= next.run_date, ErrorCode::NonMonotonicRunDate);
+            }
+        }
+
+        run.run_date = run_date;
+        if run.run_id == state.run_counter {
+            state.latest_run_date = run_date;
+        }
+
+        msg!("Run {} date set to {}", run.run_id, run_date);
+        Ok(())
+    }
+
+    /// Emit a ProgramCheckpoint event with the current program-wide counters
+    /// so indexers starting mid-history can seed their state (read-only)
+    pub fn checkpoint(ctx: Context<GetConfig>) -> Result<()> {
+        let state = &ctx.accounts.state;
+        emit!(ProgramCheckpoint {
+            version: state.version,
+            run_counter: state.run_counter,
+            record_count: state.record_count,
+            xnm_airdropped: state.xnm_airdropped,
+            xblk_airdropped: state.xblk_airdropped,
+            xuni_airdropped: state.xuni_airdropped,
+            native_airdropped: state.native_airdropped,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Hold a record until `locked_until`: credits, rescinds and closes are
+    /// rejected before then. 0 releases the lock.
+    pub fn set_lock(ctx: Context<UpdateRecordV2>, locked_until: i64) -> Result<()> {
+        let record = &mut ctx.accounts.airdrop_record;
+        record.locked_until = locked_until;
+
+        emit!(RecordLocked {
+            record: record.key(),
+            locked_until,
+        });
+
+        msg!("Record locked until {}", locked_until);
+        Ok(())
+    }
+
+    /// Migrate records passed via remaining_accounts to the current
+    /// RECORD_VERSION, growing legacy layouts first (authority pays the
+    /// extra rent). Records already at the current version are skipped.
+    pub fn batch_stamp_version<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchStampVersion<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let mut stamped: u8 = 0;
+        for info in ctx.remaining_accounts.iter() {
+            require!(info.is_writable, ErrorCode::InvalidRecordAccount);
+            verify_raw_record(info)?;
+            grow_account(
+                info,
+                &ctx.accounts.authority,
+                &ctx.accounts.system_program,
+                8 + AirdropRecordV2::INIT_SPACE,
+            )?;
+
+            let mut record = load_record(info)?;
+            if !record.migrate() {
+                continue;
+            }
+            record.exit(&crate::ID)?;
+            stamped += 1;
+        }
+
+        emit!(RecordsStamped {
+            count: stamped,
+            version: RECORD_VERSION,
+        });
+
+        msg!(
+            "Stamped {} records with version {}",
+            stamped,
+            RECORD_VERSION
+        );
+        Ok(())
+    }
+
+    /// Migrate a single record to the current RECORD_VERSION, growing a legacy
+    /// layout first. Calling it on a migrated record is a no-op.
+    pub fn migrate_record(ctx: Context<GrowRecord>) -> Result<()> {
+        let info = ctx.accounts.airdrop_record.to_account_info();
+        verify_raw_record(&info)?;
+        grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            8 + AirdropRecordV2::INIT_SPACE,
+        )?;
+
+        let mut record = AirdropRecordV2::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let from = record.version;
+        if !record.migrate() {
+            msg!("Record already at version {}", from);
+            return Ok(());
+        }
+        record.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        msg!(
+            "Migrated record from version {} to {}",
+            from,
+            RECORD_VERSION
+        );
+        Ok(())
+    }
+
+    /// Create the next labeled SubRun partition of a run for finer-grained
+    /// reporting. Sub-runs are indexed from 0 in creation order.
+    pub fn create_subrun(ctx: Context<CreateSubRun>, label: [u8; 32]) -> Result<()> {
+        let run = &mut ctx.accounts.airdrop_run;
+        require!(
+            (run.subrun_count as usize) < MAX_SUBRUNS,
+            ErrorCode::TooManySubRuns
+        );
+
+        let subrun = &mut ctx.accounts.subrun;
+        subrun.run_id = run.run_id;
+        subrun.subrun_index = run.subrun_count;
+        subrun.label = label;
+        subrun.total_recipients = 0;
+        subrun.total_xnm_amount = 0;
+        subrun.total_xblk_amount = 0;
+        subrun.total_xuni_amount = 0;
+        subrun.total_native_amount = 0;
+        subrun.bump = ctx.bumps.subrun;
+
+        run.subrun_count += 1;
+
+        msg!(
+            "Created sub-run {} of run #{}",
+            subrun.subrun_index,
+            run.run_id
+        );
+        Ok(())
+    }
+
+    /// Credit a record and attribute the amounts to one of its run's sub-runs.
+    /// The parent run's totals are rebuilt from its sub-runs by
+    /// rollup_subruns. The parent run must be active, and a dry-run parent
+    /// only accepts zero amounts. `expected_nonce` must equal the record's
+    /// current nonce.
+    pub fn update_record_and_subrun(
+        ctx: Context<UpdateRecordAndSubRun>,
+        amounts: TokenAmounts,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_record.check_nonce(expected_nonce)?;
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        ctx.accounts.airdrop_run.check_active()?;
+        ctx.accounts.airdrop_run.check_credit(&amounts)?;
+        let state = &mut ctx.accounts.state;
+        let amounts = preferred_amounts(state, &ctx.accounts.airdrop_record, &amounts);
+        state.check_update_amounts(amounts.xnm, amounts.xblk, amounts.xuni, amounts.native)?;
+        credit_record(
+            state,
+            &mut ctx.accounts.airdrop_record,
+            &amounts,
+            Some(&ctx.accounts.airdrop_run),
+            [0u8; 16],
+            Clock::get()?.unix_timestamp,
+        )?;
+        ctx.accounts.subrun.add_credit(&amounts)?;
+
+        msg!(
+            "Updated record in sub-run {} of run #{}",
+            ctx.accounts.subrun.subrun_index,
+            ctx.accounts.subrun.run_id
+        );
+        Ok(())
+    }
+
+    /// Overwrite a run's totals with the sum of its sub-runs, passed via
+    /// remaining_accounts in index order (every sub-run must be present)
+    pub fn rollup_subruns<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateRun<'info>>,
+    ) -> Result<()> {
+        let run = &mut ctx.accounts.airdrop_run;
+        run.check_active()?;
+        run.rollup(ctx.remaining_accounts)?;
+
+        msg!(
+            "Rolled up {} sub-runs into run #{}",
+            run.subrun_count,
+            run.run_id
+        );
+        Ok(())
+    }
+
+    /// Return a bitmap with bit i set when record i in remaining_accounts has a
+    /// grand total below `threshold` (read-only). Entries that are not valid
+    /// records are left unset.
+    pub fn find_dust<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetConfig<'info>>,
+        threshold: u64,
+    ) -> Result<u32> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let mut dust = 0u32;
+        for (index, info) in ctx.remaining_accounts.iter().enumerate() {
+            match load_record(info) {
+                Ok(record) => {
+                    if record.grand_total()? < threshold as u128 {
+                        dust |= 1 << index;
+                    }
+                }
+                Err(err) => msg!("Skipping batch entry {}: {:?}", index, err),
+            }
+        }
+
+        msg!(
+            "Found {} dust records of {}",
+            dust.count_ones(),
+            ctx.remaining_accounts.len()
+        );
+        Ok(dust)
+    }
+
+    /// Close records passed via remaining_accounts, each paired with its
+    /// EthRegistry entry, returning their rent to the authority. Every record
+    /// must have a grand total below `threshold` (see find_dust) and must not
+    /// be time-locked.
+    pub fn batch_close_records<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchCloseRecords<'info>>,
+        threshold: u64,
+    ) -> Result<()> {
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(pairs.remainder().is_empty(), ErrorCode::BatchLengthMismatch);
+        let count = pairs.len();
+        require!(count <= MAX_BATCH_SIZE, ErrorCode::BatchTooLarge);
+
+        let now = Clock::get()?.unix_timestamp;
+        let state = &mut ctx.accounts.state;
+        for pair in pairs {
+            let (info, registry_info) = (&pair[0], &pair[1]);
+            require!(info.is_writable, ErrorCode::InvalidRecordAccount);
+            require!(registry_info.is_writable, ErrorCode::InvalidRegistryAccount);
+            let record = load_record(info)?;
+            let registry: Account<EthRegistry> = Account::try_from(registry_info)?;
+            require_keys_eq!(
+                registry.record,
+                info.key(),
+                ErrorCode::InvalidRegistryAccount
+            );
+            record.check_unlocked(now)?;
+            require!(
+                record.grand_total()? < threshold as u128,
+                ErrorCode::NotDust
+            );
+            emit_record_closed(&record, CloseReason::Cleanup);
+            record.close(ctx.accounts.authority.to_account_info())?;
+            registry.close(ctx.accounts.authority.to_account_info())?;
+            state.record_count = state.record_count.saturating_sub(1);
+        }
+
+        msg!("Closed {} dust records", count);
+        Ok(())
+    }
+
+    /// Configure how credit_lump_sum splits a total across the four tokens,
+    /// in basis points summing to 10_000
+    pub fn set_split_bps(
+        ctx: Context<UpdateConfig>,
+        xnm_bps: u16,
+        xblk_bps: u16,
+        xuni_bps: u16,
+        native_bps: u16,
+    ) -> Result<()> {
+        require!(
+            split_bps_valid(xnm_bps, xblk_bps, xuni_bps, native_bps),
+            ErrorCode::RatioMisconfigured
+        );
+
+        let state = &mut ctx.accounts.state;
+        state.split_bps_xnm = xnm_bps;
+        state.split_bps_xblk = xblk_bps;
+        state.split_bps_xuni = xuni_bps;
+        state.split_bps_native = native_bps;
+
+        msg!(
+            "Split bps set: xnm={}, xblk={}, xuni={}, native={}",
+            xnm_bps,
+            xblk_bps,
+            xuni_bps,
+            native_bps
+        );
+        Ok(())
+    }
+
+    /// Credit a record with a single lump sum split across the four tokens by
+    /// the configured basis points (remainder to XNM). `expected_nonce` must
+    /// equal the record's current nonce.
+    pub fn credit_lump_sum(
+        ctx: Context<UpdateRecordV2>,
+        total: u64,
+        allow_recredit: bool,
+        batch_id: [u8; 16],
+        expected_nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_record.check_nonce(expected_nonce)?;
+        let amounts = ctx.accounts.state.split_lump_sum(total)?;
+        update_record_amounts(
+            &mut ctx.accounts.state,
+            &mut ctx.accounts.airdrop_record,
+            ctx.accounts.airdrop_run.as_deref(),
+            &ctx.accounts.blacklist,
+            &amounts,
+            allow_recredit,
+            batch_id,
+        )
+    }
+
+    /// Return who can administer the program and every boolean toggle in one
+    /// read-only, permissionless call (complements get_config).
+    ///
+    /// Every Operator PDA must be passed via remaining_accounts (found with
+    /// getProgramAccounts), so the returned list is complete.
+    pub fn get_admin_state<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetConfig<'info>>,
+    ) -> Result<AdminStateView> {
+        let state = &ctx.accounts.state;
+        require!(
+            ctx.remaining_accounts.len() == state.operator_count as usize,
+            ErrorCode::OperatorSetMismatch
+        );
+
+        let mut operators = vec![state.authority];
+        for (index, info) in ctx.remaining_accounts.iter().enumerate() {
+            require!(
+                ctx.remaining_accounts[..index]
+                    .iter()
+                    .all(|other| other.key != info.key),
+                ErrorCode::DuplicateAccount
+            );
+            let operator: Account<Operator> = Account::try_from(info)?;
+            let expected = Pubkey::create_program_address(
+                &[b"operator", operator.operator.as_ref(), &[operator.bump]],
+                &crate::ID,
+            )
+            .map_err(|_| error!(ErrorCode::OperatorSetMismatch))?;
+            require_keys_eq!(expected, info.key(), ErrorCode::OperatorSetMismatch);
+            operators.push(operator.operator);
+        }
+
+        Ok(AdminStateView {
+            authority: state.authority,
+            treasury: state.treasury,
+            operator_count: operators.len() as u8,
+            operators,
+            paused: state.paused,
+            retired: state.retired,
+            dedup_per_run: state.dedup_per_run,
+            require_ack: state.require_ack,
+            wallet_allowlist_enabled: state.wallet_allowlist_enabled,
+            require_reference: state.require_reference,
+            reject_zero_updates: state.reject_zero_updates,
+            max_runs_exempt_dry: state.max_runs_exempt_dry,
+            enforce_system_wallet: state.enforce_system_wallet,
+            respect_preference: state.respect_preference,
+            require_snapshot_block: state.require_snapshot_block,
+            allow_self_close: state.allow_self_close,
+            enforce_monotonic_run_dates: state.enforce_monotonic_run_dates,
+            require_eth_proof: state.require_eth_proof,
+        })
+    }
+
+    /// Dry-run a batch_update_records submission without writing anything.
+    ///
+    /// Takes the same record/blacklist pairs (records writable, as for the
+    /// real batch) and updates, runs every check
+    /// against a copy of GlobalState (so caps accumulate across entries as
+    /// they would for real) and returns a bitmap with bit i set when entry i
+    /// would succeed.
+    pub fn simulate_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetConfig<'info>>,
+        updates: Vec<RecordUpdate>,
+    ) -> Result<u32> {
+        require!(
+            updates.len() * 2 == ctx.remaining_accounts.len(),
+            ErrorCode::BatchLengthMismatch
+        );
+        require!(updates.len() <= MAX_BATCH_SIZE, ErrorCode::BatchTooLarge);
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut state = (*ctx.accounts.state).clone();
+        let mut passed = 0u32;
+        for (index, (update, pair)) in updates
+            .iter()
+            .zip(ctx.remaining_accounts.chunks(2))
+            .enumerate()
+        {
+            match credit_from_update(&mut state, &pair[0], &pair[1], update, [0u8; 16], now) {
+                Ok(_) => passed |= 1 << index,
+                Err(err) => msg!("Batch entry {} would fail: {:?}", index, err),
+            }
+        }
+
+        msg!(
+            "Simulated batch: {} of {} entries pass",
+            passed.count_ones(),
+            updates.len()
+        );
+        Ok(passed)
+    }
+
+    /// Mark an active run as completed. Its totals can no longer be updated.
+    /// A run with sub-runs is rolled up first, so they must all be passed via
+    /// remaining_accounts in index order.
+    pub fn finalize_run<'info>(ctx: Context<'_, '_, 'info, 'info, UpdateRun<'info>>) -> Result<()> {
+        let run = &mut ctx.accounts.airdrop_run;
+        run.check_active()?;
+        if run.subrun_count > 0 {
+            run.rollup(ctx.remaining_accounts)?;
+        }
+        run.finalize();
+        Ok(())
+    }
+
+    /// Mark an active run as aborted. Its totals can no longer be updated.
+    pub fn abort_run(ctx: Context<UpdateRun>) -> Result<()> {
+        let run = &mut ctx.accounts.airdrop_run;
+        run.check_active()?;
+        run.status = RunStatus::Aborted;
+
+        msg!("Aborted run #{}", run.run_id);
+        Ok(())
+    }
+
+    /// Propose a new authority (current authority only). Takes effect once the
+    /// proposed key signs accept_authority; proposing again replaces it, and
+    /// proposing the default key cancels the handover.
+    pub fn propose_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.pending_authority = new_authority;
+
+        msg!("Authority {} proposed {}", state.authority, new_authority);
+        Ok(())
+    }
+
+    /// Complete a handover started by propose_authority (pending key signs)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        msg!(
+            "Authority updated from {} to {}",
+            state.authority,
+            state.pending_authority
+        );
+        state.authority = state.pending_authority;
+        state.pending_authority = Pubkey::default();
+        Ok(())
+    }
+
+    /// Pause or resume record creation and credits during an incident.
+    /// Runs can still be created and records closed while paused.
+    pub fn set_pause(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.paused = paused;
+
+        msg!("Paused: {}", paused);
+        Ok(())
+    }
+
+    /// Configure the co-signer set and how many of them must sign
+    /// update_run_totals_v2 and correct_record. A zero threshold leaves those
+    /// instructions to the authority alone. Once a threshold is set, replacing
+    /// the set needs the current co-signers in remaining_accounts too.
+    pub fn set_signers(
+        ctx: Context<UpdateConfig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            signers.len() <= MAX_SIGNERS && threshold as usize <= signers.len(),
+            ErrorCode::InvalidSignerSet
+        );
+        for (index, signer) in signers.iter().enumerate() {
+            require!(
+                *signer != Pubkey::default() && !signers[..index].contains(signer),
+                ErrorCode::InvalidSignerSet
+            );
+        }
+        ctx.accounts.state.check_signers(ctx.remaining_accounts)?;
+
+        let state = &mut ctx.accounts.state;
+        state.signers = [Pubkey::default(); MAX_SIGNERS];
+        state.signers[..signers.len()].copy_from_slice(&signers);
+        state.threshold = threshold;
+
+        msg!("Signers set: {} of {}", threshold, signers.len());
+        Ok(())
+    }
+
+    /// Set the mint that claim pays out for `token`; the default key disables
+    /// claims for it
+    pub fn set_token_mint(
+        ctx: Context<UpdateConfig>,
+        token: TokenType,
+        mint: Pubkey,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.token_mints[token as usize] = mint;
+
+        msg!("Mint for {:?} set to {}", token, mint);
+        Ok(())
+    }
+
+    /// Grant the record's wallet `amount` of `token` to collect with claim.
+    /// Claims only ever pay out granted entitlement, never amounts already
+    /// credited through update_record_v2.
+    pub fn grant_claim(
+        ctx: Context<UpdateRecordV2>,
+        token: TokenType,
+        amount: u64,
+        expected_nonce: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmountUpdate);
+        require!(!ctx.accounts.state.paused, ErrorCode::ProgramPaused);
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        let record = &mut ctx.accounts.airdrop_record;
+        require!(!record.forfeited, ErrorCode::RecordForfeited);
+        record.check_nonce(expected_nonce)?;
+        record.add_claimable(token, amount)?;
+        record.advance_nonce()?;
+        record.last_updated = Clock::get()?.unix_timestamp;
+
+        msg!("Granted {} {:?} to {}", amount, token, record.key());
+        Ok(())
+    }
+
+    /// Transfer the recipient's granted balance of `token` from the
+    /// state-owned vault to their associated token account, then add it to
+    /// the record's airdropped amount. The entitlement is used up, so a
+    /// second claim has nothing left to pay.
+    pub fn claim(ctx: Context<Claim>, token: TokenType) -> Result<()> {
+        require!(!ctx.accounts.state.paused, ErrorCode::ProgramPaused);
+        check_not_blacklisted(&ctx.accounts.blacklist)?;
+        let record = &mut ctx.accounts.airdrop_record;
+        require!(!record.forfeited, ErrorCode::RecordForfeited);
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            record.claim_deadline == 0 || now <= record.claim_deadline,
+            ErrorCode::ClaimDeadlinePassed
+        );
+
+        let amount = record.unclaimed(token);
+        require!(amount > 0, ErrorCode::NothingToClaim);
+
+        let bump = [ctx.accounts.state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"state_v2", &bump]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        record.settle_claim(token, amount)?;
+        record.last_updated = now;
+        let paid = TokenAmounts::single(token, amount);
+        ctx.accounts
+            .state
+            .add_totals(paid.xnm, paid.xblk, paid.xuni, paid.native)?;
+
+        emit!(TokensClaimed {
+            record: record.key(),
+            wallet: ctx.accounts.wallet.key(),
+            token,
+            amount,
+            fully_claimed: record.claimed,
+        });
+
+        msg!(
+            "Claimed {} {:?} for {}",
+            amount,
+            token,
+            ctx.accounts.wallet.key()
+        );
+        Ok(())
+    }
+
+    /// Delegate record writes to `operator_key`. Operators may only call
+    /// update_record_v2 and initialize_and_update_v2; everything else,
+    /// including configuration and the authority, stays with the authority.
+    pub fn add_operator(ctx: Context<AddOperator>, operator_key: Pubkey) -> Result<()> {
+        let operator = &mut ctx.accounts.operator;
+        operator.operator = operator_key;
+        operator.added_at = Clock::get()?.unix_timestamp;
+        operator.bump = ctx.bumps.operator;
+
+        let state = &mut ctx.accounts.state;
+        state.operator_count = state
+            .operator_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!("Added operator {}", operator_key);
+        Ok(())
+    }
+
+    /// Revoke an operator by closing its Operator account. Later writes
+    /// signed by it fail with Unauthorized.
+    pub fn remove_operator(ctx: Context<RemoveOperator>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.operator_count = state.operator_count.saturating_sub(1);
+
+        msg!("Removed operator {}", ctx.accounts.operator.operator);
+        Ok(())
+    }
+
+    /// Block `wallet` from being linked to records or credited
+    pub fn blacklist_wallet(ctx: Context<BlacklistWallet>, wallet: Pubkey) -> Result<()> {
+        let blacklist = &mut ctx.accounts.blacklist;
+        blacklist.wallet = wallet;
+        blacklist.blacklisted_at = Clock::get()?.unix_timestamp;
+        blacklist.bump = ctx.bumps.blacklist;
+
+        msg!("Blacklisted wallet {}", wallet);
+        Ok(())
+    }
+
+    /// Lift a blacklist entry, returning its rent to the authority
+    pub fn unblacklist_wallet(ctx: Context<UnblacklistWallet>) -> Result<()> {
+        msg!("Unblacklisted wallet {}", ctx.accounts.blacklist.wallet);
+        Ok(())
+    }
+
+    /// Freeze or unfreeze a record. Frozen records reject credits but can
+    /// still be closed.
+    pub fn set_record_freeze(ctx: Context<FreezeRecord>, frozen: bool) -> Result<()> {
+        let record = &mut ctx.accounts.airdrop_record;
+        record.frozen = frozen;
+
+        msg!("Record {} frozen: {}", record.key(), frozen);
+        Ok(())
+    }
+
+    /// Initialize the airdrop lock PDA (one-time setup)
+    pub fn initialize_lock(ctx: Context<InitializeLock>) -> Result<()> {
+        let lock = &mut ctx.accounts.lock;
+        lock.lock_holder = Pubkey::default();
+        lock.locked_at = 0;
+        lock.timeout_seconds = 0;
+        lock.run_id = 0;
+        lock.bump = ctx.bumps.lock;
+        msg!("Initialized airdrop lock");
+        Ok(())
+    }
+
+    /// Acquire the airdrop lock (or override if expired)
+    pub fn acquire_lock(ctx: Context<AcquireLock>, timeout_seconds: i64) -> Result<()> {
+        require!(
+            (60..=3600).contains(&timeout_seconds),
+            ErrorCode::InvalidTimeout
+        );
+
+        let lock = &mut ctx.accounts.lock;
+
+        // If lock is held, check if it has expired
+        if lock.lock_holder != Pubkey::default() {
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                now >= lock.locked_at + lock.timeout_seconds,
+                ErrorCode::LockHeld
+            );
+            msg!("Overriding expired lock held by {}", lock.lock_holder);
+        }
+
+        lock.lock_holder = ctx.accounts.authority.key();
+        lock.locked_at = Clock::get()?.unix_timestamp;
+        lock.timeout_seconds = timeout_seconds;
+        lock.run_id = 0;
+        msg!("Lock acquired by {}", lock.lock_holder);
+        Ok(())
+    }
+
+    /// Release the airdrop lock (holder only)
+    pub fn release_lock(ctx: Context<ReleaseLock>) -> Result<()> {
+        let lock = &mut ctx.accounts.lock;
+        require!(
+            lock.lock_holder == ctx.accounts.authority.key(),
+            ErrorCode::LockNotHeld
+        );
+
+        msg!("Lock released by {}", lock.lock_holder);
+        lock.lock_holder = Pubkey::default();
+        lock.locked_at = 0;
+        lock.timeout_seconds = 0;
+        lock.run_id = 0;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// State Accounts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct CreateRunV2<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the new run; pass the authority to have it pay
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AirdropRunV2::INIT_SPACE,
+        seeds = [b"run_v2", (state.run_counter + 1).to_le_bytes().as_ref()],
+        bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRunTotalsV2<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+}
+
+#[derive(Accounts)]
+#[instruction(eth_address: [u8; 42], sol_wallet: Pubkey)]
+pub struct InitializeRecordV2<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the new record; pass the authority to have it pay
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AirdropRecordV2::INIT_SPACE,
+        seeds = [
+            b"airdrop_record_v2",
+            &eth_address[..21],
+            &eth_address[21..42],
+        ],
+        bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    /// Run creating the record; when omitted the record's created_in_run
+    /// is 0
+    #[account(
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Option<Account<'info, AirdropRunV2>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Blacklist PDA of `sol_wallet`; the wallet is blacklisted if
+    /// this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", sol_wallet.as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: The sol_wallet account, only read for its owner and data
+    /// length; required while enforce_system_wallet is on
+    pub wallet: Option<UncheckedAccount<'info>>,
+
+    /// AllowedWallet PDA of `sol_wallet`; required while the wallet
+    /// allowlist is enabled
+    #[account(
+        seeds = [b"allowed_wallet", sol_wallet.as_ref()],
+        bump = allowed_wallet.bump
+    )]
+    pub allowed_wallet: Option<Account<'info, AllowedWallet>>,
+
+    /// CHECK: EthRegistry PDA of `eth_address`, created by the handler (see
+    /// create_eth_registry)
+    #[account(mut)]
+    pub eth_registry: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    eth_address: [u8; 42],
+    xnm_amount: u64,
+    xblk_amount: u64,
+    xuni_amount: u64,
+    native_amount: u64,
+    score: u64,
+    sol_wallet: Pubkey
+)]
+pub struct InitializeAndUpdateV2<'info> {
+    /// The authority, or a delegated operator passing its Operator account
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the new record; pass the authority to have it pay
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.is_writer(&authority.key(), operator.as_ref()) @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AirdropRecordV2::INIT_SPACE,
+        seeds = [
+            b"airdrop_record_v2",
+            &eth_address[..21],
+            &eth_address[21..42],
+        ],
+        bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    /// Run creating the record; when omitted the record's created_in_run
+    /// is 0
+    #[account(
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Option<Account<'info, AirdropRunV2>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Required when the signer is an operator rather than the authority
+    #[account(
+        seeds = [b"operator", operator.operator.as_ref()],
+        bump = operator.bump
+    )]
+    pub operator: Option<Account<'info, Operator>>,
+
+    /// CHECK: Blacklist PDA of `sol_wallet`; the wallet is blacklisted if
+    /// this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", sol_wallet.as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: The sol_wallet account, only read for its owner and data
+    /// length; required while enforce_system_wallet is on
+    pub wallet: Option<UncheckedAccount<'info>>,
+
+    /// AllowedWallet PDA of `sol_wallet`; required while the wallet
+    /// allowlist is enabled
+    #[account(
+        seeds = [b"allowed_wallet", sol_wallet.as_ref()],
+        bump = allowed_wallet.bump
+    )]
+    pub allowed_wallet: Option<Account<'info, AllowedWallet>>,
+
+    /// CHECK: EthRegistry PDA of `eth_address`, created by the handler (see
+    /// create_eth_registry)
+    #[account(mut)]
+    pub eth_registry: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(sol_wallet: Pubkey, eth_address: [u8; 42])]
+pub struct ImportExternalRecord<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AirdropRecordV2::INIT_SPACE,
+        seeds = [
+            b"airdrop_record_v2",
+            &eth_address[..21],
+            &eth_address[21..42],
+        ],
+        bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Blacklist PDA of `sol_wallet`; the wallet is blacklisted if
+    /// this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", sol_wallet.as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: The sol_wallet account, only read for its owner and data
+    /// length; required while enforce_system_wallet is on
+    pub wallet: Option<UncheckedAccount<'info>>,
+
+    /// AllowedWallet PDA of `sol_wallet`; required while the wallet
+    /// allowlist is enabled
+    #[account(
+        seeds = [b"allowed_wallet", sol_wallet.as_ref()],
+        bump = allowed_wallet.bump
+    )]
+    pub allowed_wallet: Option<Account<'info, AllowedWallet>>,
+
+    /// CHECK: EthRegistry PDA of `eth_address`, created by the handler (see
+    /// create_eth_registry)
+    #[account(mut)]
+    pub eth_registry: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecordV2<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    /// Run the credit is attributed to; when omitted the latest run is used
+    #[account(
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Option<Account<'info, AirdropRunV2>>,
+
+    /// CHECK: Blacklist PDA of the record's sol_wallet; the wallet is
+    /// blacklisted if this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", airdrop_record.sol_wallet.as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WriteRecordV2<'info> {
+    /// The authority, or a delegated operator passing its Operator account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.is_writer(&authority.key(), operator.as_ref()) @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    /// Run the credit is attributed to; when omitted the latest run is used
+    #[account(
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Option<Account<'info, AirdropRunV2>>,
+
+    /// Required when the signer is an operator rather than the authority
+    #[account(
+        seeds = [b"operator", operator.operator.as_ref()],
+        bump = operator.bump
+    )]
+    pub operator: Option<Account<'info, Operator>>,
+
+    /// CHECK: Blacklist PDA of the record's sol_wallet; the wallet is
+    /// blacklisted if this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", airdrop_record.sol_wallet.as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStateV2<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GlobalStateV2::INIT_SPACE,
+        seeds = [b"state_v2"],
+        bump
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
+    #[account(seeds = [b"state_v2"], bump = state.bump)]
+    pub state: Account<'info, GlobalStateV2>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLock<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AirdropLock::INIT_SPACE,
+        seeds = [b"lock"],
+        bump
+    )]
+    pub lock: Account<'info, AirdropLock>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcquireLock<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"lock"],
+        bump = lock.bump
+    )]
+    pub lock: Account<'info, AirdropLock>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseLock<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"lock"],
+        bump = lock.bump
+    )]
+    pub lock: Account<'info, AirdropLock>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRecordV2<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = eth_registry.record == airdrop_record.key() @ ErrorCode::InvalidRegistryAccount
+    )]
+    pub eth_registry: Account<'info, EthRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRun<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileRecordCount<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+}
+
+#[derive(Accounts)]
+pub struct BatchUpdateRecords<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+}
+
+#[derive(Accounts)]
+pub struct BatchInitializeAndUpdate<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the new records
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdvanceSeason<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SeasonSummary::INIT_SPACE,
+        seeds = [b"season", state.current_season.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub season_summary: Account<'info, SeasonSummary>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProveAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRun<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+}
+
+#[derive(Accounts)]
+pub struct EnsureAta<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump,
+        constraint = airdrop_record.sol_wallet != Pubkey::default() @ ErrorCode::InvalidWallet
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    /// CHECK: Recipient wallet that owns the token account; must be the
+    /// record's sol_wallet
+    #[account(constraint = wallet.key() == airdrop_record.sol_wallet @ ErrorCode::WalletMismatch)]
+    pub wallet: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Created if missing; the associated token program verifies the
+    /// address against wallet, mint and token program
+    #[account(mut)]
+    pub associated_token: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReadRecord<'info> {
+    #[account(
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecordWallet<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    /// CHECK: Recipient wallet to link to the record
+    pub wallet: UncheckedAccount<'info>,
+
+    /// Required while the wallet allowlist is enabled
+    #[account(
+        seeds = [b"allowed_wallet", wallet.key().as_ref()],
+        bump = allowed_wallet.bump
+    )]
+    pub allowed_wallet: Option<Account<'info, AllowedWallet>>,
+
+    /// CHECK: Blacklist PDA of `wallet`; the wallet is blacklisted if this
+    /// account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", wallet.key().as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// Registry entry of the record's eth_address; its sol_wallet is kept in
+    /// step with the record's
+    #[account(
+        mut,
+        constraint = eth_registry.record == airdrop_record.key() @ ErrorCode::InvalidRegistryAccount
+    )]
+    pub eth_registry: Account<'info, EthRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct RecipientUpdate<'info> {
+    pub wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump,
+        constraint = airdrop_record.sol_wallet == wallet.key() @ ErrorCode::WalletMismatch
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+}
+
+#[derive(Accounts)]
+pub struct AddAllowedWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    /// CHECK: Wallet being allowlisted
+    pub wallet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AllowedWallet::INIT_SPACE,
+        seeds = [b"allowed_wallet", wallet.key().as_ref()],
+        bump
+    )]
+    pub allowed_wallet: Account<'info, AllowedWallet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowedWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"allowed_wallet", allowed_wallet.wallet.as_ref()],
+        bump = allowed_wallet.bump
+    )]
+    pub allowed_wallet: Account<'info, AllowedWallet>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAndSweep<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump,
+        constraint = airdrop_record.forfeited @ ErrorCode::NotForfeited
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    #[account(mut, address = state.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = treasury,
+        constraint = eth_registry.record == airdrop_record.key() @ ErrorCode::InvalidRegistryAccount
+    )]
+    pub eth_registry: Account<'info, EthRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(run_id: u64)]
+pub struct GetRunInfo<'info> {
+    /// CHECK: May not exist yet; only the address is verified
+    #[account(
+        seeds = [b"run_v2", run_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub airdrop_run: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecordAndRun<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    #[account(
+        mut,
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+
+    /// CHECK: Blacklist PDA of the record's sol_wallet; the wallet is
+    /// blacklisted if this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", airdrop_record.sol_wallet.as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GrowRecord<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    /// CHECK: May use an older, shorter layout; owner, discriminator and PDA
+    /// are verified in the handler
+    #[account(mut)]
+    pub airdrop_record: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GrowState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: May use an older, shorter layout; owner, discriminator and
+    /// authority are verified in the handler
+    #[account(mut, seeds = [b"state_v2"], bump)]
+    pub state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AssertEthUnique<'info> {
+    #[account(
+        seeds = [
+            b"airdrop_record_v2",
+            &record_a.eth_address[..21],
+            &record_a.eth_address[21..42],
+        ],
+        bump = record_a.bump
+    )]
+    pub record_a: Account<'info, AirdropRecordV2>,
+
+    #[account(
+        seeds = [
+            b"airdrop_record_v2",
+            &record_b.eth_address[..21],
+            &record_b.eth_address[21..42],
+        ],
+        bump = record_b.bump
+    )]
+    pub record_b: Account<'info, AirdropRecordV2>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterEthAddress<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the registry entry
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    /// CHECK: EthRegistry PDA of the record's eth_address, created by the
+    /// handler (see create_eth_registry)
+    #[account(mut)]
+    pub eth_registry: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(run_id: u64)]
+pub struct GetRun<'info> {
+    #[account(
+        seeds = [b"run_v2", run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+}
+
+#[derive(Accounts)]
+pub struct SelfCloseRecord<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump,
+        constraint = airdrop_record.sol_wallet == wallet.key() @ ErrorCode::WalletMismatch,
+        constraint = airdrop_record.claimed @ ErrorCode::NotClaimed
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    #[account(
+        mut,
+        close = wallet,
+        constraint = eth_registry.record == airdrop_record.key() @ ErrorCode::InvalidRegistryAccount
+    )]
+    pub eth_registry: Account<'info, EthRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct BeginReconcile<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ReconcileAccumulator::INIT_SPACE,
+        seeds = [b"reconcile"],
+        bump
+    )]
+    pub accumulator: Account<'info, ReconcileAccumulator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileTotals<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"reconcile"],
+        bump = accumulator.bump
+    )]
+    pub accumulator: Account<'info, ReconcileAccumulator>,
+}
+
+#[derive(Accounts)]
+pub struct FinishReconcile<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"reconcile"],
+        bump = accumulator.bump
+    )]
+    pub accumulator: Account<'info, ReconcileAccumulator>,
+}
+
+#[derive(Accounts)]
+pub struct SetRunDate<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+
+    /// Run immediately before airdrop_run; needed when monotonic dates are
+    /// enforced and airdrop_run is not the first run
+    #[account(
+        seeds = [
+            b"run_v2",
+            airdrop_run.run_id.saturating_sub(1).to_le_bytes().as_ref()
+        ],
+        bump = previous_run.bump
+    )]
+    pub previous_run: Option<Account<'info, AirdropRunV2>>,
+
+    /// Run immediately after airdrop_run; needed when monotonic dates are
+    /// enforced and airdrop_run is not the latest run
+    #[account(
+        seeds = [
+            b"run_v2",
+            airdrop_run.run_id.saturating_add(1).to_le_bytes().as_ref()
+        ],
+        bump = next_run.bump
+    )]
+    pub next_run: Option<Account<'info, AirdropRunV2>>,
+}
+
+#[derive(Accounts)]
+pub struct BatchStampVersion<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSubRun<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"run_v2", airdrop_run.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SubRun::INIT_SPACE,
+        seeds = [
+            b"subrun",
+            airdrop_run.run_id.to_le_bytes().as_ref(),
+            airdrop_run.subrun_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub subrun: Account<'info, SubRun>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecordAndSubRun<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"subrun",
+            subrun.run_id.to_le_bytes().as_ref(),
+            subrun.subrun_index.to_le_bytes().as_ref()
+        ],
+        bump = subrun.bump
+    )]
+    pub subrun: Account<'info, SubRun>,
+
+    /// Parent run of `subrun`; must be active, and blocks non-zero credits
+    /// if it is a dry run
+    #[account(
+        seeds = [b"run_v2", subrun.run_id.to_le_bytes().as_ref()],
+        bump = airdrop_run.bump
+    )]
+    pub airdrop_run: Account<'info, AirdropRunV2>,
+
+    /// CHECK: Blacklist PDA of the record's sol_wallet; the wallet is
+    /// blacklisted if this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", airdrop_record.sol_wallet.as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BatchCloseRecords<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.pending_authority != Pubkey::default() @ ErrorCode::Unauthorized,
+        constraint = state.pending_authority == new_authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+}
+
+#[derive(Accounts)]
+#[instruction(token: TokenType)]
+pub struct Claim<'info> {
+    pub wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.token_mints[token as usize] == mint.key() @ ErrorCode::MintMismatch
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump,
+        constraint = airdrop_record.sol_wallet == wallet.key() @ ErrorCode::WalletMismatch
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Program vault holding `mint`, owned by the state PDA
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = state,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = wallet,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Blacklist PDA of the claiming wallet; the wallet is
+    /// blacklisted if this account exists (see check_not_blacklisted)
+    #[account(seeds = [b"blacklist", wallet.key().as_ref()], bump)]
+    pub blacklist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator_key: Pubkey)]
+pub struct AddOperator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Operator::INIT_SPACE,
+        seeds = [b"operator", operator_key.as_ref()],
+        bump
+    )]
+    pub operator: Account<'info, Operator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveOperator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"operator", operator.operator.as_ref()],
+        bump = operator.bump
+    )]
+    pub operator: Account<'info, Operator>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct BlacklistWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Blacklist::INIT_SPACE,
+        seeds = [b"blacklist", wallet.as_ref()],
+        bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnblacklistWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"blacklist", blacklist.wallet.as_ref()],
+        bump = blacklist.bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeRecord<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"state_v2"],
+        bump = state.bump,
+        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, GlobalStateV2>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"airdrop_record_v2",
+            &airdrop_record.eth_address[..21],
+            &airdrop_record.eth_address[21..42],
+        ],
+        bump = airdrop_record.bump
+    )]
+    pub airdrop_record: Account<'info, AirdropRecordV2>,
+}
+
+// ============================================================================
+// Account Structs
+// ============================================================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalStateV2 {
+    /// Schema version (set to 1)
+    pub version: u8, // 1 byte
+    /// Authority who can create runs and update records
+    pub authority: Pubkey, // 32 bytes
+    /// Counter for run IDs
+    pub run_counter: u64, // 8 bytes
+    /// Cumulative XNM airdropped across all records
+    pub xnm_airdropped: u64, // 8 bytes
+    /// Cumulative XBLK airdropped across all records
+    pub xblk_airdropped: u64, // 8 bytes
+    /// Cumulative XUNI airdropped across all records
+    pub xuni_airdropped: u64, // 8 bytes
+    /// Cumulative native (XNT) airdropped across all records
+    pub native_airdropped: u64, // 8 bytes
+    /// Key proposed by propose_authority, awaiting accept_authority (default =
+    /// none). Occupies the former reserved space, which was always zeroed.
+    pub pending_authority: Pubkey, // 32 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+    // Fields below are appended after the original layout so existing offsets
+    // stay stable.
+    /// Number of live AirdropRecordV2 accounts
+    pub record_count: u64, // 8 bytes
+    /// Season currently accumulating into the season_* totals
+    pub current_season: u16, // 2 bytes
+    /// XNM airdropped during the current season
+    pub season_xnm_airdropped: u64, // 8 bytes
+    /// XBLK airdropped during the current season
+    pub season_xblk_airdropped: u64, // 8 bytes
+    /// XUNI airdropped during the current season
+    pub season_xuni_airdropped: u64, // 8 bytes
+    /// Native (XNT) airdropped during the current season
+    pub season_native_airdropped: u64, // 8 bytes
+    /// CLUSTER_MODE_DEVNET (0) or CLUSTER_MODE_MAINNET (1); mainnet mode
+    /// rejects placeholder eth_addresses on record creation
+    pub cluster_mode: u8, // 1 byte
+    /// Lifetime XNM cap across all records (0 = uncapped)
+    pub max_total_xnm: u64, // 8 bytes
+    /// Lifetime XBLK cap across all records (0 = uncapped)
+    pub max_total_xblk: u64, // 8 bytes
+    /// Lifetime XUNI cap across all records (0 = uncapped)
+    pub max_total_xuni: u64, // 8 bytes
+    /// Lifetime native (XNT) cap across all records (0 = uncapped)
+    pub max_total_native: u64, // 8 bytes
+    /// Reject a second update_record_v2 credit to the same record within one
+    /// run unless the caller passes allow_recredit (opt-in)
+    pub dedup_per_run: bool, // 1 byte
+    /// Reject credits to records that have not been acknowledged (opt-in)
+    pub require_ack: bool, // 1 byte
+    /// Only allow AllowedWallet destinations in set_record_wallet (opt-in)
+    pub wallet_allowlist_enabled: bool, // 1 byte
+    /// Reject update_run_totals_v2 for non-dry runs without a reference hash
+    pub require_reference: bool, // 1 byte
+    /// Destination for rent swept by close_and_sweep
+    pub treasury: Pubkey, // 32 bytes
+    /// XNM owed to forfeited records swept to the treasury
+    pub swept_xnm: u64, // 8 bytes
+    /// XBLK owed to forfeited records swept to the treasury
+    pub swept_xblk: u64, // 8 bytes
+    /// XUNI owed to forfeited records swept to the treasury
+    pub swept_xuni: u64, // 8 bytes
+    /// Native (XNT) owed to forfeited records swept to the treasury
+    pub swept_native: u64, // 8 bytes
+    /// Ceiling on a record's lifetime total summed across tokens. u64::MAX
+    /// disables it; 0 (legacy accounts grown in place) is treated the same.
+    pub max_lifetime_per_record: u64, // 8 bytes
+    /// Root of the eligibility merkle tree (all zeros = unset)
+    pub merkle_root: [u8; 32], // 32 bytes
+    /// Minimum seconds between non-dry runs (0 = no cooldown)
+    pub min_interval_between_runs: i64, // 8 bytes
+    /// Unix timestamp of the most recent non-dry run
+    pub last_run_date: i64, // 8 bytes
+    /// Reject updates whose token amounts are all zero (opt-in)
+    pub reject_zero_updates: bool, // 1 byte
+    /// Address of the most recently created run (default if none)
+    pub latest_run: Pubkey, // 32 bytes
+    /// Maximum value of run_counter (0 = unlimited)
+    pub max_runs: u64, // 8 bytes
+    /// Whether dry runs may be created past max_runs
+    pub max_runs_exempt_dry: bool, // 1 byte
+    /// Reject record wallets that are not system-owned or empty (opt-in)
+    pub enforce_system_wallet: bool, // 1 byte
+    /// Credit only records' preferred_token when set (opt-in)
+    pub respect_preference: bool, // 1 byte
+    /// Reject non-dry runs created without a snapshot block (opt-in)
+    pub require_snapshot_block: bool, // 1 byte
+    /// Decimals used by update_record_whole (9 for all tracked tokens). Legacy
+    /// accounts grown in place read 0 until set_decimals is called.
+    pub decimals: u8, // 1 byte
+    /// Unix timestamp before which credits are rejected (0 = unbounded)
+    pub distribution_start: i64, // 8 bytes
+    /// Unix timestamp after which credits are rejected (0 = unbounded)
+    pub distribution_end: i64, // 8 bytes
+    /// Set by retire_program; blocks credits, record creation and new runs
+    pub retired: bool, // 1 byte
+    /// Let recipients close their own claimed records (opt-in)
+    pub allow_self_close: bool, // 1 byte
+    /// Require run dates to never go backwards (opt-in; off for legacy imports)
+    pub enforce_monotonic_run_dates: bool, // 1 byte
+    /// run_date of the most recent run (dry runs included)
+    pub latest_run_date: i64, // 8 bytes
+    /// Share of a credit_lump_sum total credited as XNM, in basis points.
+    /// The four split_bps fields must sum to BPS_DENOMINATOR (all zero =
+    /// unconfigured).
+    pub split_bps_xnm: u16, // 2 bytes
+    /// Share credited as XBLK, in basis points
+    pub split_bps_xblk: u16, // 2 bytes
+    /// Share credited as XUNI, in basis points
+    pub split_bps_xuni: u16, // 2 bytes
+    /// Share credited as native (XNT), in basis points
+    pub split_bps_native: u16, // 2 bytes
+    /// Set by set_pause; blocks record creation and credits (runs and closes
+    /// still work so operations can wind down)
+    pub paused: bool, // 1 byte
+    /// SPL mint paid out by claim for each token, indexed by TokenType
+    /// (default = claims disabled for that token)
+    pub token_mints: [Pubkey; 4], // 128 bytes
+    /// Co-signers for sensitive instructions (unused slots = default)
+    pub signers: [Pubkey; MAX_SIGNERS], // 160 bytes
+    /// Listed signers required on sensitive instructions (0 = authority only)
+    pub threshold: u8, // 1 byte
+    /// Number of delegated Operator accounts
+    pub operator_count: u8, // 1 byte
+    /// Minimum seconds between credits to the same existing record (0 = no
+    /// limit), measured from its last_credited_at; record creation is exempt
+    pub min_update_interval: i64, // 8 bytes
+    /// Require an ETH signature on records not created by the authority (opt-in)
+    pub require_eth_proof: bool, // 1 byte
+    /// Root of the allocation merkle tree checked by initialize_and_update_v2
+    /// (all zeros = unset)
+    pub allocation_root: [u8; 32], // 32 bytes
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropRunV2 {
+    /// Schema version (set to 1)
+    pub version: u8, // 1 byte
+    /// Unique run ID
+    pub run_id: u64, // 8 bytes
+    /// Unix timestamp when run started
+    pub run_date: i64, // 8 bytes
+    /// Number of successful recipients
+    pub total_recipients: u32, // 4 bytes
+    /// Total combined amount airdropped (preserved from v1)
+    pub total_amount: u64, // 8 bytes
+    /// Total XNM amount airdropped
+    pub total_xnm_amount: u64, // 8 bytes
+    /// Total XBLK amount airdropped
+    pub total_xblk_amount: u64, // 8 bytes
+    /// Total XUNI amount airdropped
+    pub total_xuni_amount: u64, // 8 bytes
+    /// Total native (XNT) amount airdropped
+    pub total_native_amount: u64, // 8 bytes
+    /// Whether this was a dry run
+    pub dry_run: bool, // 1 byte
+    /// Reserved space for future use
+    pub reserved: [u64; 4], // 32 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+    // Fields below are appended after the original layout so existing offsets
+    // stay stable.
+    /// Lamports spent on rent and transaction fees for this run
+    pub fees_spent: u64, // 8 bytes
+    /// Total amount allotted to this run (0 = no budget set)
+    pub budget: u64, // 8 bytes
+    /// Free-form operator label (zero-padded)
+    pub label: [u8; 32], // 32 bytes
+    /// Unix timestamp the run is scheduled for (0 = unscheduled)
+    pub scheduled_at: i64, // 8 bytes
+    /// Hash of the published announcement for this run (zero if unset)
+    pub reference_hash: [u8; 32], // 32 bytes
+    /// ETH block of the snapshot this run was computed from (0 = unspecified)
+    pub snapshot_block: u64, // 8 bytes
+    /// Number of SubRun partitions created for this run
+    pub subrun_count: u16, // 2 bytes
+    /// Lifecycle status; totals only change while Active (a zeroed byte
+    /// reads as Active)
+    pub status: RunStatus, // 1 byte
+    /// Ceiling on total_recipients (0 = no cap)
+    pub max_recipients: u32, // 4 bytes
+    /// Ceiling on total_amount (0 = no cap)
+    pub max_amount: u64, // 8 bytes
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropRecordV2 {
+    /// The associated ETH address (as UTF-8 bytes, e.g., "0x1234...")
+    pub eth_address: [u8; 42], // 42 bytes
+    /// Cumulative XNM amount airdropped (in token base units, 9 decimals)
+    pub xnm_airdropped: u64, // 8 bytes
+    /// Cumulative XBLK amount airdropped (in token base units, 9 decimals)
+    pub xblk_airdropped: u64, // 8 bytes
+    /// Cumulative XUNI amount airdropped (in token base units, 9 decimals)
+    pub xuni_airdropped: u64, // 8 bytes
+    /// Cumulative native token (XNT) airdropped (in lamports, 9 decimals)
+    pub native_airdropped: u64, // 8 bytes
+    /// Cumulative XNM paid out by claim (formerly reserved[0]; zeroed by
+    /// migration to version 2)
+    pub xnm_claimed: u64, // 8 bytes
+    /// Cumulative XBLK paid out by claim (formerly reserved[1])
+    pub xblk_claimed: u64, // 8 bytes
+    /// Cumulative XUNI paid out by claim (formerly reserved[2])
+    pub xuni_claimed: u64, // 8 bytes
+    /// Cumulative native (XNT) paid out by claim (formerly reserved[3])
+    pub native_claimed: u64, // 8 bytes
+    /// Unix timestamp of last update
+    pub last_updated: i64, // 8 bytes
+    /// PDA bump seed for derivation
+    pub bump: u8, // 1 byte
+    // Fields below are appended after the original layout so existing offsets
+    // stay stable and legacy accounts can be grown in place.
+    /// Whether the record was imported from the xnm-airdrop-tracker program
+    pub imported: bool, // 1 byte
+    /// Season in which the record was last credited
+    pub last_credited_season: u16, // 2 bytes
+    /// Whether `ensure_ata` has provisioned an associated token account for
+    /// the recipient. Set by the first mint ensured; it does not track mints
+    /// individually.
+    pub ata_ready: bool, // 1 byte
+    /// Run that created the record (0 for imported records); never changes
+    pub created_in_run: u64, // 8 bytes
+    /// Run the last credit was attributed to: the run account passed to the
+    /// crediting instruction, else the latest run at that time
+    pub last_credited_run: u64, // 8 bytes
+    /// Solana wallet set at creation or linked via `set_record_wallet`
+    /// (default if unset)
+    pub sol_wallet: Pubkey, // 32 bytes
+    /// Whether sol_wallet has accepted the campaign terms
+    pub acknowledged: bool, // 1 byte
+    /// Whether the recipient forfeited; forfeited records can be swept
+    pub forfeited: bool, // 1 byte
+    /// Unix timestamp XNM was last credited (0 if never)
+    pub xnm_updated_at: i64, // 8 bytes
+    /// Unix timestamp XBLK was last credited (0 if never)
+    pub xblk_updated_at: i64, // 8 bytes
+    /// Unix timestamp XUNI was last credited (0 if never)
+    pub xuni_updated_at: i64, // 8 bytes
+    /// Unix timestamp native (XNT) was last credited (0 if never)
+    pub native_updated_at: i64, // 8 bytes
+    /// Off-chain distribution batch of the last credit (zero = unspecified)
+    pub last_batch_id: [u8; 16], // 16 bytes
+    /// XNM added by the most recent credit (cleared by rescind_last_update)
+    pub last_delta_xnm: u64, // 8 bytes
+    /// XBLK added by the most recent credit
+    pub last_delta_xblk: u64, // 8 bytes
+    /// XUNI added by the most recent credit
+    pub last_delta_xuni: u64, // 8 bytes
+    /// Native (XNT) added by the most recent credit
+    pub last_delta_native: u64, // 8 bytes
+    /// Off-chain eligibility score, set at creation
+    pub score: u64, // 8 bytes
+    /// Amount credited by the last distribute_by_score
+    pub score_amount: u64, // 8 bytes
+    /// Single token the recipient elected to receive (None = all tokens)
+    pub preferred_token: Option<TokenType>, // 2 bytes
+    /// Free-form campaign tag (zero-padded)
+    pub tag: [u8; 32], // 32 bytes
+    /// Unix timestamp after which the record can no longer be claimed (0 = none)
+    pub claim_deadline: i64, // 8 bytes
+    /// Set once the recipient has claimed their allocation
+    pub claimed: bool, // 1 byte
+    /// Unix timestamp before which the record cannot be changed or closed
+    /// (0 = unlocked)
+    pub locked_until: i64, // 8 bytes
+    /// Record schema version (see `RECORD_VERSION`)
+    pub version: u8, // 1 byte
+    /// Unix timestamp the record was created; never changes afterwards
+    /// (0 for records created before the field existed)
+    pub created_at: i64, // 8 bytes
+    /// Whether credits are blocked pending a dispute (see set_record_freeze)
+    pub frozen: bool, // 1 byte
+    /// Incremented on every change to the amounts; every crediting,
+    /// correcting and rescinding instruction must quote it as expected_nonce
+    pub nonce: u64, // 8 bytes
+    /// Unix timestamp of the last credit, which min_update_interval is
+    /// measured from (0 if never credited since the field existed)
+    pub last_credited_at: i64, // 8 bytes
+    /// XNM granted via grant_claim and not yet paid out by claim. Credits
+    /// from update_record_v2 record transfers already sent off-chain and
+    /// never add to it.
+    pub xnm_claimable: u64, // 8 bytes
+    /// XBLK granted and not yet claimed
+    pub xblk_claimable: u64, // 8 bytes
+    /// XUNI granted and not yet claimed
+    pub xuni_claimable: u64, // 8 bytes
+    /// Native (XNT) granted and not yet claimed
+    pub native_claimable: u64, // 8 bytes
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropLock {
+    /// Public key of the current lock holder
+    pub lock_holder: Pubkey, // 32 bytes
+    /// Unix timestamp when the lock was acquired
+    pub locked_at: i64, // 8 bytes
+    /// Lock timeout duration in seconds
+    pub timeout_seconds: i64, // 8 bytes
+    /// Associated run ID (set after create_run for audit trail)
+    pub run_id: u64, // 8 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonSummary {
+    /// Season number this summary closes out
+    pub season: u16, // 2 bytes
+    /// XNM airdropped during the season
+    pub xnm_airdropped: u64, // 8 bytes
+    /// XBLK airdropped during the season
+    pub xblk_airdropped: u64, // 8 bytes
+    /// XUNI airdropped during the season
+    pub xuni_airdropped: u64, // 8 bytes
+    /// Native (XNT) airdropped during the season
+    pub native_airdropped: u64, // 8 bytes
+    /// Unix timestamp when the season was closed
+    pub ended_at: i64, // 8 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AllowedWallet {
+    /// Wallet permitted as a record destination
+    pub wallet: Pubkey, // 32 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+/// Registry entry for one ETH address, seeded by its 20 decoded bytes so
+/// that mixed-case spellings of the address can't each get a record
+#[account]
+#[derive(InitSpace)]
+pub struct EthRegistry {
+    /// The address's AirdropRecordV2
+    pub record: Pubkey, // 32 bytes
+    /// Wallet linked to the record (default if unset)
+    pub sol_wallet: Pubkey, // 32 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Blacklist {
+    /// Wallet barred from records and credits
+    pub wallet: Pubkey, // 32 bytes
+    /// Unix timestamp the wallet was blacklisted
+    pub blacklisted_at: i64, // 8 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReconcileAccumulator {
+    /// Key of the last record summed; pages must continue above it
+    pub cursor: Pubkey, // 32 bytes
+    /// Number of records summed so far
+    pub records: u64, // 8 bytes
+    /// XNM summed so far
+    pub xnm_airdropped: u64, // 8 bytes
+    /// XBLK summed so far
+    pub xblk_airdropped: u64, // 8 bytes
+    /// XUNI summed so far
+    pub xuni_airdropped: u64, // 8 bytes
+    /// Native (XNT) summed so far
+    pub native_airdropped: u64, // 8 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SubRun {
+    /// Parent run ID
+    pub run_id: u64, // 8 bytes
+    /// Position within the parent run, from 0
+    pub subrun_index: u16, // 2 bytes
+    /// Free-form operator label (zero-padded)
+    pub label: [u8; 32], // 32 bytes
+    /// Number of credits attributed to this sub-run
+    pub total_recipients: u32, // 4 bytes
+    /// XNM attributed to this sub-run
+    pub total_xnm_amount: u64, // 8 bytes
+    /// XBLK attributed to this sub-run
+    pub total_xblk_amount: u64, // 8 bytes
+    /// XUNI attributed to this sub-run
+    pub total_xuni_amount: u64, // 8 bytes
+    /// Native (XNT) attributed to this sub-run
+    pub total_native_amount: u64, // 8 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Operator {
+    /// Key allowed to create and credit records on the authority's behalf
+    pub operator: Pubkey, // 32 bytes
+    /// Unix timestamp the operator was added
+    pub added_at: i64, // 8 bytes
+    /// PDA bump
+    pub bump: u8, // 1 byte
+}
+
+impl GlobalStateV2 {
+    /// Fail with UpdateTooSoon if `record` was last credited less than
+    /// min_update_interval seconds ago. Records never credited pass.
+    pub fn check_update_interval(&self, record: &AirdropRecordV2, now: i64) -> Result<()> {
+        if record.last_credited_at == 0 {
+            return Ok(());
+        }
+        require!(
+            now.saturating_sub(record.last_credited_at) >= self.min_update_interval,
+            ErrorCode::UpdateTooSoon
+        );
+        Ok(())
+    }
+
+    /// Whether `signer` may write records: the authority, or the key of the
+    /// Operator account passed alongside it
+    pub fn is_writer(&self, signer: &Pubkey, operator: Option<&Account<Operator>>) -> bool {
+        *signer == self.authority || operator.is_some_and(|op| op.operator == *signer)
+    }
+
+    /// Fail with Unauthorized unless at least `threshold` listed co-signers
+    /// appear as signers in `accounts`. Passes when no threshold is set.
+    pub fn check_signers(&self, accounts: &[AccountInfo]) -> Result<()> {
+        if self.threshold == 0 {
+            return Ok(());
+        }
+        require!(
+            self.signer_approvals(accounts) >= self.threshold as usize,
+            ErrorCode::Unauthorized
+        );
+        Ok(())
+    }
+
+    /// Number of configured co-signers that signed among `accounts`.
+    pub fn signer_approvals(&self, accounts: &[AccountInfo]) -> usize {
+        self.signers
+            .iter()
+            .filter(|signer| **signer != Pubkey::default())
+            .filter(|signer| {
+                accounts
+                    .iter()
+                    .any(|info| info.is_signer && info.key == *signer)
+            })
+            .count()
+    }
+
+    /// Add credited amounts to the lifetime and current-season totals.
+    /// Either all totals are updated or none are.
+    pub fn add_totals(
+        &mut self,
+        xnm_amount: u64,
+        xblk_amount: u64,
+        xuni_amount: u64,
+        native_amount: u64,
+    ) -> Result<()> {
+        let xnm = self
+            .xnm_airdropped
+            .checked_add(xnm_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let xblk = self
+            .xblk_airdropped
+            .checked_add(xblk_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let xuni = self
+            .xuni_airdropped
+            .checked_add(xuni_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let native = self
+            .native_airdropped
+            .checked_add(native_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            within_cap(xnm, self.max_total_xnm)
+                && within_cap(xblk, self.max_total_xblk)
+                && within_cap(xuni, self.max_total_xuni)
+                && within_cap(native, self.max_total_native),
+            ErrorCode::TokenCapExceeded
+        );
+        let season_xnm = self
+            .season_xnm_airdropped
+            .checked_add(xnm_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let season_xblk = self
+            .season_xblk_airdropped
+            .checked_add(xblk_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let season_xuni = self
+            .season_xuni_airdropped
+            .checked_add(xuni_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let season_native = self
+            .season_native_airdropped
+            .checked_add(native_amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        self.xnm_airdropped = xnm;
+        self.xblk_airdropped = xblk;
+        self.xuni_airdropped = xuni;
+        self.native_airdropped = native;
+        self.season_xnm_airdropped = season_xnm;
+        self.season_xblk_airdropped = season_xblk;
+        self.season_xuni_airdropped = season_xuni;
+        self.season_native_airdropped = season_native;
+        Ok(())
+    }
+
+    /// Allocate the next run ID, enforcing the run cap and, for non-dry runs,
+    /// the snapshot block requirement and the cooldown
+    pub fn start_run(&mut self, dry_run: bool, snapshot_block: u64, now: i64) -> Result<u64> {
+        require!(!self.retired, ErrorCode::ProgramRetired);
+        if self.enforce_monotonic_run_dates {
+            require!(now >= self.latest_run_date, ErrorCode::NonMonotonicRunDate);
+        }
+        if self.max_runs != 0 && !(dry_run && self.max_runs_exempt_dry) {
+            require!(self.run_counter < self.max_runs, ErrorCode::MaxRunsReached);
+        }
+        if !dry_run {
+            if self.require_snapshot_block {
+                require!(snapshot_block != 0, ErrorCode::SnapshotBlockRequired);
+            }
+            require!(
+                now.saturating_sub(self.last_run_date) >= self.min_interval_between_runs,
+                ErrorCode::RunCooldownActive
+            );
+            self.last_run_date = now;
+        }
+        self.latest_run_date = now;
+        self.run_counter += 1;
+        Ok(self.run_counter)
+    }
+
+    /// Split a lump sum across the four tokens by the configured basis points.
+    /// Each share rounds down and the remainder goes to XNM, so the shares
+    /// always add up to `total`.
+    pub fn split_lump_sum(&self, total: u64) -> Result<TokenAmounts> {
+        require!(
+            split_bps_valid(
+                self.split_bps_xnm,
+                self.split_bps_xblk,
+                self.split_bps_xuni,
+                self.split_bps_native
+            ),
+            ErrorCode::RatioMisconfigured
+        );
+
+        let xblk = apply_bps(total, self.split_bps_xblk as u64)?;
+        let xuni = apply_bps(total, self.split_bps_xuni as u64)?;
+        let native = apply_bps(total, self.split_bps_native as u64)?;
+        let xnm = total
+            .checked_sub(xblk)
+            .and_then(|t| t.checked_sub(xuni))
+            .and_then(|t| t.checked_sub(native))
+            .ok_or(ErrorCode::Underflow)?;
+        Ok(TokenAmounts {
+            xnm,
+            xblk,
+            xuni,
+            native,
+        })
+    }
+
+    /// Count a newly created record
+    pub fn increment_record_count(&mut self) -> Result<()> {
+        self.record_count = self
+            .record_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Subtract rescinded amounts from the lifetime totals, and from the
+    /// current-season totals when `include_season` is set.
+    /// Either all totals are updated or none are.
+    pub fn sub_totals(
+        &mut self,
+        xnm_amount: u64,
+        xblk_amount: u64,
+        xuni_amount: u64,
+        native_amount: u64,
+        include_season: bool,
+    ) -> Result<()> {
+        let xnm = self
+            .xnm_airdropped
+            .checked_sub(xnm_amount)
+            .ok_or(ErrorCode::Underflow)?;
+        let xblk = self
+            .xblk_airdropped
+            .checked_sub(xblk_amount)
+            .ok_or(ErrorCode::Underflow)?;
+        let xuni = self
+            .xuni_airdropped
+            .checked_sub(xuni_amount)
+            .ok_or(ErrorCode::Underflow)?;
+        let native = self
+            .native_airdropped
+            .checked_sub(native_amount)
+            .ok_or(ErrorCode::Underflow)?;
+
+        if include_season {
+            let season_xnm = self
+                .season_xnm_airdropped
+                .checked_sub(xnm_amount)
+                .ok_or(ErrorCode::Underflow)?;
+            let season_xblk = self
+                .season_xblk_airdropped
+                .checked_sub(xblk_amount)
+                .ok_or(ErrorCode::Underflow)?;
+            let season_xuni = self
+                .season_xuni_airdropped
+                .checked_sub(xuni_amount)
+                .ok_or(ErrorCode::Underflow)?;
+            let season_native = self
+                .season_native_airdropped
+                .checked_sub(native_amount)
+                .ok_or(ErrorCode::Underflow)?;
+
+            self.season_xnm_airdropped = season_xnm;
+            self.season_xblk_airdropped = season_xblk;
+            self.season_xuni_airdropped = season_xuni;
+            self.season_native_airdropped = season_native;
+        }
+
+        self.xnm_airdropped = xnm;
+        self.xblk_airdropped = xblk;
+        self.xuni_airdropped = xuni;
+        self.native_airdropped = native;
+        Ok(())
+    }
+
+    /// Whether `now` falls inside the distribution window (0 = unbounded)
+    pub fn distribution_open(&self, now: i64) -> bool {
+        (self.distribution_start == 0 || now >= self.distribution_start)
+            && (self.distribution_end == 0 || now <= self.distribution_end)
+    }
+
+    /// Reject all-zero updates when reject_zero_updates is enabled
+    pub fn check_update_amounts(
+        &self,
+        xnm_amount: u64,
+        xblk_amount: u64,
+        xuni_amount: u64,
+        native_amount: u64,
+    ) -> Result<()> {
+        if self.reject_zero_updates {
+            require!(
+                xnm_amount != 0 || xblk_amount != 0 || xuni_amount != 0 || native_amount != 0,
+                ErrorCode::ZeroAmountUpdate
+            );
+        }
+        Ok(())
+    }
+
+    /// Verify an ETH ownership proof for a new record: a signature over
+    /// eth_proof_message for the `sol_wallet` being linked. Without one, the
+    /// record may only be created by the authority while require_eth_proof
+    /// is on.
+    pub fn check_eth_proof(
+        &self,
+        signer: &Pubkey,
+        eth_address: &[u8; 42],
+        proof: Option<(&[u8; 65], &Pubkey)>,
+    ) -> Result<()> {
+        match proof {
+            Some((signature, sol_wallet)) => {
+                let message = eth_proof_message(eth_address, sol_wallet);
+                let recovered = recover_eth_address(signature, message.as_bytes())
+                    .ok_or(ErrorCode::EthSignatureInvalid)?;
+                require!(
+                    eth_address_matches(eth_address, &recovered),
+                    ErrorCode::EthSignatureInvalid
+                );
+            }
+            None => require!(
+                !self.require_eth_proof || *signer == self.authority,
+                ErrorCode::EthSignatureInvalid
+            ),
+        }
+        Ok(())
+    }
+
+    /// Reject the default pubkey and any wallet that is off the ed25519
+    /// curve (a PDA can't sign or hold native funds as a plain wallet). The
+    /// all-zero key decodes to a curve point, so it is rejected explicitly.
+    /// While enforce_system_wallet is on, the wallet's account must also be
+    /// passed and be system-owned or empty, and while the wallet allowlist is
+    /// enabled its AllowedWallet PDA must be passed.
+    pub fn check_wallet(
+        &self,
+        wallet: &Pubkey,
+        info: Option<&AccountInfo>,
+        allowed: Option<&AllowedWallet>,
+    ) -> Result<()> {
+        require_keys_neq!(*wallet, Pubkey::default(), ErrorCode::InvalidWallet);
+        require!(wallet.is_on_curve(), ErrorCode::InvalidWallet);
+        if self.wallet_allowlist_enabled {
+            require!(
+                allowed.is_some_and(|allowed| allowed.wallet == *wallet),
+                ErrorCode::WalletNotAllowed
+            );
+        }
+        if self.enforce_system_wallet {
+            let info = info.ok_or(ErrorCode::InvalidWallet)?;
+            require_keys_eq!(info.key(), *wallet, ErrorCode::InvalidWallet);
+            require!(
+                info.owner == &system_program::ID || info.data_is_empty(),
+                ErrorCode::InvalidWalletOwner
+            );
+        }
+        Ok(())
+    }
+
+    /// Validate an eth_address before a record is created for it
+    pub fn check_new_record(&self, eth_address: &[u8; 42]) -> Result<()> {
+        require!(!self.paused, ErrorCode::ProgramPaused);
+        require!(!self.retired, ErrorCode::ProgramRetired);
+        require!(
+            validate_eth_address(eth_address),
+            ErrorCode::InvalidEthAddress
+        );
+        if self.cluster_mode == CLUSTER_MODE_MAINNET {
+            require!(
+                !is_test_eth_address(eth_address),
+                ErrorCode::TestAddressRejected
+            );
+        }
+        Ok(())
+    }
+}
+
+impl AirdropRunV2 {
+    /// Set every field of a freshly created run to its default
+    pub fn initialize(&mut self, run_id: u64, dry_run: bool, bump: u8, now: i64) {
+        self.version = 1;
+        self.run_id = run_id;
+        self.run_date = now;
+        self.total_recipients = 0;
+        self.total_amount = 0;
+        self.total_xnm_amount = 0;
+        self.total_xblk_amount = 0;
+        self.total_xuni_amount = 0;
+        self.total_native_amount = 0;
+        self.dry_run = dry_run;
+        self.reserved = [0u64; 4];
+        self.bump = bump;
+        self.fees_spent = 0;
+        self.budget = 0;
+        self.label = [0u8; 32];
+        self.scheduled_at = 0;
+        self.reference_hash = [0u8; 32];
+        self.snapshot_block = 0;
+        self.subrun_count = 0;
+        self.status = RunStatus::Active;
+        self.max_recipients = 0;
+        self.max_amount = 0;
+    }
+
+    /// Fail with RunClosed unless the run is still Active
+    pub fn check_active(&self) -> Result<()> {
+        require!(self.status == RunStatus::Active, ErrorCode::RunClosed);
+        Ok(())
+    }
+
+    /// Mark the run Completed, which also locks its recorded fees, and emit
+    /// RunFinalized. Shared by finalize_run and update_run_totals_v2's
+    /// auto_finalize.
+    pub fn finalize(&mut self) {
+        self.status = RunStatus::Completed;
+
+        emit!(RunFinalized {
+            run_id: self.run_id,
+            fees_spent: self.fees_spent,
+        });
+
+        msg!("Finalized run #{}", self.run_id);
+    }
+
+    /// Overwrite the run's totals with the sum of its sub-runs, passed in
+    /// index order (every sub-run must be present)
+    pub fn rollup<'info>(&mut self, subruns: &'info [AccountInfo<'info>]) -> Result<()> {
+        require!(
+            subruns.len() == self.subrun_count as usize,
+            ErrorCode::SubRunMismatch
+        );
+
+        let mut totals = TokenAmounts {
+            xnm: 0,
+            xblk: 0,
+            xuni: 0,
+            native: 0,
+        };
+        let mut recipients: u32 = 0;
+        for (index, info) in subruns.iter().enumerate() {
+            let subrun: Account<SubRun> = Account::try_from(info)?;
+            let expected = Pubkey::create_program_address(
+                &[
+                    b"subrun",
+                    self.run_id.to_le_bytes().as_ref(),
+                    (index as u16).to_le_bytes().as_ref(),
+                    &[subrun.bump],
+                ],
+                &crate::ID,
+            )
+            .map_err(|_| error!(ErrorCode::SubRunMismatch))?;
+            require_keys_eq!(expected, info.key(), ErrorCode::SubRunMismatch);
+
+            recipients = recipients
+                .checked_add(subrun.total_recipients)
+                .ok_or(ErrorCode::Overflow)?;
+            totals.xnm = totals
+                .xnm
+                .checked_add(subrun.total_xnm_amount)
+                .ok_or(ErrorCode::Overflow)?;
+            totals.xblk = totals
+                .xblk
+                .checked_add(subrun.total_xblk_amount)
+                .ok_or(ErrorCode::Overflow)?;
+            totals.xuni = totals
+                .xuni
+                .checked_add(subrun.total_xuni_amount)
+                .ok_or(ErrorCode::Overflow)?;
+            totals.native = totals
+                .native
+                .checked_add(subrun.total_native_amount)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        let total = totals
+            .xnm
+            .checked_add(totals.xblk)
+            .and_then(|t| t.checked_add(totals.xuni))
+            .and_then(|t| t.checked_add(totals.native))
+            .ok_or(ErrorCode::Overflow)?;
+        self.check_caps(recipients, total)?;
+
+        self.total_recipients = recipients;
+        self.total_xnm_amount = totals.xnm;
+        self.total_xblk_amount = totals.xblk;
+        self.total_xuni_amount = totals.xuni;
+        self.total_native_amount = totals.native;
+        self.total_amount = total;
+        Ok(())
+    }
+
+    /// Fail with DryRunWriteBlocked if this is a dry run and any amount is
+    /// non-zero. Zero-amount credits still count recipients in a rehearsal.
+    pub fn check_credit(&self, amounts: &TokenAmounts) -> Result<()> {
+        if self.dry_run {
+            require!(
+                amounts.xnm == 0 && amounts.xblk == 0 && amounts.xuni == 0 && amounts.native == 0,
+                ErrorCode::DryRunWriteBlocked
+            );
+        }
+        Ok(())
+    }
+
+    /// Count one credited recipient and add its amounts to the run totals.
+    /// Either all totals are updated or none are.
+    pub fn add_credit(&mut self, amounts: &TokenAmounts) -> Result<()> {
+        let recipients = self
+            .total_recipients
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        let xnm = self
+            .total_xnm_amount
+            .checked_add(amounts.xnm)
+            .ok_or(ErrorCode::Overflow)?;
+        let xblk = self
+            .total_xblk_amount
+            .checked_add(amounts.xblk)
+            .ok_or(ErrorCode::Overflow)?;
+        let xuni = self
+            .total_xuni_amount
+            .checked_add(amounts.xuni)
+            .ok_or(ErrorCode::Overflow)?;
+        let native = self
+            .total_native_amount
+            .checked_add(amounts.native)
+            .ok_or(ErrorCode::Overflow)?;
+        let total = self
+            .total_amount
+            .checked_add(amounts.xnm)
+            .and_then(|t| t.checked_add(amounts.xblk))
+            .and_then(|t| t.checked_add(amounts.xuni))
+            .and_then(|t| t.checked_add(amounts.native))
+            .ok_or(ErrorCode::Overflow)?;
+        self.check_caps(recipients, total)?;
+
+        self.total_recipients = recipients;
+        self.total_xnm_amount = xnm;
+        self.total_xblk_amount = xblk;
+        self.total_xuni_amount = xuni;
+        self.total_native_amount = native;
+        self.total_amount = total;
+        Ok(())
+    }
+
+    /// Fail with CapExceeded if the proposed totals pass the run's caps
+    pub fn check_caps(&self, total_recipients: u32, total_amount: u64) -> Result<()> {
+        require!(
+            self.max_recipients == 0 || total_recipients <= self.max_recipients,
+            ErrorCode::CapExceeded
+        );
+        require!(
+            self.max_amount == 0 || total_amount <= self.max_amount,
+            ErrorCode::CapExceeded
+        );
+        Ok(())
+    }
+}
+
+impl SubRun {
+    /// Count one credited recipient and add its amounts to the sub-run totals.
+    /// Either all totals are updated or none are.
+    pub fn add_credit(&mut self, amounts: &TokenAmounts) -> Result<()> {
+        let recipients = self
+            .total_recipients
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        let xnm = self
+            .total_xnm_amount
+            .checked_add(amounts.xnm)
+            .ok_or(ErrorCode::Overflow)?;
+        let xblk = self
+            .total_xblk_amount
+            .checked_add(amounts.xblk)
+            .ok_or(ErrorCode::Overflow)?;
+        let xuni = self
+            .total_xuni_amount
+            .checked_add(amounts.xuni)
+            .ok_or(ErrorCode::Overflow)?;
+        let native = self
+            .total_native_amount
+            .checked_add(amounts.native)
+            .ok_or(ErrorCode::Overflow)?;
+
+        self.total_recipients = recipients;
+        self.total_xnm_amount = xnm;
+        self.total_xblk_amount = xblk;
+        self.total_xuni_amount = xuni;
+        self.total_native_amount = native;
+        Ok(())
+    }
+}
+
+impl AirdropRecordV2 {
+    /// Set every field of a freshly created record to its default
+    pub fn initialize(&mut self, eth_address: [u8; 42], bump: u8, now: i64) {
+        self.eth_address = eth_address;
+        self.xnm_airdropped = 0;
+        self.xblk_airdropped = 0;
+        self.xuni_airdropped = 0;
+        self.native_airdropped = 0;
+        self.xnm_claimed = 0;
+        self.xblk_claimed = 0;
+        self.xuni_claimed = 0;
+        self.native_claimed = 0;
+        self.last_updated = now;
+        self.bump = bump;
+        self.imported = false;
+        self.last_credited_season = 0;
+        self.ata_ready = false;
+        self.created_in_run = 0;
+        self.last_credited_run = 0;
+        self.sol_wallet = Pubkey::default();
+        self.acknowledged = false;
+        self.forfeited = false;
+        self.xnm_updated_at = 0;
+        self.xblk_updated_at = 0;
+        self.xuni_updated_at = 0;
+        self.native_updated_at = 0;
+        self.last_batch_id = [0u8; 16];
+        self.last_delta_xnm = 0;
+        self.last_delta_xblk = 0;
+        self.last_delta_xuni = 0;
+        self.last_delta_native = 0;
+        self.score = 0;
+        self.score_amount = 0;
+        self.preferred_token = None;
+        self.tag = [0u8; 32];
+        self.claim_deadline = 0;
+        self.claimed = false;
+        self.locked_until = 0;
+        self.version = RECORD_VERSION;
+        self.created_at = now;
+        self.frozen = false;
+        self.nonce = 0;
+        self.last_credited_at = 0;
+        self.xnm_claimable = 0;
+        self.xblk_claimable = 0;
+        self.xuni_claimable = 0;
+        self.native_claimable = 0;
+    }
+
+    /// Bring an older record up to RECORD_VERSION. Returns false if it was
+    /// already current, so repeated calls leave the data untouched.
+    pub fn migrate(&mut self) -> bool {
+        if self.version >= RECORD_VERSION {
+            return false;
+        }
+        if self.version < 2 {
+            // Reserved slots were never written for payouts; start clean
+            self.xnm_claimed = 0;
+            self.xblk_claimed = 0;
+            self.xuni_claimed = 0;
+            self.native_claimed = 0;
+        }
+        self.version = RECORD_VERSION;
+        true
+    }
+
+    /// Granted but not yet claimed amount of `token`
+    pub fn unclaimed(&self, token: TokenType) -> u64 {
+        match token {
+            TokenType::Xnm => self.xnm_claimable,
+            TokenType::Xblk => self.xblk_claimable,
+            TokenType::Xuni => self.xuni_claimable,
+            TokenType::Native => self.native_claimable,
+        }
+    }
+
+    /// Add `amount` of `token` to the entitlement claim pays out
+    pub fn add_claimable(&mut self, token: TokenType, amount: u64) -> Result<()> {
+        let claimable = match token {
+            TokenType::Xnm => &mut self.xnm_claimable,
+            TokenType::Xblk => &mut self.xblk_claimable,
+            TokenType::Xuni => &mut self.xuni_claimable,
+            TokenType::Native => &mut self.native_claimable,
+        };
+        *claimable = claimable.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        self.claimed = false;
+        Ok(())
+    }
+
+    /// Record a payout of `token`: move it out of the claimable entitlement
+    /// into the claimed and airdropped amounts, and set `claimed` once
+    /// nothing is left
+    pub fn settle_claim(&mut self, token: TokenType, amount: u64) -> Result<()> {
+        let (claimable, claimed, airdropped) = match token {
+            TokenType::Xnm => (
+                &mut self.xnm_claimable,
+                &mut self.xnm_claimed,
+                &mut self.xnm_airdropped,
+            ),
+            TokenType::Xblk => (
+                &mut self.xblk_claimable,
+                &mut self.xblk_claimed,
+                &mut self.xblk_airdropped,
+            ),
+            TokenType::Xuni => (
+                &mut self.xuni_claimable,
+                &mut self.xuni_claimed,
+                &mut self.xuni_airdropped,
+            ),
+            TokenType::Native => (
+                &mut self.native_claimable,
+                &mut self.native_claimed,
+                &mut self.native_airdropped,
+            ),
+        };
+        *claimable = claimable.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
+        *claimed = claimed.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        *airdropped = airdropped.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        self.claimed = [
+            TokenType::Xnm,
+            TokenType::Xblk,
+            TokenType::Xuni,
+            TokenType::Native,
+        ]
+        .iter()
+        .all(|token| self.unclaimed(*token) == 0);
+        Ok(())
+    }
+
+    /// Sum of all four token amounts, widened to u128
+    pub fn grand_total(&self) -> Result<u128> {
+        let total = (self.xnm_airdropped as u128)
+            .checked_add(self.xblk_airdropped as u128)
+            .and_then(|t| t.checked_add(self.xuni_airdropped as u128))
+            .and_then(|t| t.checked_add(self.native_airdropped as u128))
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(total)
+    }
+
+    /// Fail with RecordTimeLocked while a time lock is in force
+    pub fn check_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.locked_until, ErrorCode::RecordTimeLocked);
+        Ok(())
+    }
+
+    /// Fail with NonceMismatch unless `expected` is the current nonce
+    pub fn check_nonce(&self, expected: u64) -> Result<()> {
+        require!(self.nonce == expected, ErrorCode::NonceMismatch);
+        Ok(())
+    }
+
+    /// Bump the nonce after any change to the record's amounts
+    pub fn advance_nonce(&mut self) -> Result<()> {
+        self.nonce = self.nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Whether no amount has ever been credited to this record
+    pub fn is_empty(&self) -> bool {
+        self.xnm_airdropped == 0
+            && self.xblk_airdropped == 0
+            && self.xuni_airdropped == 0
+            && self.native_airdropped == 0
+    }
+
+    /// Add credited amounts to the record's cumulative totals.
+    /// Either all four totals are updated or none are.
+    pub fn add_amounts(
+        &mut self,
+        xnm_amount: u64,
+        xblk_amount: u64,
+        xuni_amount: u64,
+        native_amount: u64,
+    ) -> Result<()> {
+        let xnm = self
+            .xnm_airdropped
+            .checked_add(xnm_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let xblk = self
+            .xblk_airdropped
+            .checked_add(xblk_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let xuni = self
+            .xuni_airdropped
+            .checked_add(xuni_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let native = self
+            .native_airdropped
+            .checked_add(native_amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        self.xnm_airdropped = xnm;
+        self.xblk_airdropped = xblk;
+        self.xuni_airdropped = xuni;
+        self.native_airdropped = native;
+        Ok(())
+    }
+
+    /// Subtract amounts from the record's cumulative totals.
+    /// Either all four totals are updated or none are.
+    pub fn sub_amounts(
+        &mut self,
+        xnm_amount: u64,
+        xblk_amount: u64,
+        xuni_amount: u64,
+        native_amount: u64,
+    ) -> Result<()> {
+        let xnm = self
+            .xnm_airdropped
+            .checked_sub(xnm_amount)
+            .ok_or(ErrorCode::Underflow)?;
+        let xblk = self
+            .xblk_airdropped
+            .checked_sub(xblk_amount)
+            .ok_or(ErrorCode::Underflow)?;
+        let xuni = self
+            .xuni_airdropped
+            .checked_sub(xuni_amount)
+            .ok_or(ErrorCode::Underflow)?;
+        let native = self
+            .native_airdropped
+            .checked_sub(native_amount)
+            .ok_or(ErrorCode::Underflow)?;
+
+        self.xnm_airdropped = xnm;
+        self.xblk_airdropped = xblk;
+        self.xuni_airdropped = xuni;
+        self.native_airdropped = native;
+        Ok(())
+    }
+}
+
+/// Id of `run`, or 0 when a record is created outside any run
+fn run_id_or_zero(run: Option<&AirdropRunV2>) -> u64 {
+    run.map_or(0, |run| run.run_id)
+}
+
+/// Credit amounts to a record and the global totals, and emit RecordUpdated.
+///
+/// Every instruction that adds to a record's balances goes through here so
+/// program-wide accounting stays in one place.
+pub fn credit_record(
+    state: &mut GlobalStateV2,
+    record: &mut AirdropRecordV2,
+    amounts: &TokenAmounts,
+    run: Option<&AirdropRunV2>,
+    batch_id: [u8; 16],
+    now: i64,
+) -> Result<()> {
+    apply_credit(state, record, amounts, run, batch_id, now)?;
+    emit_record_updated(record, now);
+    Ok(())
+}
+
+/// Body of credit_record without the event, for simulate_batch. Credits
+/// within a dry run are exempt from the distribution window.
+fn apply_credit(
+    state: &mut GlobalStateV2,
+    record: &mut AirdropRecordV2,
+    amounts: &TokenAmounts,
+    run: Option<&AirdropRunV2>,
+    batch_id: [u8; 16],
+    now: i64,
+) -> Result<()> {
+    if state.require_ack {
+        require!(record.acknowledged, ErrorCode::TermsNotAccepted);
+    }
+    if !run.is_some_and(|run| run.dry_run) {
+        require!(
+            state.distribution_open(now),
+            ErrorCode::DistributionWindowClosed
+        );
+    }
+
+    require!(!state.paused, ErrorCode::ProgramPaused);
+    require!(!state.retired, ErrorCode::ProgramRetired);
+    require!(!record.frozen, ErrorCode::RecordFrozen);
+    record.check_unlocked(now)?;
+    state.check_update_interval(record, now)?;
+    record.add_amounts(amounts.xnm, amounts.xblk, amounts.xuni, amounts.native)?;
+    record.advance_nonce()?;
+    if state.max_lifetime_per_record != 0 {
+        require!(
+            record.grand_total()? <= state.max_lifetime_per_record as u128,
+            ErrorCode::LifetimeCapExceeded
+        );
+    }
+    record.last_updated = now;
+    record.last_credited_at = now;
+    if amounts.xnm > 0 {
+        record.xnm_updated_at = now;
+    }
+    if amounts.xblk > 0 {
+        record.xblk_updated_at = now;
+    }
+    if amounts.xuni > 0 {
+        record.xuni_updated_at = now;
+    }
+    if amounts.native > 0 {
+        record.native_updated_at = now;
+    }
+    record.last_credited_season = state.current_season;
+    record.last_credited_run = run.map_or(state.run_counter, |run| run.run_id);
+    record.last_batch_id = batch_id;
+    record.last_delta_xnm = amounts.xnm;
+    record.last_delta_xblk = amounts.xblk;
+    record.last_delta_xuni = amounts.xuni;
+    record.last_delta_native = amounts.native;
+
+    state.add_totals(amounts.xnm, amounts.xblk, amounts.xuni, amounts.native)
+}
+
+/// Emit RecordUpdated for the credit just applied to `record`
+fn emit_record_updated(record: &AirdropRecordV2, now: i64) {
+    emit!(RecordUpdated {
+        sol_wallet: record.sol_wallet,
+        eth_address: record.eth_address,
+        xnm_amount: record.last_delta_xnm,
+        xblk_amount: record.last_delta_xblk,
+        xuni_amount: record.last_delta_xuni,
+        native_amount: record.last_delta_native,
+        run_id: record.last_credited_run,
+        batch_id: record.last_batch_id,
+        timestamp: now,
+    });
+}
+
+/// Whether `total` fits under `cap`, where a cap of 0 means uncapped
+fn within_cap(total: u64, cap: u64) -> bool {
+    cap == 0 || total <= cap
+}
+
+/// Amount still distributable under `cap`; u64::MAX when uncapped
+fn remaining_under_cap(distributed: u64, cap: u64) -> u64 {
+    if cap == 0 {
+        u64::MAX
+    } else {
+        cap.saturating_sub(distributed)
+    }
+}
+
+/// Basis points denominator (10_000 bps = 100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// `value * bps / 10_000` with u128 intermediate math
+pub fn apply_bps(value: u64, bps: u64) -> Result<u64> {
+    let result = (value as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(result).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+/// Whether per-token split basis points add up to exactly BPS_DENOMINATOR
+pub fn split_bps_valid(xnm: u16, xblk: u16, xuni: u16, native: u16) -> bool {
+    xnm as u64 + xblk as u64 + xuni as u64 + native as u64 == BPS_DENOMINATOR
+}
+
+/// Whether `eth_address` is "0x" followed by 40 hex digits (either case)
+pub fn validate_eth_address(eth_address: &[u8; 42]) -> bool {
+    eth_address[..2] == *b"0x" && eth_address[2..].iter().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Recover the 20-byte ETH address that produced a 65-byte `r || s || v`
+/// personal_sign signature over `message` (EIP-191 prefixed)
+pub fn recover_eth_address(signature: &[u8; 65], message: &[u8]) -> Option<[u8; 20]> {
+    let recovery_id = match signature[64] {
+        v @ 27..=28 => v - 27,
+        v @ 0..=1 => v,
+        _ => return None,
+    };
+    let length = message.len().to_string();
+    let hash = keccak::hashv(&[
+        b"\x19Ethereum Signed Message:\n",
+        length.as_bytes(),
+        message,
+    ]);
+    let pubkey = secp256k1_recover(&hash.to_bytes(), recovery_id, &signature[..64]).ok()?;
+    let digest = keccak::hashv(&[&pubkey.to_bytes()]).to_bytes();
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&digest[12..]);
+    Some(address)
+}
+
+/// The message a recipient personal_signs to prove they own `eth_address`.
+/// It names this program and the Solana wallet being linked, so a signature
+/// can't be replayed against another deployment or wallet, and the address
+/// is lowercased so every spelling of it signs the same text.
+pub fn eth_proof_message(eth_address: &[u8; 42], sol_wallet: &Pubkey) -> String {
+    format!(
+        "{}\nProgram: {}\nETH address: {}\nSolana wallet: {}",
+        ETH_PROOF_DOMAIN,
+        crate::ID,
+        String::from_utf8_lossy(eth_address).to_ascii_lowercase(),
+        sol_wallet
+    )
+}
+
+/// True when the stored "0x"-prefixed hex `eth_address` spells `address`,
+/// ignoring case (so checksummed and lowercase forms both match)
+pub fn eth_address_matches(eth_address: &[u8; 42], address: &[u8; 20]) -> bool {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    address.iter().enumerate().all(|(i, byte)| {
+        eth_address[2 + 2 * i].eq_ignore_ascii_case(&HEX[(byte >> 4) as usize])
+            && eth_address[3 + 2 * i].eq_ignore_ascii_case(&HEX[(byte & 0x0f) as usize])
+    })
+}
+
+/// Decode a validated "0x"-prefixed hex `eth_address` into its 20 bytes, so
+/// every spelling of one address (any mix of case) yields the same key
+pub fn eth_address_bytes(eth_address: &[u8; 42]) -> [u8; 20] {
+    let nibble = |c: u8| (c as char).to_digit(16).unwrap_or(0) as u8;
+    let mut address = [0u8; 20];
+    for (i, byte) in address.iter_mut().enumerate() {
+        *byte = nibble(eth_address[2 + 2 * i]) << 4 | nibble(eth_address[3 + 2 * i]);
+    }
+    address
+}
+
+/// True for obviously-fake addresses: every hex digit after "0x" is the same
+/// character, or the address is one of `KNOWN_TEST_ETH_ADDRESSES`
+pub fn is_test_eth_address(eth_address: &[u8; 42]) -> bool {
+    let hex = &eth_address[2..];
+    if hex.iter().all(|c| c.eq_ignore_ascii_case(&hex[0])) {
+        return true;
+    }
+    KNOWN_TEST_ETH_ADDRESSES
+        .iter()
+        .any(|known| hex.eq_ignore_ascii_case(&known[..]))
+}
+
+/// Merkle leaf for an eligible eth_address: keccak256 of its 42 stored bytes
+pub fn eligibility_leaf(eth_address: &[u8; 42]) -> [u8; 32] {
+    keccak::hashv(&[eth_address]).to_bytes()
+}
+
+/// Merkle leaf for an allocation: keccak256 of the wallet's 32 bytes, the 42
+/// stored eth_address bytes and the amount as 8 big-endian bytes (matching
+/// `abi.encodePacked(bytes32, bytes, uint64)`)
+pub fn allocation_leaf(sol_wallet: &Pubkey, eth_address: &[u8; 42], amount: u64) -> [u8; 32] {
+    keccak::hashv(&[sol_wallet.as_ref(), eth_address, &amount.to_be_bytes()]).to_bytes()
+}
+
+/// Verify a merkle proof using sorted-pair keccak256 hashing
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
+
+/// Drop amounts for tokens the recipient opted out of, when the deployment
+/// respects preferences
+fn preferred_amounts(
+    state: &GlobalStateV2,
+    record: &AirdropRecordV2,
+    amounts: &TokenAmounts,
+) -> TokenAmounts {
+    match record.preferred_token {
+        Some(token) if state.respect_preference => {
+            let filtered = TokenAmounts::single(token, amounts.get(token));
+            if filtered != *amounts {
+                msg!("Skipping tokens other than preferred {:?}", token);
+            }
+            filtered
+        }
+        _ => amounts.clone(),
+    }
+}
+
+/// Shared body of the update_record_* and credit_lump_sum instructions
+fn update_record_amounts(
+    state: &mut GlobalStateV2,
+    record: &mut AirdropRecordV2,
+    run: Option<&AirdropRunV2>,
+    blacklist: &AccountInfo,
+    amounts: &TokenAmounts,
+    allow_recredit: bool,
+    batch_id: [u8; 16],
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    check_not_blacklisted(blacklist)?;
+    let amounts = &preferred_amounts(state, record, amounts);
+    state.check_update_amounts(amounts.xnm, amounts.xblk, amounts.xuni, amounts.native)?;
+    if let Some(run) = run {
+        run.check_credit(amounts)?;
+    }
+    let run_id = run.map(|run| run.run_id);
+    if state.dedup_per_run && !allow_recredit {
+        require!(
+            record.last_credited_run != run_id.unwrap_or(state.run_counter),
+            ErrorCode::AlreadyCreditedThisRun
+        );
+    }
+
+    credit_record(state, record, amounts, run, batch_id, now)?;
+
+    msg!(
+        "Updated V2 airdrop record: xnm={}, xblk={}, xuni={}, native={}",
+        amounts.xnm,
+        amounts.xblk,
+        amounts.xuni,
+        amounts.native
+    );
+    Ok(())
+}
+
+/// Fail with WalletBlacklisted if `entry`, the Blacklist PDA of the wallet in
+/// question, exists. Callers pass the PDA whether or not it exists, so a
+/// blacklisted wallet can't be credited by leaving its entry out.
+fn check_not_blacklisted(entry: &AccountInfo) -> Result<()> {
+    require_keys_neq!(*entry.owner, crate::ID, ErrorCode::WalletBlacklisted);
+    Ok(())
+}
+
+/// Verify that a remaining_accounts entry is the Blacklist PDA of `wallet`,
+/// then check it like check_not_blacklisted
+fn check_blacklist_entry(wallet: &Pubkey, entry: &AccountInfo) -> Result<()> {
+    let (expected, _) = Pubkey::find_program_address(&[b"blacklist", wallet.as_ref()], &crate::ID);
+    require_keys_eq!(expected, entry.key(), ErrorCode::InvalidBlacklistAccount);
+    check_not_blacklisted(entry)
+}
+
+/// Load an AllowedWallet from a remaining_accounts entry, or None if the
+/// account doesn't exist. check_wallet compares its wallet to the record's,
+/// so an entry for another wallet is rejected there.
+fn load_allowed_wallet<'info>(
+    entry: &'info AccountInfo<'info>,
+) -> Result<Option<Account<'info, AllowedWallet>>> {
+    if *entry.owner != crate::ID {
+        return Ok(None);
+    }
+    Ok(Some(Account::try_from(entry)?))
+}
+
+/// Verify owner, discriminator and PDA of a record that may still use an
+/// older, shorter layout (and so cannot be deserialized yet)
+fn verify_raw_record(info: &AccountInfo) -> Result<()> {
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidRecordAccount);
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() > RECORD_BUMP_OFFSET && data[..8] == *AirdropRecordV2::DISCRIMINATOR,
+        ErrorCode::InvalidRecordAccount
+    );
+    let expected = Pubkey::create_program_address(
+        &[
+            b"airdrop_record_v2",
+            &data[8..29],
+            &data[29..50],
+            &[data[RECORD_BUMP_OFFSET]],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(ErrorCode::InvalidRecordAccount))?;
+    require_keys_eq!(expected, info.key(), ErrorCode::InvalidRecordAccount);
+    Ok(())
+}
+
+/// Emit RecordClosed with the record's final balances
+fn emit_record_closed(record: &Account<AirdropRecordV2>, reason: CloseReason) {
+    emit!(RecordClosed {
+        record: record.key(),
+        eth_address: record.eth_address,
+        reason,
+        xnm_airdropped: record.xnm_airdropped,
+        xblk_airdropped: record.xblk_airdropped,
+        xuni_airdropped: record.xuni_airdropped,
+        native_airdropped: record.native_airdropped,
+    });
+}
+
+/// Resize a program account up to `target_len`, topping up rent from `payer`
+/// and zeroing the new tail. No-op if the account is already large enough.
+fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system: &Program<'info, System>,
+    target_len: usize,
+) -> Result<()> {
+    let current_len = info.data_len();
+    if current_len >= target_len {
+        msg!("Account already {} bytes, nothing to grow", current_len);
+        return Ok(());
+    }
+
+    let required = Rent::get()?
+        .minimum_balance(target_len)
+        .saturating_sub(info.lamports());
+    if required > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            required,
+        )?;
+    }
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + AirdropRecordV2::INIT_SPACE,
-        seeds = [
+    info.resize(target_len)?;
+    info.try_borrow_mut_data()?[current_len..].fill(0);
+
+    msg!("Grew account from {} to {} bytes", current_len, target_len);
+    Ok(())
+}
+
+/// Create the record PDA for `eth_address` at `info`, funded by `payer`.
+/// Returns the canonical bump, or None if the record already exists. Like
+/// Anchor's `init`, this tolerates lamports sent to the address beforehand.
+fn create_record_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system: &Program<'info, System>,
+    eth_address: &[u8; 42],
+) -> Result<Option<u8>> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[
             b"airdrop_record_v2",
             &eth_address[..21],
             &eth_address[21..42],
         ],
-        bump
-    )]
-    pub airdrop_record: Account<'info, AirdropRecordV2>,
+        &crate::ID,
+    );
+    require_keys_eq!(expected, info.key(), ErrorCode::InvalidRecordAccount);
+    if *info.owner == crate::ID {
+        return Ok(None);
+    }
+    require!(
+        info.is_writable && *info.owner == system_program::ID && info.data_is_empty(),
+        ErrorCode::InvalidRecordAccount
+    );
 
-    pub system_program: Program<'info, System>,
+    create_pda_account(
+        info,
+        payer,
+        system,
+        &[
+            b"airdrop_record_v2",
+            &eth_address[..21],
+            &eth_address[21..42],
+            &[bump],
+        ],
+        8 + AirdropRecordV2::INIT_SPACE,
+    )?;
+    Ok(Some(bump))
 }
 
-#[derive(Accounts)]
-pub struct UpdateRecordV2<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+/// Create a `space`-byte account owned by this program at the PDA `info`,
+/// signing with `seeds` (bump included) and funded by `payer`. Tolerates
+/// lamports sent to the address beforehand.
+fn create_pda_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system: &Program<'info, System>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    let signer = &[seeds];
+    let rent = Rent::get()?.minimum_balance(space);
 
-    #[account(
-        mut,
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+    if info.lamports() == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system.to_account_info(),
+                system_program::CreateAccount {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+                signer,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        )?;
+        return Ok(());
+    }
 
-    #[account(
-        mut,
-        seeds = [
+    let required = rent.saturating_sub(info.lamports());
+    if required > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            required,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system.to_account_info(),
+            system_program::Allocate {
+                account_to_allocate: info.clone(),
+            },
+            signer,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system.to_account_info(),
+            system_program::Assign {
+                account_to_assign: info.clone(),
+            },
+            signer,
+        ),
+        &crate::ID,
+    )?;
+    Ok(())
+}
+
+/// Register `eth_address` by creating its EthRegistry PDA at `info`, pointing
+/// at `record`. Fails with DuplicateEthAddress if the address is already
+/// registered under any spelling.
+fn create_eth_registry<'info>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system: &Program<'info, System>,
+    eth_address: &[u8; 42],
+    record: Pubkey,
+    sol_wallet: Pubkey,
+) -> Result<()> {
+    let key = eth_address_bytes(eth_address);
+    let (expected, bump) = Pubkey::find_program_address(&[b"eth", &key], &crate::ID);
+    require_keys_eq!(expected, info.key(), ErrorCode::InvalidRegistryAccount);
+    require_keys_neq!(*info.owner, crate::ID, ErrorCode::DuplicateEthAddress);
+    require!(info.is_writable, ErrorCode::InvalidRegistryAccount);
+
+    create_pda_account(
+        info,
+        payer,
+        system,
+        &[b"eth", &key, &[bump]],
+        8 + EthRegistry::INIT_SPACE,
+    )?;
+    let registry = EthRegistry {
+        record,
+        sol_wallet,
+        bump,
+    };
+    let mut data = info.try_borrow_mut_data()?;
+    registry.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Deserialize a record passed via remaining_accounts and verify it is the
+/// canonical PDA for its eth_address
+pub fn load_record<'info>(
+    info: &'info AccountInfo<'info>,
+) -> Result<Account<'info, AirdropRecordV2>> {
+    let record: Account<'info, AirdropRecordV2> = Account::try_from(info)?;
+    let expected = Pubkey::create_program_address(
+        &[
             b"airdrop_record_v2",
-            &airdrop_record.eth_address[..21],
-            &airdrop_record.eth_address[21..42],
+            &record.eth_address[..21],
+            &record.eth_address[21..42],
+            &[record.bump],
         ],
-        bump = airdrop_record.bump
-    )]
-    pub airdrop_record: Account<'info, AirdropRecordV2>,
+        &crate::ID,
+    )
+    .map_err(|_| error!(ErrorCode::InvalidRecordAccount))?;
+    require_keys_eq!(expected, info.key(), ErrorCode::InvalidRecordAccount);
+    Ok(record)
 }
 
-#[derive(Accounts)]
-pub struct InitializeStateV2<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + GlobalStateV2::INIT_SPACE,
-        seeds = [b"state_v2"],
-        bump
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+/// Apply one batch_update_records entry to a record passed via remaining_accounts
+fn apply_record_update<'info>(
+    state: &mut GlobalStateV2,
+    info: &'info AccountInfo<'info>,
+    blacklist: &AccountInfo,
+    update: &RecordUpdate,
+    batch_id: [u8; 16],
+    now: i64,
+) -> Result<()> {
+    let record = credit_from_update(state, info, blacklist, update, batch_id, now)?;
+    emit_record_updated(&record, now);
 
-    pub system_program: Program<'info, System>,
+    record.exit(&crate::ID)
 }
 
-#[derive(Accounts)]
-pub struct UpdateAuthority<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+/// Validate a batch entry's record account, load it and credit the entry to
+/// it in memory, without persisting the record or emitting RecordUpdated.
+/// Shared by batch_update_records and simulate_batch, so a simulation
+/// rejects exactly the accounts the real batch would.
+fn credit_from_update<'info>(
+    state: &mut GlobalStateV2,
+    info: &'info AccountInfo<'info>,
+    blacklist: &AccountInfo,
+    update: &RecordUpdate,
+    batch_id: [u8; 16],
+    now: i64,
+) -> Result<Account<'info, AirdropRecordV2>> {
+    require!(info.is_writable, ErrorCode::InvalidRecordAccount);
+    let mut record = load_record(info)?;
+    record.check_nonce(update.expected_nonce)?;
+    check_blacklist_entry(&record.sol_wallet, blacklist)?;
+    let amounts = preferred_amounts(
+        state,
+        &record,
+        &TokenAmounts {
+            xnm: update.xnm_amount,
+            xblk: update.xblk_amount,
+            xuni: update.xuni_amount,
+            native: update.native_amount,
+        },
+    );
+    state.check_update_amounts(amounts.xnm, amounts.xblk, amounts.xuni, amounts.native)?;
 
-    #[account(
-        mut,
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+    apply_credit(state, &mut record, &amounts, None, batch_id, now)?;
+    Ok(record)
 }
 
-#[derive(Accounts)]
-pub struct InitializeLock<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+// ============================================================================
+// Instruction Data
+// ============================================================================
 
-    #[account(
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+/// Amounts to add to one record in `batch_update_records`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RecordUpdate {
+    pub xnm_amount: u64,
+    pub xblk_amount: u64,
+    pub xuni_amount: u64,
+    pub native_amount: u64,
+    /// Must equal the record's current nonce
+    pub expected_nonce: u64,
+}
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + AirdropLock::INIT_SPACE,
-        seeds = [b"lock"],
-        bump
-    )]
-    pub lock: Account<'info, AirdropLock>,
+/// One new record in `batch_initialize_and_update`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct NewRecord {
+    pub eth_address: [u8; 42],
+    pub sol_wallet: Pubkey,
+    pub xnm_amount: u64,
+    pub xblk_amount: u64,
+    pub xuni_amount: u64,
+    pub native_amount: u64,
+    pub score: u64,
+}
 
-    pub system_program: Program<'info, System>,
+/// Named per-token amounts, so clients can't swap positional arguments
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct TokenAmounts {
+    pub xnm: u64,
+    pub xblk: u64,
+    pub xuni: u64,
+    pub native: u64,
 }
 
-#[derive(Accounts)]
-pub struct AcquireLock<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+impl TokenAmounts {
+    /// Amounts with only `token` set
+    pub fn single(token: TokenType, amount: u64) -> Self {
+        let mut amounts = TokenAmounts {
+            xnm: 0,
+            xblk: 0,
+            xuni: 0,
+            native: 0,
+        };
+        match token {
+            TokenType::Xnm => amounts.xnm = amount,
+            TokenType::Xblk => amounts.xblk = amount,
+            TokenType::Xuni => amounts.xuni = amount,
+            TokenType::Native => amounts.native = amount,
+        }
+        amounts
+    }
 
-    #[account(
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+    /// Scale whole-token amounts to base units (`* 10^decimals`)
+    pub fn to_base_units(&self, decimals: u8) -> Result<Self> {
+        let scale = 10u64
+            .checked_pow(decimals as u32)
+            .ok_or(ErrorCode::Overflow)?;
+        let scaled = |amount: u64| amount.checked_mul(scale).ok_or(ErrorCode::Overflow);
+        Ok(TokenAmounts {
+            xnm: scaled(self.xnm)?,
+            xblk: scaled(self.xblk)?,
+            xuni: scaled(self.xuni)?,
+            native: scaled(self.native)?,
+        })
+    }
 
-    #[account(
-        mut,
-        seeds = [b"lock"],
-        bump = lock.bump
-    )]
-    pub lock: Account<'info, AirdropLock>,
+    /// Amount for one token
+    pub fn get(&self, token: TokenType) -> u64 {
+        match token {
+            TokenType::Xnm => self.xnm,
+            TokenType::Xblk => self.xblk,
+            TokenType::Xuni => self.xuni,
+            TokenType::Native => self.native,
+        }
+    }
 }
 
-#[derive(Accounts)]
-pub struct ReleaseLock<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+/// Lifecycle status of an AirdropRunV2
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunStatus {
+    /// Created; totals may still be updated
+    Active,
+    /// Finalized via finalize_run or auto_finalize
+    Completed,
+    /// Abandoned via abort_run
+    Aborted,
+}
 
-    #[account(
-        seeds = [b"state_v2"],
-        bump = state.bump,
-        constraint = state.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub state: Account<'info, GlobalStateV2>,
+/// Why a record was closed, reported in RecordClosed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseReason {
+    /// Recipient forfeited the allocation
+    Forfeit,
+    /// Record duplicated another recipient's
+    Duplicate,
+    /// Record was migrated to a new account
+    Migration,
+    /// Routine cleanup (e.g. dust)
+    Cleanup,
+    /// Closed by the recipient via self_close_record
+    Recipient,
+}
 
-    #[account(
-        mut,
-        seeds = [b"lock"],
-        bump = lock.bump
-    )]
-    pub lock: Account<'info, AirdropLock>,
+/// Token tracked on a record
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenType {
+    Xnm,
+    Xblk,
+    Xuni,
+    Native,
 }
 
 // ============================================================================
-// Account Structs
+// View Types
 // ============================================================================
 
-#[account]
-#[derive(InitSpace)]
-pub struct GlobalStateV2 {
-    /// Schema version (set to 1)
-    pub version: u8, // 1 byte
+/// Layout version of `ConfigView`, bumped whenever fields are added
+pub const CONFIG_VIEW_VERSION: u8 = 1;
+
+/// Program configuration returned by `get_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigView {
+    /// Layout version of this view (see `CONFIG_VIEW_VERSION`)
+    pub view_version: u8,
+    /// GlobalState schema version
+    pub state_version: u8,
     /// Authority who can create runs and update records
-    pub authority: Pubkey, // 32 bytes
-    /// Counter for run IDs
-    pub run_counter: u64, // 8 bytes
-    /// Cumulative XNM airdropped across all records
-    pub xnm_airdropped: u64, // 8 bytes
-    /// Cumulative XBLK airdropped across all records
-    pub xblk_airdropped: u64, // 8 bytes
-    /// Cumulative XUNI airdropped across all records
-    pub xuni_airdropped: u64, // 8 bytes
-    /// Cumulative native (XNT) airdropped across all records
-    pub native_airdropped: u64, // 8 bytes
-    /// Reserved space for future use
-    pub reserved: [u64; 4], // 32 bytes
-    /// PDA bump
-    pub bump: u8, // 1 byte
+    pub authority: Pubkey,
+    /// Authority proposed via propose_authority (default = none)
+    pub pending_authority: Pubkey,
+    /// Whether record writes are paused
+    pub paused: bool,
+    /// Lifetime per-token caps (0 = uncapped)
+    pub max_total_xnm: u64,
+    pub max_total_xblk: u64,
+    pub max_total_xuni: u64,
+    pub max_total_native: u64,
+    /// Distribution window bounds (0 = unbounded)
+    pub distribution_start: i64,
+    pub distribution_end: i64,
+    /// CLUSTER_MODE_DEVNET or CLUSTER_MODE_MAINNET
+    pub cluster_mode: u8,
+    /// Decimals used to scale whole-token amounts
+    pub decimals: u8,
+    /// Mints paid out by claim (xnm, xblk, xuni, native)
+    pub token_mints: [Pubkey; 4],
+    /// Co-signers for sensitive instructions (unused slots = default)
+    pub signers: [Pubkey; MAX_SIGNERS],
+    /// Co-signer approvals required (0 = authority only)
+    pub threshold: u8,
+    /// Eligibility merkle root (all zeros = unset)
+    pub merkle_root: [u8; 32],
+    /// Allocation merkle root (all zeros = unset)
+    pub allocation_root: [u8; 32],
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct AirdropRunV2 {
-    /// Schema version (set to 1)
-    pub version: u8, // 1 byte
-    /// Unique run ID
-    pub run_id: u64, // 8 bytes
-    /// Unix timestamp when run started
-    pub run_date: i64, // 8 bytes
+/// Caps, intervals and distribution window returned by `get_limits`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LimitsView {
+    /// Lifetime per-token caps (0 = uncapped)
+    pub max_total_xnm: u64,
+    pub max_total_xblk: u64,
+    pub max_total_xuni: u64,
+    pub max_total_native: u64,
+    /// Ceiling on a record's lifetime total across tokens
+    pub max_lifetime_per_record: u64,
+    /// Maximum number of runs (0 = unlimited)
+    pub max_runs: u64,
+    /// Minimum seconds between non-dry runs
+    pub min_interval_between_runs: i64,
+    /// Minimum seconds between credits to the same existing record
+    pub min_update_interval: i64,
+    /// Distribution window bounds (0 = unbounded)
+    pub distribution_start: i64,
+    pub distribution_end: i64,
+    /// Lump-sum split in basis points (xnm, xblk, xuni, native)
+    pub split_bps: [u16; 4],
+}
+
+/// Outcome of `batch_update_records`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchUpdateResult {
+    /// Number of entries applied
+    pub applied: u8,
+    /// Indexes of entries skipped in best-effort mode
+    pub failed: Vec<u8>,
+}
+
+/// Outcome of `batch_initialize_and_update`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchInitResult {
+    /// Number of records created and credited
+    pub created: u8,
+    /// Indexes of entries whose record already existed
+    pub skipped: Vec<u8>,
+}
+
+/// Per-token amounts still distributable, returned by `remaining_budget`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RemainingBudget {
+    pub xnm: u64,
+    pub xblk: u64,
+    pub xuni: u64,
+    pub native: u64,
+}
+
+/// Run address and status returned by `run_info`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RunInfo {
+    /// Run PDA
+    pub pda: Pubkey,
+    /// Canonical PDA bump
+    pub bump: u8,
+    /// Whether the run account has been created
+    pub exists: bool,
+    /// Lifecycle status of the run
+    pub status: RunStatus,
+    /// Whether the run is a dry run
+    pub dry_run: bool,
+}
+
+/// Per-token totals for one ETH address, returned by `sum_by_eth`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EthTotals {
+    /// Number of records summed
+    pub records: u8,
+    pub xnm: u64,
+    pub xblk: u64,
+    pub xuni: u64,
+    pub native: u64,
+}
+
+/// Operational status returned by `health_check`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct HealthView {
+    /// Record creation and credits are paused
+    pub paused: bool,
+    /// Program has been permanently retired
+    pub retired: bool,
+    /// GlobalState schema version
+    pub version: u8,
+}
+
+/// Program-wide totals returned by `get_state_summary`. Return data is 40
+/// bytes, five little-endian u64s in field order:
+///
+/// | offset | field             |
+/// |--------|-------------------|
+/// | 0      | run_counter       |
+/// | 8      | xnm_airdropped    |
+/// | 16     | xblk_airdropped   |
+/// | 24     | xuni_airdropped   |
+/// | 32     | native_airdropped |
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StateSummary {
+    /// Number of runs created so far
+    pub run_counter: u64,
+    pub xnm_airdropped: u64,
+    pub xblk_airdropped: u64,
+    pub xuni_airdropped: u64,
+    pub native_airdropped: u64,
+}
+
+/// Per-token run totals returned by `get_run_token_totals`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RunTokenTotals {
+    pub xnm_total: u64,
+    pub xblk_total: u64,
+    pub xuni_total: u64,
+    pub native_total: u64,
     /// Number of successful recipients
-    pub total_recipients: u32, // 4 bytes
-    /// Total combined amount airdropped (preserved from v1)
-    pub total_amount: u64, // 8 bytes
-    /// Total XNM amount airdropped
-    pub total_xnm_amount: u64, // 8 bytes
-    /// Total XBLK amount airdropped
-    pub total_xblk_amount: u64, // 8 bytes
-    /// Total XUNI amount airdropped
-    pub total_xuni_amount: u64, // 8 bytes
-    /// Total native (XNT) amount airdropped
-    pub total_native_amount: u64, // 8 bytes
-    /// Whether this was a dry run
-    pub dry_run: bool, // 1 byte
-    /// Reserved space for future use
-    pub reserved: [u64; 4], // 32 bytes
-    /// PDA bump
-    pub bump: u8, // 1 byte
+    pub recipients: u32,
+    /// Lifecycle status of the run
+    pub status: RunStatus,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct AirdropRecordV2 {
-    /// The associated ETH address (as UTF-8 bytes, e.g., "0x1234...")
-    pub eth_address: [u8; 42], // 42 bytes
-    /// Cumulative XNM amount airdropped (in token base units, 9 decimals)
-    pub xnm_airdropped: u64, // 8 bytes
-    /// Cumulative XBLK amount airdropped (in token base units, 9 decimals)
-    pub xblk_airdropped: u64, // 8 bytes
-    /// Cumulative XUNI amount airdropped (in token base units, 9 decimals)
-    pub xuni_airdropped: u64, // 8 bytes
-    /// Cumulative native token (XNT) airdropped (in lamports, 9 decimals)
-    pub native_airdropped: u64, // 8 bytes
-    /// Reserved space for future use (8 bytes each * 4 = 32 bytes)
-    pub reserved: [u64; 4], // 32 bytes
-    /// Unix timestamp of last update
-    pub last_updated: i64, // 8 bytes
-    /// PDA bump seed for derivation
-    pub bump: u8, // 1 byte
+/// Administrative roles and toggles returned by `get_admin_state`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AdminStateView {
+    /// Authority who can create runs, update records and change config
+    pub authority: Pubkey,
+    /// Destination for rent swept by close_and_sweep
+    pub treasury: Pubkey,
+    /// Keys allowed to write records: the authority plus delegated operators
+    pub operator_count: u8,
+    /// The authority followed by every delegated operator
+    pub operators: Vec<Pubkey>,
+    /// Record creation and credits are paused
+    pub paused: bool,
+    /// Program has been permanently retired
+    pub retired: bool,
+    pub dedup_per_run: bool,
+    pub require_ack: bool,
+    pub wallet_allowlist_enabled: bool,
+    pub require_reference: bool,
+    pub reject_zero_updates: bool,
+    pub max_runs_exempt_dry: bool,
+    pub enforce_system_wallet: bool,
+    pub respect_preference: bool,
+    pub require_snapshot_block: bool,
+    pub allow_self_close: bool,
+    pub enforce_monotonic_run_dates: bool,
+    pub require_eth_proof: bool,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct AirdropLock {
-    /// Public key of the current lock holder
-    pub lock_holder: Pubkey, // 32 bytes
-    /// Unix timestamp when the lock was acquired
-    pub locked_at: i64, // 8 bytes
-    /// Lock timeout duration in seconds
-    pub timeout_seconds: i64, // 8 bytes
-    /// Associated run ID (set after create_run for audit trail)
-    pub run_id: u64, // 8 bytes
-    /// PDA bump
-    pub bump: u8, // 1 byte
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct RecordImported {
+    pub sol_wallet: Pubkey,
+    pub eth_address: [u8; 42],
+    pub xnm_amount: u64,
+    pub xblk_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecordCountReconciled {
+    pub old_count: u64,
+    pub new_count: u64,
+}
+
+#[event]
+pub struct UpdateRescinded {
+    pub eth_address: [u8; 42],
+    pub xnm_amount: u64,
+    pub xblk_amount: u64,
+    pub xuni_amount: u64,
+    pub native_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchTouched {
+    pub count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TotalsReconciled {
+    pub records: u64,
+    pub old_xnm: u64,
+    pub old_xblk: u64,
+    pub old_xuni: u64,
+    pub old_native: u64,
+    pub new_xnm: u64,
+    pub new_xblk: u64,
+    pub new_xuni: u64,
+    pub new_native: u64,
+    pub old_record_count: u64,
+}
+
+#[event]
+pub struct ProgramCheckpoint {
+    pub version: u8,
+    pub run_counter: u64,
+    pub record_count: u64,
+    pub xnm_airdropped: u64,
+    pub xblk_airdropped: u64,
+    pub xuni_airdropped: u64,
+    pub native_airdropped: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecordLocked {
+    pub record: Pubkey,
+    pub locked_until: i64,
+}
+
+#[event]
+pub struct RecordsStamped {
+    pub count: u8,
+    pub version: u8,
+}
+
+#[event]
+pub struct RecordClosed {
+    pub record: Pubkey,
+    pub eth_address: [u8; 42],
+    pub reason: CloseReason,
+    pub xnm_airdropped: u64,
+    pub xblk_airdropped: u64,
+    pub xuni_airdropped: u64,
+    pub native_airdropped: u64,
+}
+
+#[event]
+pub struct RecordUpdated {
+    pub sol_wallet: Pubkey,
+    pub eth_address: [u8; 42],
+    pub xnm_amount: u64,
+    pub xblk_amount: u64,
+    pub xuni_amount: u64,
+    pub native_amount: u64,
+    pub run_id: u64,
+    /// Off-chain distribution batch of the credit (zero = unspecified)
+    pub batch_id: [u8; 16],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RunCreated {
+    pub run_id: u64,
+    pub run_date: i64,
+    pub dry_run: bool,
+    /// Hash of the run's published announcement (zero = not yet set)
+    pub reference_hash: [u8; 32],
+}
+
+#[event]
+pub struct RunFeesRecorded {
+    pub run_id: u64,
+    pub fees_spent: u64,
+}
+
+#[event]
+pub struct RunFinalized {
+    pub run_id: u64,
+    pub fees_spent: u64,
+}
+
+#[event]
+pub struct RunTotalsUpdated {
+    pub run_id: u64,
+    pub total_recipients: u32,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct TokensClaimed {
+    pub record: Pubkey,
+    pub wallet: Pubkey,
+    pub token: TokenType,
+    pub amount: u64,
+    /// Whether every token is now fully claimed
+    pub fully_claimed: bool,
 }
 
 #[error_code]
@@ -571,4 +6452,150 @@ pub enum ErrorCode {
     InvalidTimeout,
     #[msg("Lock is not held by the caller")]
     LockNotHeld,
+    #[msg("Run total_amount is less than the sum of its per-token totals")]
+    InconsistentRunTotals,
+    #[msg("record_count exceeds MAX_RECONCILED_RECORD_COUNT")]
+    RecordCountTooHigh,
+    #[msg("Number of entries does not match the number of accounts")]
+    BatchLengthMismatch,
+    #[msg("Batch exceeds the maximum number of entries")]
+    BatchTooLarge,
+    #[msg("Account is not a writable AirdropRecordV2 PDA")]
+    InvalidRecordAccount,
+    #[msg("Cluster mode must be 0 (devnet) or 1 (mainnet)")]
+    InvalidClusterMode,
+    #[msg("Placeholder eth_address rejected in mainnet cluster mode")]
+    TestAddressRejected,
+    #[msg("Budget must be non-zero for non-dry runs")]
+    ZeroBudget,
+    #[msg("Credit would exceed the program-wide token cap")]
+    TokenCapExceeded,
+    #[msg("Record already has credited amounts and cannot be repaired")]
+    RecordAlreadyPopulated,
+    #[msg("Record reserved space is not zeroed")]
+    ReservedNotZeroed,
+    #[msg("Record was already credited in the current run")]
+    AlreadyCreditedThisRun,
+    #[msg("Signer is not the wallet linked to this record")]
+    WalletMismatch,
+    #[msg("Recipient has not acknowledged the campaign terms")]
+    TermsNotAccepted,
+    #[msg("Wallet is not on the destination allowlist")]
+    WalletNotAllowed,
+    #[msg("Run has no reference hash")]
+    MissingReference,
+    #[msg("Record has not been forfeited")]
+    NotForfeited,
+    #[msg("Treasury has not been set")]
+    TreasuryNotSet,
+    #[msg("Credit would exceed the per-record lifetime cap")]
+    LifetimeCapExceeded,
+    #[msg("Merkle root has not been set")]
+    MerkleRootNotSet,
+    #[msg("Too soon after the previous run")]
+    RunCooldownActive,
+    #[msg("Interval must not be negative")]
+    InvalidInterval,
+    #[msg("Arithmetic underflow when updating total")]
+    Underflow,
+    #[msg("Record has no update to rescind")]
+    NoUpdateToRescind,
+    #[msg("Update amounts are all zero")]
+    ZeroAmountUpdate,
+    #[msg("Maximum number of runs reached")]
+    MaxRunsReached,
+    #[msg("Record eth_address does not match the requested address")]
+    EthAddressMismatch,
+    #[msg("Wallet must be a system-owned account")]
+    InvalidWalletOwner,
+    #[msg("Non-dry runs must specify a snapshot block")]
+    SnapshotBlockRequired,
+    #[msg("Two distinct records share the same eth_address")]
+    DuplicateEthAddress,
+    #[msg("Distribution start must not be after its end")]
+    InvalidDistributionWindow,
+    #[msg("Outside the distribution window")]
+    DistributionWindowClosed,
+    #[msg("Retirement confirmation does not match")]
+    RetireNotConfirmed,
+    #[msg("Program has been retired")]
+    ProgramRetired,
+    #[msg("eth_address must be 0x followed by 40 hex digits")]
+    InvalidEthAddress,
+    #[msg("Claim deadline must be in the future")]
+    InvalidClaimDeadline,
+    #[msg("Self-close is not enabled")]
+    SelfCloseDisabled,
+    #[msg("Record has not been claimed")]
+    NotClaimed,
+    #[msg("Records must be passed in ascending key order past the cursor")]
+    ReconcileOutOfOrder,
+    #[msg("Run date would precede the previous run")]
+    NonMonotonicRunDate,
+    #[msg("Record is time-locked")]
+    RecordTimeLocked,
+    #[msg("Run already has the maximum number of sub-runs")]
+    TooManySubRuns,
+    #[msg("Sub-runs must all be passed in index order")]
+    SubRunMismatch,
+    #[msg("Record total is not below the dust threshold")]
+    NotDust,
+    #[msg("Split basis points must sum to 10000")]
+    RatioMisconfigured,
+    #[msg("Run is completed or aborted")]
+    RunClosed,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Mint does not match the configured mint for this token")]
+    MintMismatch,
+    #[msg("Claim deadline has passed")]
+    ClaimDeadlinePassed,
+    #[msg("Nothing left to claim for this token")]
+    NothingToClaim,
+    #[msg("Record has been forfeited")]
+    RecordForfeited,
+    #[msg("Merkle proof does not match the eligibility root")]
+    InvalidProof,
+    #[msg("Run totals exceed the run's recipient or amount cap")]
+    CapExceeded,
+    #[msg("Run is still active; finalize or abort it first")]
+    RunStillActive,
+    #[msg("Signer set must be at most 5 distinct keys covering the threshold")]
+    InvalidSignerSet,
+    #[msg("Dry runs cannot credit non-zero amounts")]
+    DryRunWriteBlocked,
+    #[msg("Record is frozen")]
+    RecordFrozen,
+    #[msg("Record was updated too recently")]
+    UpdateTooSoon,
+    #[msg("ETH signature does not match eth_address")]
+    EthSignatureInvalid,
+    #[msg("expected_nonce does not match the record's nonce")]
+    NonceMismatch,
+    #[msg("Wallet is blacklisted")]
+    WalletBlacklisted,
+    #[msg("Wallet is not an on-curve account")]
+    InvalidWallet,
+    #[msg("Account is not the Blacklist PDA of the record's wallet")]
+    InvalidBlacklistAccount,
+    #[msg("Account is not the EthRegistry PDA of the record's eth_address")]
+    InvalidRegistryAccount,
+    #[msg("Retiring requires at least 2 co-signer signatures")]
+    InsufficientSignersForRetire,
+    #[msg("Reconciliation has not scanned every record")]
+    ReconcileIncomplete,
+    #[msg("The same account was passed more than once")]
+    DuplicateAccount,
+    #[msg("Operator accounts must be every Operator PDA, each exactly once")]
+    OperatorSetMismatch,
+    #[msg("Token decimals have not been set")]
+    DecimalsNotSet,
+    #[msg("Decimals exceed MAX_DECIMALS")]
+    InvalidDecimals,
+    #[msg("Run has sub-runs; credit it through update_record_and_subrun")]
+    RunHasSubRuns,
+    #[msg("Run date is in the future")]
+    RunDateInFuture,
+    #[msg("Record was not created in this run")]
+    NotCreatedInRun,
 }
@@ -32,8 +32,8 @@ import {
   deriveAirdropRunPDA,
 } from '../src/onchain/pda.js';
 import {
-  AIRDROP_RECORD_V2_SIZE,
-  AIRDROP_RUN_V2_SIZE,
+  AIRDROP_RECORD_V2_DISCRIMINATOR,
+  AIRDROP_RUN_V2_DISCRIMINATOR,
 } from '../src/onchain/types.js';
 
 dotenv.config();
@@ -88,7 +88,15 @@ async function main() {
   // --- 1. Close all AirdropRecordV2 accounts ---
   console.log('\n--- Closing AirdropRecordV2 accounts ---');
   const recordAccounts = await connection.getProgramAccounts(programId, {
-    filters: [{ dataSize: AIRDROP_RECORD_V2_SIZE }],
+    filters: [
+      {
+        memcmp: {
+          offset: 0,
+          bytes: AIRDROP_RECORD_V2_DISCRIMINATOR.toString('base64'),
+          encoding: 'base64',
+        },
+      },
+    ],
   });
   console.log(`Found ${recordAccounts.length} record(s)`);
 
@@ -118,7 +126,15 @@ async function main() {
   // --- 2. Close all AirdropRunV2 accounts ---
   console.log('\n--- Closing AirdropRunV2 accounts ---');
   const runAccounts = await connection.getProgramAccounts(programId, {
-    filters: [{ dataSize: AIRDROP_RUN_V2_SIZE }],
+    filters: [
+      {
+        memcmp: {
+          offset: 0,
+          bytes: AIRDROP_RUN_V2_DISCRIMINATOR.toString('base64'),
+          encoding: 'base64',
+        },
+      },
+    ],
   });
   console.log(`Found ${runAccounts.length} run(s)`);
 
@@ -24,6 +24,7 @@ import {
 } from '../solana/transfer.js';
 import {
   fetchAllMultiTokenSnapshots,
+  fetchBlacklistedWallets,
   makeSnapshotKey,
   createOnChainRunV2,
   updateOnChainRunTotalsV2,
@@ -402,6 +403,20 @@ export async function executeAirdrop(
       miners = allMiners;
     }
 
+    // Skip wallets on the on-chain blacklist
+    const blacklisted = await fetchBlacklistedWallets(
+      connection,
+      config.airdropTrackerProgramId
+    );
+    if (blacklisted.size > 0) {
+      const before = miners.length;
+      miners = miners.filter((m) => !blacklisted.has(m.solAddress));
+      logger.info(
+        { skipped: before - miners.length },
+        'Skipped blacklisted wallets'
+      );
+    }
+
     // Fetch on-chain snapshots
     logger.info('Fetching on-chain snapshots...');
     const snapshots = await fetchAllMultiTokenSnapshots(
@@ -505,6 +520,7 @@ export async function executeAirdrop(
       xnmConfig,
       xblkConfig,
       xuniConfig,
+      runId,
       deltas,
       snapshots,
       (result) => {
@@ -589,11 +605,12 @@ async function processSingleRecipient(
   xnmConfig: TokenConfig,
   xblkConfig: TokenConfig,
   xuniConfig: TokenConfig,
+  runId: bigint,
   delta: MultiTokenDelta,
-  hasExistingRecord: boolean
+  snapshot: OnChainSnapshot | undefined
 ): Promise<MultiTokenAirdropResult> {
   // Build single record update instruction for all tokens (including native)
-  const recordInstruction = hasExistingRecord
+  const recordInstruction = snapshot
     ? createUpdateRecordInstruction(
         config.airdropTrackerProgramId,
         payer.publicKey,
@@ -601,12 +618,20 @@ async function processSingleRecipient(
         delta.xnmDelta,
         delta.xblkDelta,
         delta.xuniDelta,
-        delta.nativeAmount
+        delta.nativeAmount,
+        false,
+        Buffer.alloc(16),
+        runId,
+        false,
+        snapshot.nonce,
+        snapshot.solWallet
       )
     : createInitializeAndUpdateInstruction(
         config.airdropTrackerProgramId,
         payer.publicKey,
+        runId,
         delta.ethAddress,
+        new PublicKey(delta.walletAddress),
         delta.xnmDelta,
         delta.xblkDelta,
         delta.xuniDelta,
@@ -714,6 +739,7 @@ async function processMultiTokenAirdrops(
   xnmConfig: TokenConfig,
   xblkConfig: TokenConfig,
   xuniConfig: TokenConfig,
+  runId: bigint,
   deltas: MultiTokenDelta[],
   snapshots: Map<string, OnChainSnapshot>,
   onResult: (result: MultiTokenAirdropResult) => void
@@ -790,7 +816,6 @@ async function processMultiTokenAirdrops(
     // Process batch concurrently
     const batchPromises = batch.map((delta) => {
       const snapshotKey = makeSnapshotKey(delta.ethAddress);
-      const hasExistingRecord = snapshots.has(snapshotKey);
 
       return processSingleRecipient(
         connection,
@@ -799,8 +824,9 @@ async function processMultiTokenAirdrops(
         xnmConfig,
         xblkConfig,
         xuniConfig,
+        runId,
         delta,
-        hasExistingRecord
+        snapshots.get(snapshotKey)
       );
     });
 
@@ -1,3 +1,5 @@
+import { PublicKey } from '@solana/web3.js';
+
 export interface Miner {
   account: string; // ETH address
   solAddress: string; // Solana wallet address
@@ -39,6 +41,10 @@ export interface OnChainSnapshot {
   xuniAirdropped: bigint;
   /** Native token (XNT) airdropped amount (from reserved[0]) */
   nativeAirdropped: bigint;
+  /** Record nonce, quoted as expected_nonce when crediting the record */
+  nonce: bigint;
+  /** Record's sol_wallet, whose blacklist PDA accompanies every credit */
+  solWallet: PublicKey;
 }
 
 /** Multi-token airdrop result for a single recipient */
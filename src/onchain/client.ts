@@ -12,20 +12,53 @@ import {
   deriveGlobalStatePDA,
   deriveAirdropRunPDA,
   deriveAirdropLockPDA,
+  deriveOperatorPDA,
+  deriveBlacklistPDA,
+  deriveAllowedWalletPDA,
+  deriveEthRegistryPDA,
   ethAddressToBytes,
 } from './pda.js';
 import {
   AIRDROP_RECORD_V2_OFFSETS,
-  AIRDROP_RECORD_V2_SIZE,
+  AIRDROP_RECORD_V2_DISCRIMINATOR,
+  AIRDROP_RECORD_V2_NONCE_OFFSET,
+  AIRDROP_RECORD_V2_SOL_WALLET_OFFSET,
   GLOBAL_STATE_V2_OFFSETS,
   AIRDROP_RUN_V2_OFFSETS,
   AIRDROP_LOCK_OFFSETS,
+  BLACKLIST_DISCRIMINATOR,
+  BLACKLIST_OFFSETS,
   AirdropRecordV2,
   AirdropLock,
   GlobalStateV2,
+  NewRecord,
   OnChainAirdropRunV2,
+  RecordUpdate,
 } from './types.js';
 
+/**
+ * Key for an optional account at the end of an instruction's account list.
+ * Anchor requires the slot to be present and reads the program ID as None.
+ */
+function optionalAccount(programId: PublicKey, pubkey?: PublicKey) {
+  return { pubkey: pubkey ?? programId, isSigner: false, isWritable: false };
+}
+
+/**
+ * Operator account slot: the signer's Operator PDA when it writes as a
+ * delegated operator, otherwise None
+ */
+function operatorAccount(
+  programId: PublicKey,
+  authority: PublicKey,
+  asOperator: boolean
+) {
+  return optionalAccount(
+    programId,
+    asOperator ? deriveOperatorPDA(programId, authority)[0] : undefined
+  );
+}
+
 // ============================================================================
 // Deserialization Functions
 // ============================================================================
@@ -54,12 +87,12 @@ export function deserializeGlobalState(data: Buffer): GlobalStateV2 {
   const nativeAirdropped = data.readBigUInt64LE(
     GLOBAL_STATE_V2_OFFSETS.NATIVE_AIRDROPPED
   );
-  const reserved: bigint[] = [];
-  for (let i = 0; i < 4; i++) {
-    reserved.push(
-      data.readBigUInt64LE(GLOBAL_STATE_V2_OFFSETS.RESERVED + i * 8)
-    );
-  }
+  const pendingAuthority = new PublicKey(
+    data.slice(
+      GLOBAL_STATE_V2_OFFSETS.PENDING_AUTHORITY,
+      GLOBAL_STATE_V2_OFFSETS.BUMP
+    )
+  );
   const bump = data.readUInt8(GLOBAL_STATE_V2_OFFSETS.BUMP);
 
   return {
@@ -70,7 +103,7 @@ export function deserializeGlobalState(data: Buffer): GlobalStateV2 {
     xblkAirdropped,
     xuniAirdropped,
     nativeAirdropped,
-    reserved,
+    pendingAuthority,
     bump,
   };
 }
@@ -137,12 +170,21 @@ export function deserializeAirdropRecordV2(data: Buffer): AirdropRecordV2 {
     AIRDROP_RECORD_V2_OFFSETS.NATIVE_AIRDROPPED
   );
 
-  const reserved: bigint[] = [];
-  for (let i = 0; i < 4; i++) {
-    reserved.push(
-      data.readBigUInt64LE(AIRDROP_RECORD_V2_OFFSETS.RESERVED + i * 8)
-    );
-  }
+  const xnmClaimed = data.readBigUInt64LE(
+    AIRDROP_RECORD_V2_OFFSETS.XNM_CLAIMED
+  );
+
+  const xblkClaimed = data.readBigUInt64LE(
+    AIRDROP_RECORD_V2_OFFSETS.XBLK_CLAIMED
+  );
+
+  const xuniClaimed = data.readBigUInt64LE(
+    AIRDROP_RECORD_V2_OFFSETS.XUNI_CLAIMED
+  );
+
+  const nativeClaimed = data.readBigUInt64LE(
+    AIRDROP_RECORD_V2_OFFSETS.NATIVE_CLAIMED
+  );
 
   const lastUpdated = data.readBigInt64LE(
     AIRDROP_RECORD_V2_OFFSETS.LAST_UPDATED
@@ -150,15 +192,35 @@ export function deserializeAirdropRecordV2(data: Buffer): AirdropRecordV2 {
 
   const bump = data.readUInt8(AIRDROP_RECORD_V2_OFFSETS.BUMP);
 
+  const solWallet =
+    data.length >= AIRDROP_RECORD_V2_SOL_WALLET_OFFSET + 32
+      ? new PublicKey(
+          data.subarray(
+            AIRDROP_RECORD_V2_SOL_WALLET_OFFSET,
+            AIRDROP_RECORD_V2_SOL_WALLET_OFFSET + 32
+          )
+        )
+      : PublicKey.default;
+
+  const nonce =
+    data.length >= AIRDROP_RECORD_V2_NONCE_OFFSET + 8
+      ? data.readBigUInt64LE(AIRDROP_RECORD_V2_NONCE_OFFSET)
+      : 0n;
+
   return {
     ethAddress,
     xnmAirdropped,
     xblkAirdropped,
     xuniAirdropped,
     nativeAirdropped,
-    reserved,
+    xnmClaimed,
+    xblkClaimed,
+    xuniClaimed,
+    nativeClaimed,
     lastUpdated,
     bump,
+    solWallet,
+    nonce,
   };
 }
 
@@ -303,7 +365,7 @@ export function makeSnapshotKey(ethAddress: string): string {
 
 /**
  * Fetch on-chain snapshots for all miners in batch (all tokens)
- * Returns a Map of ethAddress -> { xnmAirdropped, xblkAirdropped, xuniAirdropped, nativeAirdropped }
+ * Returns a Map of ethAddress -> { xnmAirdropped, xblkAirdropped, xuniAirdropped, nativeAirdropped, nonce, solWallet }
  */
 export async function fetchAllMultiTokenSnapshots(
   connection: Connection,
@@ -316,6 +378,8 @@ export async function fetchAllMultiTokenSnapshots(
       xblkAirdropped: bigint;
       xuniAirdropped: bigint;
       nativeAirdropped: bigint;
+      nonce: bigint;
+      solWallet: PublicKey;
     }
   >
 > {
@@ -326,11 +390,23 @@ export async function fetchAllMultiTokenSnapshots(
       xblkAirdropped: bigint;
       xuniAirdropped: bigint;
       nativeAirdropped: bigint;
+      nonce: bigint;
+      solWallet: PublicKey;
     }
   >();
 
+  // Match on the discriminator rather than dataSize: records created before
+  // newer fields were appended are shorter than the current layout.
   const accounts = await connection.getProgramAccounts(programId, {
-    filters: [{ dataSize: AIRDROP_RECORD_V2_SIZE }],
+    filters: [
+      {
+        memcmp: {
+          offset: 0,
+          bytes: AIRDROP_RECORD_V2_DISCRIMINATOR.toString('base64'),
+          encoding: 'base64',
+        },
+      },
+    ],
   });
 
   for (const { account } of accounts) {
@@ -344,6 +420,8 @@ export async function fetchAllMultiTokenSnapshots(
         xblkAirdropped: record.xblkAirdropped,
         xuniAirdropped: record.xuniAirdropped,
         nativeAirdropped: record.nativeAirdropped,
+        nonce: record.nonce,
+        solWallet: record.solWallet,
       });
     } catch {
       // Skip malformed accounts
@@ -353,6 +431,37 @@ export async function fetchAllMultiTokenSnapshots(
   return snapshots;
 }
 
+/**
+ * Fetch every wallet with a Blacklist account, as base58 strings
+ */
+export async function fetchBlacklistedWallets(
+  connection: Connection,
+  programId: PublicKey
+): Promise<Set<string>> {
+  const accounts = await connection.getProgramAccounts(programId, {
+    filters: [
+      {
+        memcmp: {
+          offset: 0,
+          bytes: BLACKLIST_DISCRIMINATOR.toString('base64'),
+          encoding: 'base64',
+        },
+      },
+    ],
+  });
+
+  return new Set(
+    accounts.map(({ account }) =>
+      new PublicKey(
+        account.data.subarray(
+          BLACKLIST_OFFSETS.WALLET,
+          BLACKLIST_OFFSETS.WALLET + 32
+        )
+      ).toBase58()
+    )
+  );
+}
+
 // ============================================================================
 // Instruction Builders
 // ============================================================================
@@ -381,13 +490,19 @@ export function createInitializeStateV2Instruction(
 }
 
 /**
- * Create instruction to create a new airdrop run
+ * Create instruction to create a new airdrop run. `referenceHash` is the
+ * 32-byte hash of the run's published announcement, if already known.
  */
 export function createCreateRunV2Instruction(
   programId: PublicKey,
   authority: PublicKey,
   nextRunId: bigint,
-  dryRun: boolean
+  dryRun: boolean,
+  snapshotBlock: bigint = 0n,
+  payer: PublicKey = authority,
+  maxRecipients: number = 0,
+  maxAmount: bigint = 0n,
+  referenceHash?: Buffer
 ): TransactionInstruction {
   const [state] = deriveGlobalStatePDA(programId);
   const [airdropRun] = deriveAirdropRunPDA(programId, nextRunId);
@@ -395,13 +510,25 @@ export function createCreateRunV2Instruction(
   // Anchor discriminator for "create_run_v2"
   const discriminator = Buffer.from([26, 236, 217, 25, 54, 95, 138, 75]);
 
-  const data = Buffer.alloc(discriminator.length + 1);
-  discriminator.copy(data, 0);
-  data.writeUInt8(dryRun ? 1 : 0, 8);
+  // dry_run (1 byte) + snapshot_block (8 bytes) + max_recipients (4 bytes)
+  // + max_amount (8 bytes); zero caps mean no cap
+  const args = Buffer.alloc(1 + 8 + 4 + 8);
+  args.writeUInt8(dryRun ? 1 : 0, 0);
+  args.writeBigUInt64LE(snapshotBlock, 1);
+  args.writeUInt32LE(maxRecipients, 9);
+  args.writeBigUInt64LE(maxAmount, 13);
+
+  // reference_hash (Option<[u8; 32]>)
+  const referenceOption = referenceHash
+    ? Buffer.concat([Buffer.from([1]), referenceHash])
+    : Buffer.from([0]);
+
+  const data = Buffer.concat([discriminator, args, referenceOption]);
 
   return new TransactionInstruction({
     keys: [
-      { pubkey: authority, isSigner: true, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: state, isSigner: false, isWritable: true },
       { pubkey: airdropRun, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
@@ -423,7 +550,9 @@ export function createUpdateRunTotalsV2Instruction(
   totalXnmAmount: bigint,
   totalXblkAmount: bigint,
   totalXuniAmount: bigint,
-  totalNativeAmount: bigint
+  totalNativeAmount: bigint,
+  autoFinalize: boolean = false,
+  coSigners: PublicKey[] = []
 ): TransactionInstruction {
   const [state] = deriveGlobalStatePDA(programId);
   const [airdropRun] = deriveAirdropRunPDA(programId, runId);
@@ -431,8 +560,8 @@ export function createUpdateRunTotalsV2Instruction(
   // Anchor discriminator for "update_run_totals_v2"
   const discriminator = Buffer.from([188, 197, 94, 210, 219, 102, 141, 240]);
 
-  // total_recipients (4) + total_amount (8) + xnm (8) + xblk (8) + xuni (8) + native (8)
-  const data = Buffer.alloc(discriminator.length + 4 + 8 + 8 + 8 + 8 + 8);
+  // total_recipients (4) + total_amount (8) + xnm (8) + xblk (8) + xuni (8) + native (8) + auto_finalize (1)
+  const data = Buffer.alloc(discriminator.length + 4 + 8 + 8 + 8 + 8 + 8 + 1);
   discriminator.copy(data, 0);
   data.writeUInt32LE(totalRecipients, 8);
   data.writeBigUInt64LE(totalAmount, 12);
@@ -440,12 +569,19 @@ export function createUpdateRunTotalsV2Instruction(
   data.writeBigUInt64LE(totalXblkAmount, 28);
   data.writeBigUInt64LE(totalXuniAmount, 36);
   data.writeBigUInt64LE(totalNativeAmount, 44);
+  data.writeUInt8(autoFinalize ? 1 : 0, 52);
 
   return new TransactionInstruction({
     keys: [
       { pubkey: authority, isSigner: true, isWritable: true },
       { pubkey: state, isSigner: false, isWritable: false },
       { pubkey: airdropRun, isSigner: false, isWritable: true },
+      // Co-signers required by the state's signer threshold
+      ...coSigners.map((pubkey) => ({
+        pubkey,
+        isSigner: true,
+        isWritable: false,
+      })),
     ],
     programId,
     data,
@@ -453,28 +589,93 @@ export function createUpdateRunTotalsV2Instruction(
 }
 
 /**
- * Create instruction to initialize a new airdrop record
+ * The message a recipient personal_signs to prove they own `ethAddress`.
+ * Mirrors eth_proof_message in the program: it names the program and the
+ * wallet being linked, with the address lowercased.
+ */
+export function ethProofMessage(
+  programId: PublicKey,
+  ethAddress: string,
+  solWallet: PublicKey
+): Buffer {
+  return Buffer.from(
+    'XenBlocks airdrop: link ETH address\n' +
+      `Program: ${programId.toBase58()}\n` +
+      `ETH address: ${ethAddress.toLowerCase()}\n` +
+      `Solana wallet: ${solWallet.toBase58()}`
+  );
+}
+
+/**
+ * Create instruction to initialize a new airdrop record linked to
+ * `solWallet`. `ethSignature` is the recipient's 65-byte personal_sign
+ * signature over ethProofMessage, proving control of `ethAddress`. Set
+ * `allowlisted` to pass solWallet's AllowedWallet PDA, required while the
+ * wallet allowlist is enabled.
  */
 export function createInitializeRecordInstruction(
   programId: PublicKey,
   authority: PublicKey,
-  ethAddress: string
+  runId: bigint,
+  ethAddress: string,
+  solWallet: PublicKey,
+  score: bigint = 0n,
+  payer: PublicKey = authority,
+  ethSignature?: Buffer,
+  allowlisted: boolean = false
 ): TransactionInstruction {
   const [state] = deriveGlobalStatePDA(programId);
   const [airdropRecord] = deriveAirdropRecordPDA(programId, ethAddress);
+  const [airdropRun] = deriveAirdropRunPDA(programId, runId);
   const ethBytes = ethAddressToBytes(ethAddress);
 
   // Anchor discriminator for "initialize_record_v2"
   const discriminator = Buffer.from([9, 168, 75, 31, 120, 164, 180, 40]);
 
-  const data = Buffer.concat([discriminator, Buffer.from(ethBytes)]);
+  // eth_address (42 bytes) + sol_wallet (32 bytes) + score (8 bytes)
+  const scoreBuffer = Buffer.alloc(8);
+  scoreBuffer.writeBigUInt64LE(score);
+
+  // eth_signature (Option<[u8; 65]>)
+  const signatureOption = ethSignature
+    ? Buffer.concat([Buffer.from([1]), ethSignature])
+    : Buffer.from([0]);
+
+  const data = Buffer.concat([
+    discriminator,
+    Buffer.from(ethBytes),
+    solWallet.toBuffer(),
+    scoreBuffer,
+    signatureOption,
+  ]);
 
   return new TransactionInstruction({
     keys: [
-      { pubkey: authority, isSigner: true, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: state, isSigner: false, isWritable: true },
       { pubkey: airdropRecord, isSigner: false, isWritable: true },
+      { pubkey: airdropRun, isSigner: false, isWritable: false },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      {
+        pubkey: deriveBlacklistPDA(programId, solWallet)[0],
+        isSigner: false,
+        isWritable: false,
+      },
+      // sol_wallet account, read on-chain for its owner
+      optionalAccount(programId, solWallet),
+      // AllowedWallet PDA, required while the wallet allowlist is enabled
+      optionalAccount(
+        programId,
+        allowlisted
+          ? deriveAllowedWalletPDA(programId, solWallet)[0]
+          : undefined
+      ),
+      {
+        pubkey: deriveEthRegistryPDA(programId, ethAddress)[0],
+        isSigner: false,
+        isWritable: true,
+      },
     ],
     programId,
     data,
@@ -482,7 +683,8 @@ export function createInitializeRecordInstruction(
 }
 
 /**
- * Create instruction to update an existing airdrop record
+ * Create instruction to update an existing airdrop record. `solWallet` is
+ * the record's stored sol_wallet, used to derive its blacklist PDA.
  */
 export function createUpdateRecordInstruction(
   programId: PublicKey,
@@ -491,7 +693,13 @@ export function createUpdateRecordInstruction(
   xnmAmount: bigint,
   xblkAmount: bigint,
   xuniAmount: bigint,
-  nativeAmount: bigint = 0n
+  nativeAmount: bigint = 0n,
+  allowRecredit: boolean = false,
+  batchId: Buffer = Buffer.alloc(16),
+  runId?: bigint,
+  asOperator: boolean = false,
+  expectedNonce: bigint = 0n,
+  solWallet: PublicKey = PublicKey.default
 ): TransactionInstruction {
   const [state] = deriveGlobalStatePDA(programId);
   const [airdropRecord] = deriveAirdropRecordPDA(programId, ethAddress);
@@ -499,19 +707,171 @@ export function createUpdateRecordInstruction(
   // Anchor discriminator for "update_record_v2"
   const discriminator = Buffer.from([128, 80, 71, 187, 243, 5, 79, 128]);
 
-  // xnm_amount (8 bytes) + xblk_amount (8 bytes) + xuni_amount (8 bytes) + native_amount (8 bytes)
-  const data = Buffer.alloc(discriminator.length + 8 + 8 + 8 + 8);
+  // xnm_amount (8 bytes) + xblk_amount (8 bytes) + xuni_amount (8 bytes) + native_amount (8 bytes) + allow_recredit (1 byte) + batch_id (16 bytes) + expected_nonce (8 bytes)
+  const data = Buffer.alloc(
+    discriminator.length + 8 + 8 + 8 + 8 + 1 + 16 + 8
+  );
   discriminator.copy(data, 0);
   data.writeBigUInt64LE(xnmAmount, 8);
   data.writeBigUInt64LE(xblkAmount, 16);
   data.writeBigUInt64LE(xuniAmount, 24);
   data.writeBigUInt64LE(nativeAmount, 32);
+  data.writeUInt8(allowRecredit ? 1 : 0, 40);
+  batchId.copy(data, 41, 0, 16);
+  data.writeBigUInt64LE(expectedNonce, 57);
+
+  // Optional run the credit is attributed to (defaults to the latest run)
+  const airdropRun =
+    runId === undefined
+      ? undefined
+      : deriveAirdropRunPDA(programId, runId)[0];
 
   return new TransactionInstruction({
     keys: [
       { pubkey: authority, isSigner: true, isWritable: true },
       { pubkey: state, isSigner: false, isWritable: true },
       { pubkey: airdropRecord, isSigner: false, isWritable: true },
+      optionalAccount(programId, airdropRun),
+      operatorAccount(programId, authority, asOperator),
+      {
+        pubkey: deriveBlacklistPDA(programId, solWallet)[0],
+        isSigner: false,
+        isWritable: false,
+      },
+    ],
+    programId,
+    data,
+  });
+}
+
+/**
+ * Create instruction to apply amount updates to many existing records at once.
+ * Each record PDA and its wallet's blacklist PDA are passed as a pair of
+ * remaining accounts, in the same order as `updates`.
+ */
+export function createBatchUpdateRecordsInstruction(
+  programId: PublicKey,
+  authority: PublicKey,
+  updates: RecordUpdate[],
+  bestEffort: boolean = false,
+  batchId: Buffer = Buffer.alloc(16)
+): TransactionInstruction {
+  const [state] = deriveGlobalStatePDA(programId);
+
+  // Anchor discriminator for "batch_update_records"
+  const discriminator = Buffer.from([6, 103, 228, 248, 55, 219, 73, 208]);
+
+  // updates (4-byte length + 40 bytes each) + best_effort (1 byte) + batch_id (16 bytes)
+  const data = Buffer.alloc(
+    discriminator.length + 4 + updates.length * 40 + 1 + 16
+  );
+  discriminator.copy(data, 0);
+  data.writeUInt32LE(updates.length, 8);
+  let offset = 12;
+  for (const update of updates) {
+    data.writeBigUInt64LE(update.xnmAmount, offset);
+    data.writeBigUInt64LE(update.xblkAmount, offset + 8);
+    data.writeBigUInt64LE(update.xuniAmount, offset + 16);
+    data.writeBigUInt64LE(update.nativeAmount, offset + 24);
+    data.writeBigUInt64LE(update.expectedNonce, offset + 32);
+    offset += 40;
+  }
+  data.writeUInt8(bestEffort ? 1 : 0, offset);
+  batchId.copy(data, offset + 1, 0, 16);
+
+  const recordKeys = updates.flatMap((update) => [
+    {
+      pubkey: deriveAirdropRecordPDA(programId, update.ethAddress)[0],
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: deriveBlacklistPDA(programId, update.solWallet)[0],
+      isSigner: false,
+      isWritable: false,
+    },
+  ]);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: state, isSigner: false, isWritable: true },
+      ...recordKeys,
+    ],
+    programId,
+    data,
+  });
+}
+
+/**
+ * Create instruction to create and credit many new records at once.
+ * Each entry passes its record PDA, its eth_address's registry PDA, and its
+ * solWallet's blacklist PDA, wallet and AllowedWallet PDA as remaining
+ * accounts, in the same order as `records`; entries whose record or registry
+ * entry already exists are skipped on-chain.
+ */
+export function createBatchInitializeAndUpdateInstruction(
+  programId: PublicKey,
+  authority: PublicKey,
+  runId: bigint,
+  records: NewRecord[],
+  payer: PublicKey = authority
+): TransactionInstruction {
+  const [state] = deriveGlobalStatePDA(programId);
+  const [airdropRun] = deriveAirdropRunPDA(programId, runId);
+
+  // Anchor discriminator for "batch_initialize_and_update"
+  const discriminator = Buffer.from([52, 91, 228, 111, 135, 27, 112, 133]);
+
+  // records (4-byte length + 114 bytes each: eth_address, sol_wallet, four
+  // amounts, score)
+  const data = Buffer.alloc(discriminator.length + 4 + records.length * 114);
+  discriminator.copy(data, 0);
+  data.writeUInt32LE(records.length, 8);
+  let offset = 12;
+  for (const record of records) {
+    Buffer.from(ethAddressToBytes(record.ethAddress)).copy(data, offset);
+    record.solWallet.toBuffer().copy(data, offset + 42);
+    data.writeBigUInt64LE(record.xnmAmount, offset + 74);
+    data.writeBigUInt64LE(record.xblkAmount, offset + 82);
+    data.writeBigUInt64LE(record.xuniAmount, offset + 90);
+    data.writeBigUInt64LE(record.nativeAmount, offset + 98);
+    data.writeBigUInt64LE(record.score, offset + 106);
+    offset += 114;
+  }
+
+  const recordKeys = records.flatMap((record) => [
+    {
+      pubkey: deriveAirdropRecordPDA(programId, record.ethAddress)[0],
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: deriveEthRegistryPDA(programId, record.ethAddress)[0],
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: deriveBlacklistPDA(programId, record.solWallet)[0],
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: record.solWallet, isSigner: false, isWritable: false },
+    {
+      pubkey: deriveAllowedWalletPDA(programId, record.solWallet)[0],
+      isSigner: false,
+      isWritable: false,
+    },
+  ]);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: state, isSigner: false, isWritable: true },
+      { pubkey: airdropRun, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      ...recordKeys,
     ],
     programId,
     data,
@@ -519,25 +879,34 @@ export function createUpdateRecordInstruction(
 }
 
 /**
- * Create instruction to initialize and update a record in one call
+ * Create instruction to initialize and update a record in one call, linking
+ * it to `solWallet`. Set `allowlisted` to pass solWallet's AllowedWallet PDA.
  */
 export function createInitializeAndUpdateInstruction(
   programId: PublicKey,
   authority: PublicKey,
+  runId: bigint,
   ethAddress: string,
+  solWallet: PublicKey,
   xnmAmount: bigint,
   xblkAmount: bigint,
   xuniAmount: bigint,
-  nativeAmount: bigint = 0n
+  nativeAmount: bigint = 0n,
+  score: bigint = 0n,
+  payer: PublicKey = authority,
+  proof: Buffer[] = [],
+  asOperator: boolean = false,
+  allowlisted: boolean = false
 ): TransactionInstruction {
   const [state] = deriveGlobalStatePDA(programId);
   const [airdropRecord] = deriveAirdropRecordPDA(programId, ethAddress);
+  const [airdropRun] = deriveAirdropRunPDA(programId, runId);
   const ethBytes = ethAddressToBytes(ethAddress);
 
   // Anchor discriminator for "initialize_and_update_v2"
   const discriminator = Buffer.from([11, 96, 49, 240, 7, 7, 185, 214]);
 
-  // eth_address (42 bytes) + xnm_amount (8 bytes) + xblk_amount (8 bytes) + xuni_amount (8 bytes) + native_amount (8 bytes)
+  // eth_address (42 bytes) + xnm_amount (8 bytes) + xblk_amount (8 bytes) + xuni_amount (8 bytes) + native_amount (8 bytes) + score (8 bytes)
   const xnmBuffer = Buffer.alloc(8);
   xnmBuffer.writeBigUInt64LE(xnmAmount);
 
@@ -550,6 +919,14 @@ export function createInitializeAndUpdateInstruction(
   const nativeBuffer = Buffer.alloc(8);
   nativeBuffer.writeBigUInt64LE(nativeAmount);
 
+  const scoreBuffer = Buffer.alloc(8);
+  scoreBuffer.writeBigUInt64LE(score);
+
+  // sol_wallet (32 bytes) + proof (Vec<[u8; 32]>: u32 length + nodes),
+  // only checked on-chain once a merkle root is set
+  const proofLength = Buffer.alloc(4);
+  proofLength.writeUInt32LE(proof.length);
+
   const data = Buffer.concat([
     discriminator,
     Buffer.from(ethBytes),
@@ -557,14 +934,40 @@ export function createInitializeAndUpdateInstruction(
     xblkBuffer,
     xuniBuffer,
     nativeBuffer,
+    scoreBuffer,
+    solWallet.toBuffer(),
+    proofLength,
+    ...proof,
   ]);
 
   return new TransactionInstruction({
     keys: [
-      { pubkey: authority, isSigner: true, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: state, isSigner: false, isWritable: true },
       { pubkey: airdropRecord, isSigner: false, isWritable: true },
+      { pubkey: airdropRun, isSigner: false, isWritable: false },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      operatorAccount(programId, authority, asOperator),
+      {
+        pubkey: deriveBlacklistPDA(programId, solWallet)[0],
+        isSigner: false,
+        isWritable: false,
+      },
+      // sol_wallet account, read on-chain for its owner
+      optionalAccount(programId, solWallet),
+      // AllowedWallet PDA, required while the wallet allowlist is enabled
+      optionalAccount(
+        programId,
+        allowlisted
+          ? deriveAllowedWalletPDA(programId, solWallet)[0]
+          : undefined
+      ),
+      {
+        pubkey: deriveEthRegistryPDA(programId, ethAddress)[0],
+        isSigner: false,
+        isWritable: true,
+      },
     ],
     programId,
     data,
@@ -572,17 +975,18 @@ export function createInitializeAndUpdateInstruction(
 }
 
 /**
- * Create instruction to update the program authority
+ * Create instruction to propose a new program authority. The handover
+ * completes once the proposed key signs accept_authority.
  */
-export function createUpdateAuthorityInstruction(
+export function createProposeAuthorityInstruction(
   programId: PublicKey,
   authority: PublicKey,
   newAuthority: PublicKey
 ): TransactionInstruction {
   const [state] = deriveGlobalStatePDA(programId);
 
-  // Anchor discriminator for "update_authority"
-  const discriminator = Buffer.from([32, 46, 64, 28, 149, 75, 243, 88]);
+  // Anchor discriminator for "propose_authority"
+  const discriminator = Buffer.from([20, 148, 236, 198, 76, 119, 99, 142]);
 
   const data = Buffer.alloc(discriminator.length + 32);
   discriminator.copy(data, 0);
@@ -590,7 +994,7 @@ export function createUpdateAuthorityInstruction(
 
   return new TransactionInstruction({
     keys: [
-      { pubkey: authority, isSigner: true, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
       { pubkey: state, isSigner: false, isWritable: true },
     ],
     programId,
@@ -711,7 +1115,8 @@ export async function createOnChainRunV2(
   connection: Connection,
   programId: PublicKey,
   payer: Keypair,
-  dryRun: boolean
+  dryRun: boolean,
+  snapshotBlock: bigint = 0n
 ): Promise<{ runId: bigint; signature: string }> {
   const state = await getGlobalState(connection, programId);
   if (!state) {
@@ -724,7 +1129,13 @@ export async function createOnChainRunV2(
 
   const transaction = new Transaction();
   transaction.add(
-    createCreateRunV2Instruction(programId, payer.publicKey, nextRunId, dryRun)
+    createCreateRunV2Instruction(
+      programId,
+      payer.publicKey,
+      nextRunId,
+      dryRun,
+      snapshotBlock
+    )
   );
 
   const signature = await sendAndConfirmTransaction(
@@ -750,7 +1161,8 @@ export async function updateOnChainRunTotalsV2(
   totalXnmAmount: bigint,
   totalXblkAmount: bigint,
   totalXuniAmount: bigint,
-  totalNativeAmount: bigint
+  totalNativeAmount: bigint,
+  autoFinalize: boolean = false
 ): Promise<string> {
   const transaction = new Transaction();
   transaction.add(
@@ -763,7 +1175,8 @@ export async function updateOnChainRunTotalsV2(
       totalXnmAmount,
       totalXblkAmount,
       totalXuniAmount,
-      totalNativeAmount
+      totalNativeAmount,
+      autoFinalize
     )
   );
 
@@ -856,13 +1269,15 @@ export async function releaseLock(
 
 /**
  * Update on-chain record after a successful airdrop
- * Creates the record if it doesn't exist
+ * Creates the record, linked to `solWallet`, if it doesn't exist
  */
 export async function updateOnChainRecord(
   connection: Connection,
   programId: PublicKey,
   payer: Keypair,
+  runId: bigint,
   ethAddress: string,
+  solWallet: PublicKey,
   xnmAmount: bigint,
   xblkAmount: bigint,
   xuniAmount: bigint,
@@ -874,7 +1289,8 @@ export async function updateOnChainRecord(
   const transaction = new Transaction();
 
   if (accountInfo) {
-    // Record exists, just update
+    // Record exists, just update; quote its nonce so a replay can't land
+    const { nonce, solWallet } = deserializeAirdropRecordV2(accountInfo.data);
     transaction.add(
       createUpdateRecordInstruction(
         programId,
@@ -883,7 +1299,13 @@ export async function updateOnChainRecord(
         xnmAmount,
         xblkAmount,
         xuniAmount,
-        nativeAmount
+        nativeAmount,
+        false,
+        Buffer.alloc(16),
+        runId,
+        false,
+        nonce,
+        solWallet
       )
     );
   } else {
@@ -892,7 +1314,9 @@ export async function updateOnChainRecord(
       createInitializeAndUpdateInstruction(
         programId,
         payer.publicKey,
+        runId,
         ethAddress,
+        solWallet,
         xnmAmount,
         xblkAmount,
         xuniAmount,
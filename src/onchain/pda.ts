@@ -39,6 +39,74 @@ export function deriveAirdropRunPDA(
   );
 }
 
+/**
+ * Derive the PDA for a delegated operator
+ *
+ * Seeds: ["operator", operator pubkey]
+ */
+export function deriveOperatorPDA(
+  programId: PublicKey,
+  operator: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('operator'), operator.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive the PDA for a wallet's blacklist entry
+ *
+ * Seeds: ["blacklist", wallet pubkey]
+ */
+export function deriveBlacklistPDA(
+  programId: PublicKey,
+  wallet: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('blacklist'), wallet.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive the PDA allowlisting a destination wallet
+ *
+ * Seeds: ["allowed_wallet", wallet pubkey]
+ */
+export function deriveAllowedWalletPDA(
+  programId: PublicKey,
+  wallet: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('allowed_wallet'), wallet.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive the PDA registering an ETH address. The seed is the decoded
+ * 20-byte address, so every spelling of it maps to the same PDA.
+ *
+ * Seeds: ["eth", eth_address as 20 bytes]
+ */
+export function deriveEthRegistryPDA(
+  programId: PublicKey,
+  ethAddress: string
+): [PublicKey, number] {
+  const addressBytes = Buffer.from(ethAddress.slice(2), 'hex');
+  if (addressBytes.length !== 20) {
+    throw new Error(
+      `Invalid ETH address length: ${addressBytes.length}, expected 20 bytes`
+    );
+  }
+
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('eth'), addressBytes],
+    programId
+  );
+}
+
 /**
  * Derive the PDA for an airdrop record (V2 — ETH-only, no sol_wallet)
  * Normalizes ETH address to lowercase to prevent case-sensitive PDA collisions.
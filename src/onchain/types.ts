@@ -11,7 +11,7 @@ export interface GlobalStateV2 {
   xblkAirdropped: bigint;
   xuniAirdropped: bigint;
   nativeAirdropped: bigint;
-  reserved: bigint[];
+  pendingAuthority: PublicKey;
   bump: number;
 }
 
@@ -26,7 +26,7 @@ export interface GlobalStateV2 {
  * - 8 bytes: xblk_airdropped (u64)
  * - 8 bytes: xuni_airdropped (u64)
  * - 8 bytes: native_airdropped (u64)
- * - 32 bytes: reserved ([u64; 4])
+ * - 32 bytes: pending_authority (Pubkey, formerly reserved)
  * - 1 byte: bump (u8)
  */
 export const GLOBAL_STATE_V2_OFFSETS = {
@@ -38,7 +38,7 @@ export const GLOBAL_STATE_V2_OFFSETS = {
   XBLK_AIRDROPPED: 9 + 32 + 8 + 8,
   XUNI_AIRDROPPED: 9 + 32 + 8 + 8 + 8,
   NATIVE_AIRDROPPED: 9 + 32 + 8 + 8 + 8 + 8,
-  RESERVED: 9 + 32 + 8 + 8 + 8 + 8 + 8,
+  PENDING_AUTHORITY: 9 + 32 + 8 + 8 + 8 + 8 + 8,
   BUMP: 9 + 32 + 8 + 8 + 8 + 8 + 8 + 32,
 } as const;
 
@@ -78,6 +78,19 @@ export interface OnChainAirdropRunV2 {
  * - 1 byte: dry_run (bool)
  * - 32 bytes: reserved ([u64; 4])
  * - 1 byte: bump (u8)
+ * - 8 bytes: fees_spent (u64)
+ * - 8 bytes: budget (u64)
+ * - 32 bytes: label ([u8; 32])
+ * - 8 bytes: scheduled_at (i64)
+ * - 32 bytes: reference_hash ([u8; 32])
+ * - 8 bytes: snapshot_block (u64)
+ * - 2 bytes: subrun_count (u16)
+ * - 1 byte: status (RunStatus: 0 = Active, 1 = Completed, 2 = Aborted)
+ * - 4 bytes: max_recipients (u32, 0 = no cap)
+ * - 8 bytes: max_amount (u64, 0 = no cap)
+ *
+ * Fields after bump are appended to the original layout; legacy accounts are
+ * shorter, so only the original offsets are read here.
  */
 export const AIRDROP_RUN_V2_OFFSETS = {
   DISCRIMINATOR: 0,
@@ -95,9 +108,22 @@ export const AIRDROP_RUN_V2_OFFSETS = {
   BUMP: 102,
 } as const;
 
-export const AIRDROP_RUN_V2_SIZE =
+const AIRDROP_RUN_V2_ORIGINAL_SIZE =
   8 + 1 + 8 + 8 + 4 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 1; // 103 bytes
 
+/** Sum of the appended field sizes listed above */
+const AIRDROP_RUN_V2_APPENDED_SIZE = 111;
+
+export const AIRDROP_RUN_V2_SIZE =
+  AIRDROP_RUN_V2_ORIGINAL_SIZE + AIRDROP_RUN_V2_APPENDED_SIZE; // 214 bytes
+
+/**
+ * Anchor account discriminator for AirdropRunV2 (sha256("account:AirdropRunV2")[..8])
+ */
+export const AIRDROP_RUN_V2_DISCRIMINATOR = Buffer.from([
+  127, 201, 150, 176, 20, 89, 56, 68,
+]);
+
 /**
  * On-chain AirdropRecord account data structure (ETH-only PDA)
  */
@@ -107,9 +133,17 @@ export interface AirdropRecordV2 {
   xblkAirdropped: bigint;
   xuniAirdropped: bigint;
   nativeAirdropped: bigint;
-  reserved: bigint[]; // [u64; 4]
+  /** Claimed amounts; only meaningful once the record is at version 2 */
+  xnmClaimed: bigint;
+  xblkClaimed: bigint;
+  xuniClaimed: bigint;
+  nativeClaimed: bigint;
   lastUpdated: bigint;
   bump: number;
+  /** Linked Solana wallet; default if unset or the record predates it */
+  solWallet: PublicKey;
+  /** Credits applied so far; 0 for records that predate the field */
+  nonce: bigint;
 }
 
 /**
@@ -121,9 +155,48 @@ export interface AirdropRecordV2 {
  * - 8 bytes: xblk_airdropped (u64)
  * - 8 bytes: xuni_airdropped (u64)
  * - 8 bytes: native_airdropped (u64)
- * - 32 bytes: reserved ([u64; 4])
+ * - 8 bytes: xnm_claimed (u64, formerly reserved[0])
+ * - 8 bytes: xblk_claimed (u64, formerly reserved[1])
+ * - 8 bytes: xuni_claimed (u64, formerly reserved[2])
+ * - 8 bytes: native_claimed (u64, formerly reserved[3])
  * - 8 bytes: last_updated (i64)
  * - 1 byte: bump (u8)
+ * - 1 byte: imported (bool)
+ * - 2 bytes: last_credited_season (u16)
+ * - 1 byte: ata_ready (bool)
+ * - 8 bytes: created_in_run (u64)
+ * - 8 bytes: last_credited_run (u64)
+ * - 32 bytes: sol_wallet (Pubkey)
+ * - 1 byte: acknowledged (bool)
+ * - 1 byte: forfeited (bool)
+ * - 8 bytes: xnm_updated_at (i64)
+ * - 8 bytes: xblk_updated_at (i64)
+ * - 8 bytes: xuni_updated_at (i64)
+ * - 8 bytes: native_updated_at (i64)
+ * - 16 bytes: last_batch_id ([u8; 16])
+ * - 8 bytes: last_delta_xnm (u64)
+ * - 8 bytes: last_delta_xblk (u64)
+ * - 8 bytes: last_delta_xuni (u64)
+ * - 8 bytes: last_delta_native (u64)
+ * - 8 bytes: score (u64)
+ * - 8 bytes: score_amount (u64)
+ * - 2 bytes: preferred_token (Option<TokenType>)
+ * - 32 bytes: tag ([u8; 32])
+ * - 8 bytes: claim_deadline (i64)
+ * - 1 byte: claimed (bool)
+ * - 8 bytes: locked_until (i64)
+ * - 1 byte: version (u8)
+ * - 8 bytes: created_at (i64)
+ * - 1 byte: frozen (bool)
+ * - 8 bytes: nonce (u64)
+ * - 8 bytes: last_credited_at (i64)
+ * - 8 bytes: xnm_claimable (u64)
+ * - 8 bytes: xblk_claimable (u64)
+ * - 8 bytes: xuni_claimable (u64)
+ * - 8 bytes: native_claimable (u64)
+ *
+ * Fields after bump are appended to the original layout; legacy accounts are
+ * shorter, so only the original offsets are read here.
  */
 export const AIRDROP_RECORD_V2_OFFSETS = {
   DISCRIMINATOR: 0,
@@ -132,12 +205,57 @@ export const AIRDROP_RECORD_V2_OFFSETS = {
   XBLK_AIRDROPPED: 8 + 42 + 8,
   XUNI_AIRDROPPED: 8 + 42 + 8 + 8,
   NATIVE_AIRDROPPED: 8 + 42 + 8 + 8 + 8,
-  RESERVED: 8 + 42 + 8 + 8 + 8 + 8,
+  XNM_CLAIMED: 8 + 42 + 8 + 8 + 8 + 8,
+  XBLK_CLAIMED: 8 + 42 + 8 + 8 + 8 + 8 + 8,
+  XUNI_CLAIMED: 8 + 42 + 8 + 8 + 8 + 8 + 16,
+  NATIVE_CLAIMED: 8 + 42 + 8 + 8 + 8 + 8 + 24,
   LAST_UPDATED: 8 + 42 + 8 + 8 + 8 + 8 + 32,
   BUMP: 8 + 42 + 8 + 8 + 8 + 8 + 32 + 8,
 } as const;
 
-export const AIRDROP_RECORD_V2_SIZE = 8 + 42 + 8 + 8 + 8 + 8 + 32 + 8 + 1; // 123 bytes
+const AIRDROP_RECORD_V2_ORIGINAL_SIZE = 8 + 42 + 8 + 8 + 8 + 8 + 32 + 8 + 1; // 123 bytes
+
+/** Sum of the appended field sizes listed above */
+const AIRDROP_RECORD_V2_APPENDED_SIZE = 259;
+
+export const AIRDROP_RECORD_V2_SIZE =
+  AIRDROP_RECORD_V2_ORIGINAL_SIZE + AIRDROP_RECORD_V2_APPENDED_SIZE; // 382 bytes
+
+/** nonce precedes last_credited_at and the four claimable amounts */
+export const AIRDROP_RECORD_V2_NONCE_OFFSET = AIRDROP_RECORD_V2_SIZE - 48;
+
+/** sol_wallet follows imported, season, ata_ready and the two run ids */
+export const AIRDROP_RECORD_V2_SOL_WALLET_OFFSET =
+  AIRDROP_RECORD_V2_ORIGINAL_SIZE + 1 + 2 + 1 + 8 + 8; // 143
+
+/**
+ * Anchor account discriminator for AirdropRecordV2 (sha256("account:AirdropRecordV2")[..8])
+ */
+export const AIRDROP_RECORD_V2_DISCRIMINATOR = Buffer.from([
+  246, 23, 150, 93, 132, 249, 155, 7,
+]);
+
+/**
+ * Offset constants for Blacklist deserialization
+ * Account layout:
+ * - 8 bytes: Anchor discriminator
+ * - 32 bytes: wallet (Pubkey)
+ * - 8 bytes: blacklisted_at (i64)
+ * - 1 byte: bump (u8)
+ */
+export const BLACKLIST_OFFSETS = {
+  DISCRIMINATOR: 0,
+  WALLET: 8,
+  BLACKLISTED_AT: 8 + 32, // 40
+  BUMP: 8 + 32 + 8, // 48
+} as const;
+
+/**
+ * Anchor account discriminator for Blacklist (sha256("account:Blacklist")[..8])
+ */
+export const BLACKLIST_DISCRIMINATOR = Buffer.from([
+  131, 9, 212, 250, 58, 186, 247, 3,
+]);
 
 /**
  * On-chain AirdropLock account data structure
@@ -170,3 +288,25 @@ export const AIRDROP_LOCK_OFFSETS = {
 } as const;
 
 export const AIRDROP_LOCK_SIZE = 8 + 32 + 8 + 8 + 8 + 1; // 65 bytes
+
+/**
+ * One entry of a batch_update_records call (amounts in base units)
+ */
+export interface RecordUpdate {
+  ethAddress: string;
+  /** The record's sol_wallet, whose blacklist PDA is passed alongside it */
+  solWallet: PublicKey;
+  xnmAmount: bigint;
+  xblkAmount: bigint;
+  xuniAmount: bigint;
+  nativeAmount: bigint;
+  /** The record's current nonce, quoted as expected_nonce */
+  expectedNonce: bigint;
+}
+
+/**
+ * One entry of a batch_initialize_and_update call (amounts in base units)
+ */
+export interface NewRecord extends Omit<RecordUpdate, 'expectedNonce'> {
+  score: bigint;
+}
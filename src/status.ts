@@ -5,7 +5,10 @@ import {
   fetchAllMultiTokenSnapshots,
   deserializeAirdropRunV2,
 } from './onchain/client.js';
-import { AIRDROP_RUN_V2_SIZE, OnChainAirdropRunV2 } from './onchain/types.js';
+import {
+  AIRDROP_RUN_V2_DISCRIMINATOR,
+  OnChainAirdropRunV2,
+} from './onchain/types.js';
 import { fetchMiners } from './airdrop/executor.js';
 import {
   calculateMultiTokenDeltas,
@@ -86,7 +89,15 @@ async function main(): Promise<void> {
     fetchAllMultiTokenSnapshots(connection, program),
     fetchMiners(apiEndpoint),
     connection.getProgramAccounts(program, {
-      filters: [{ dataSize: AIRDROP_RUN_V2_SIZE }],
+      filters: [
+        {
+          memcmp: {
+            offset: 0,
+            bytes: AIRDROP_RUN_V2_DISCRIMINATOR.toString('base64'),
+            encoding: 'base64',
+          },
+        },
+      ],
     }),
   ]);
 
@@ -7,7 +7,7 @@ import { loadConfig } from './config.js';
 import { getConnection, getPayer } from './solana/connection.js';
 import {
   getGlobalState,
-  createUpdateAuthorityInstruction,
+  createProposeAuthorityInstruction,
 } from './onchain/client.js';
 
 function parseNewAuthority(): PublicKey {
@@ -58,11 +58,15 @@ async function main() {
     process.exit(0);
   }
 
-  console.log('\nSending update_authority transaction...');
+  console.log('\nSending propose_authority transaction...');
 
   const transaction = new Transaction();
   transaction.add(
-    createUpdateAuthorityInstruction(programId, payer.publicKey, newAuthority)
+    createProposeAuthorityInstruction(
+      programId,
+      payer.publicKey,
+      newAuthority
+    )
   );
 
   const signature = await sendAndConfirmTransaction(
@@ -72,11 +76,14 @@ async function main() {
     { commitment: 'confirmed' }
   );
 
-  console.log(`\nAuthority updated successfully.`);
+  console.log(`\nAuthority proposed successfully.`);
   console.log(`Signature: ${signature}`);
+  console.log(
+    'The new authority must sign accept_authority to complete the handover.'
+  );
 }
 
 main().catch((err) => {
-  console.error('Failed to update authority:', err);
+  console.error('Failed to propose authority:', err);
   process.exit(1);
 });
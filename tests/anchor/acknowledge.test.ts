@@ -0,0 +1,64 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { Keypair } from '@solana/web3.js';
+import {
+  hasValidator,
+  program,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  credit,
+} from './helpers';
+
+describe.skipIf(!hasValidator)('acknowledgement on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+    await setConfig('setRequireAck', true);
+  });
+
+  afterAll(async () => {
+    await setConfig('setRequireAck', false);
+  });
+
+  describe('require_ack', () => {
+    it('should reject credits before the recipient acknowledges', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(credit(recordPDA)).rejects.toThrow(/TermsNotAccepted/);
+    });
+
+    it('should accept credits once the recipient acknowledges', async () => {
+      const wallet = Keypair.generate();
+      const recordPDA = await initializeRecord(
+        randomEthAddress(),
+        wallet.publicKey
+      );
+
+      await program.methods
+        .acknowledge()
+        .accounts({ wallet: wallet.publicKey, airdropRecord: recordPDA })
+        .signers([wallet])
+        .rpc();
+      await credit(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.acknowledged).toBe(true);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+  });
+
+  describe('acknowledge', () => {
+    it('should reject a signer other than the linked wallet', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const impostor = Keypair.generate();
+
+      await expect(
+        program.methods
+          .acknowledge()
+          .accounts({ wallet: impostor.publicKey, airdropRecord: recordPDA })
+          .signers([impostor])
+          .rpc()
+      ).rejects.toThrow(/WalletMismatch/);
+    });
+  });
+});
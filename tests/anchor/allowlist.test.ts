@@ -0,0 +1,137 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  recordRegistryPDA,
+  PROGRAM_ID,
+} from './helpers';
+import {
+  deriveAllowedWalletPDA as deriveAllowedWalletPDAFor,
+  deriveBlacklistPDA,
+} from '../../src/onchain/pda';
+
+function deriveAllowedWalletPDA(wallet: PublicKey): PublicKey {
+  return deriveAllowedWalletPDAFor(PROGRAM_ID, wallet)[0];
+}
+
+async function addAllowedWallet(wallet: PublicKey): Promise<void> {
+  await program.methods
+    .addAllowedWallet()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      wallet,
+      allowedWallet: deriveAllowedWalletPDA(wallet),
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+/** Create a record linked to a freshly allowlisted wallet */
+async function initializeAllowedRecord(): Promise<PublicKey> {
+  const wallet = Keypair.generate().publicKey;
+  await addAllowedWallet(wallet);
+  return initializeRecord(
+    randomEthAddress(),
+    wallet,
+    deriveAllowedWalletPDA(wallet)
+  );
+}
+
+async function setRecordWallet(
+  recordPDA: PublicKey,
+  wallet: PublicKey,
+  allowed: boolean
+): Promise<void> {
+  await program.methods
+    .setRecordWallet()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      wallet,
+      allowedWallet: allowed ? deriveAllowedWalletPDA(wallet) : null,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, wallet)[0],
+      ethRegistry: await recordRegistryPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('wallet allowlist on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+    await setConfig('setWalletAllowlist', true);
+  });
+
+  afterAll(async () => {
+    await setConfig('setWalletAllowlist', false);
+  });
+
+  describe('record creation', () => {
+    it('should refuse a wallet that is not allowlisted', async () => {
+      await expect(initializeRecord(randomEthAddress())).rejects.toThrow(
+        /WalletNotAllowed/
+      );
+    });
+
+    it('should create a record for an allowlisted wallet', async () => {
+      const wallet = Keypair.generate().publicKey;
+      await addAllowedWallet(wallet);
+
+      const recordPDA = await initializeRecord(
+        randomEthAddress(),
+        wallet,
+        deriveAllowedWalletPDA(wallet)
+      );
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.solWallet.equals(wallet)).toBe(true);
+    });
+  });
+
+  describe('set_record_wallet', () => {
+    it('should refuse a wallet that is not allowlisted', async () => {
+      const recordPDA = await initializeAllowedRecord();
+
+      await expect(
+        setRecordWallet(recordPDA, Keypair.generate().publicKey, false)
+      ).rejects.toThrow(/WalletNotAllowed/);
+    });
+
+    it('should link an allowlisted wallet', async () => {
+      const recordPDA = await initializeAllowedRecord();
+      const wallet = Keypair.generate().publicKey;
+      await addAllowedWallet(wallet);
+
+      await setRecordWallet(recordPDA, wallet, true);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.solWallet.equals(wallet)).toBe(true);
+    });
+
+    it('should refuse a wallet removed from the allowlist', async () => {
+      const recordPDA = await initializeAllowedRecord();
+      const wallet = Keypair.generate().publicKey;
+      await addAllowedWallet(wallet);
+      await program.methods
+        .removeAllowedWallet()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          allowedWallet: deriveAllowedWalletPDA(wallet),
+        })
+        .rpc();
+
+      await expect(setRecordWallet(recordPDA, wallet, false)).rejects.toThrow(
+        /WalletNotAllowed/
+      );
+    });
+  });
+});
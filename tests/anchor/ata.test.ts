@@ -0,0 +1,91 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  hasValidator,
+  statePDA,
+  provider,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+} from './helpers';
+
+describe.skipIf(!hasValidator)('ensure_ata on-chain tests', () => {
+  let mint: PublicKey;
+
+  async function ensureAta(
+    recordPDA: PublicKey,
+    wallet: PublicKey
+  ): Promise<PublicKey> {
+    const associatedToken = getAssociatedTokenAddressSync(mint, wallet);
+    await program.methods
+      .ensureAta()
+      .accounts({
+        authority: authority.publicKey,
+        state: statePDA,
+        airdropRecord: recordPDA,
+        wallet,
+        mint,
+        associatedToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return associatedToken;
+  }
+
+  beforeAll(async () => {
+    await setupSuite();
+
+    mint = await createMint(
+      provider.connection,
+      authority.payer,
+      authority.publicKey,
+      null,
+      0
+    );
+  });
+
+  describe('ensure_ata', () => {
+    it('should create the token account and mark the record', async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeRecord(randomEthAddress(), wallet);
+
+      const associatedToken = await ensureAta(recordPDA, wallet);
+
+      const account = await getAccount(provider.connection, associatedToken);
+      expect(account.owner.equals(wallet)).toBe(true);
+      expect(account.mint.equals(mint)).toBe(true);
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.ataReady).toBe(true);
+    });
+
+    it('should succeed again when the account already exists', async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeRecord(randomEthAddress(), wallet);
+      await ensureAta(recordPDA, wallet);
+
+      await ensureAta(recordPDA, wallet);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.ataReady).toBe(true);
+    });
+
+    it('should refuse a wallet other than the record wallet', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(
+        ensureAta(recordPDA, Keypair.generate().publicKey)
+      ).rejects.toThrow(/WalletMismatch/);
+    });
+  });
+});
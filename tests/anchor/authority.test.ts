@@ -0,0 +1,111 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  program,
+  authority,
+  setupProgram,
+} from './helpers';
+
+describe.skipIf(!hasValidator)('Authority handover on-chain tests', () => {
+  beforeAll(async () => {
+    await setupProgram();
+  });
+
+  describe('prove_authority', () => {
+    it('should accept the authority', async () => {
+      await program.methods
+        .proveAuthority()
+        .accounts({ authority: authority.publicKey, state: statePDA })
+        .rpc();
+    });
+
+    it('should reject any other signer', async () => {
+      const impostor = Keypair.generate();
+
+      await expect(
+        program.methods
+          .proveAuthority()
+          .accounts({ authority: impostor.publicKey, state: statePDA })
+          .signers([impostor])
+          .rpc()
+      ).rejects.toThrow(/Unauthorized/);
+    });
+  });
+
+  describe('propose_authority / accept_authority', () => {
+    it('should reject accept from a non-pending signer', async () => {
+      const proposed = Keypair.generate();
+      const impostor = Keypair.generate();
+
+      await program.methods
+        .proposeAuthority(proposed.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+        })
+        .rpc();
+
+      await expect(
+        program.methods
+          .acceptAuthority()
+          .accounts({
+            newAuthority: impostor.publicKey,
+            state: statePDA,
+          })
+          .signers([impostor])
+          .rpc()
+      ).rejects.toThrow(/Unauthorized|0x1771|ConstraintRaw|2003/);
+
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.authority.equals(authority.publicKey)).toBe(true);
+      expect(state.pendingAuthority.equals(proposed.publicKey)).toBe(true);
+    });
+
+    it('should hand over to the pending key and clear it', async () => {
+      const proposed = Keypair.generate();
+
+      await program.methods
+        .proposeAuthority(proposed.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+        })
+        .rpc();
+
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          newAuthority: proposed.publicKey,
+          state: statePDA,
+        })
+        .signers([proposed])
+        .rpc();
+
+      let state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.authority.equals(proposed.publicKey)).toBe(true);
+      expect(state.pendingAuthority.equals(PublicKey.default)).toBe(true);
+
+      // Hand authority back so other suites keep working
+      await program.methods
+        .proposeAuthority(authority.publicKey)
+        .accounts({
+          authority: proposed.publicKey,
+          state: statePDA,
+        })
+        .signers([proposed])
+        .rpc();
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          newAuthority: authority.publicKey,
+          state: statePDA,
+        })
+        .rpc();
+
+      state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.authority.equals(authority.publicKey)).toBe(true);
+    });
+  });
+});
@@ -0,0 +1,62 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+  emittedEvents,
+} from './helpers';
+
+async function creditInBatch(
+  recordPDA: PublicKey,
+  batchId: number[]
+): Promise<string> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  return program.methods
+    .updateRecordV2(
+      new anchor.BN(1_000),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      true,
+      batchId,
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      operator: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc({ commitment: 'confirmed' });
+}
+
+describe.skipIf(!hasValidator)('batch_id on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('update_record_v2', () => {
+    it('should store and emit the batch_id', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const batchId = Array.from({ length: 16 }, (_, i) => i + 1);
+
+      const signature = await creditInBatch(recordPDA, batchId);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.lastBatchId).toEqual(batchId);
+      const event = (await emittedEvents(signature)).find(
+        (e) => e.name === 'recordUpdated'
+      );
+      expect(event?.data.batchId).toEqual(batchId);
+    });
+  });
+});
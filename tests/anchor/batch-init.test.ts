@@ -0,0 +1,179 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runId,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  deriveRecordPDA,
+  deriveRegistryPDA,
+  PROGRAM_ID,
+} from './helpers';
+import {
+  deriveAllowedWalletPDA,
+  deriveBlacklistPDA,
+} from '../../src/onchain/pda';
+
+/** A new record for `ethAddress`, linked to a fresh wallet */
+interface Entry {
+  ethAddress: string;
+  solWallet: PublicKey;
+}
+
+function entryFor(ethAddress: string): Entry {
+  return { ethAddress, solWallet: Keypair.generate().publicKey };
+}
+
+function newRecord({ ethAddress, solWallet }: Entry) {
+  return {
+    ethAddress: Array.from(Buffer.from(ethAddress)),
+    solWallet,
+    xnmAmount: new anchor.BN(1_000),
+    xblkAmount: new anchor.BN(0),
+    xuniAmount: new anchor.BN(0),
+    nativeAmount: new anchor.BN(0),
+    score: new anchor.BN(7),
+  };
+}
+
+/** The five accounts batch_initialize_and_update takes per entry */
+function entryAccounts(
+  { ethAddress, solWallet }: Entry,
+  recordPDA: PublicKey = deriveRecordPDA(ethAddress)[0]
+) {
+  return [
+    { pubkey: recordPDA, isSigner: false, isWritable: true },
+    {
+      pubkey: deriveRegistryPDA(ethAddress)[0],
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: solWallet, isSigner: false, isWritable: false },
+    {
+      pubkey: deriveAllowedWalletPDA(PROGRAM_ID, solWallet)[0],
+      isSigner: false,
+      isWritable: false,
+    },
+  ];
+}
+
+async function batchInitialize(entries: Entry[]): Promise<void> {
+  await program.methods
+    .batchInitializeAndUpdate(entries.map(newRecord))
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(entries.flatMap((entry) => entryAccounts(entry)))
+    .rpc();
+}
+
+async function fetchRecord(ethAddress: string) {
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  return program.account.airdropRecordV2.fetch(recordPDA);
+}
+
+describe.skipIf(!hasValidator)('batch initialize on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite(false);
+  });
+
+  describe('batch_initialize_and_update', () => {
+    it('should create and credit every record in the batch', async () => {
+      const entries = [
+        entryFor(randomEthAddress()),
+        entryFor(randomEthAddress()),
+      ];
+      await batchInitialize(entries);
+
+      for (const { ethAddress, solWallet } of entries) {
+        const record = await fetchRecord(ethAddress);
+        expect(record.xnmAirdropped.toString()).toBe('1000');
+        expect(record.score.toString()).toBe('7');
+        expect(record.createdInRun.toString()).toBe(runId.toString());
+        expect(record.solWallet.equals(solWallet)).toBe(true);
+      }
+    });
+
+    it('should skip existing records when the batch is resent', async () => {
+      const existing = entryFor(randomEthAddress());
+      await batchInitialize([existing]);
+
+      const fresh = entryFor(randomEthAddress());
+      await batchInitialize([existing, fresh]);
+
+      const skipped = await fetchRecord(existing.ethAddress);
+      expect(skipped.xnmAirdropped.toString()).toBe('1000');
+      const created = await fetchRecord(fresh.ethAddress);
+      expect(created.xnmAirdropped.toString()).toBe('1000');
+    });
+
+    it('should skip an address registered in another case', async () => {
+      const lower = randomEthAddress();
+      await batchInitialize([entryFor(lower)]);
+
+      const upper = '0x' + lower.slice(2).toUpperCase();
+      await batchInitialize([entryFor(upper)]);
+
+      await expect(fetchRecord(upper)).rejects.toThrow(
+        /Account does not exist/
+      );
+    });
+
+    it('should reject a blacklisted wallet', async () => {
+      const entry = entryFor(randomEthAddress());
+      await program.methods
+        .blacklistWallet(entry.solWallet)
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          blacklist: deriveBlacklistPDA(PROGRAM_ID, entry.solWallet)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      await expect(batchInitialize([entry])).rejects.toThrow(
+        /WalletBlacklisted/
+      );
+    });
+
+    it('should reject the default wallet', async () => {
+      const entry = {
+        ethAddress: randomEthAddress(),
+        solWallet: PublicKey.default,
+      };
+
+      await expect(batchInitialize([entry])).rejects.toThrow(/InvalidWallet/);
+    });
+
+    it('should reject a record account that is not the PDA', async () => {
+      const entry = entryFor(randomEthAddress());
+      await expect(
+        program.methods
+          .batchInitializeAndUpdate([newRecord(entry)])
+          .accounts({
+            authority: authority.publicKey,
+            payer: authority.publicKey,
+            state: statePDA,
+            airdropRun: runPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(entryAccounts(entry, Keypair.generate().publicKey))
+          .rpc()
+      ).rejects.toThrow(/InvalidRecordAccount/);
+    });
+  });
+});
@@ -0,0 +1,134 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+/**
+ * Credit 1,000 XNM to each record through batch_update_records, quoting
+ * `nonceOffset` past each record's current nonce
+ */
+async function batchCredit(
+  records: { recordPDA: PublicKey; nonceOffset: number }[],
+  bestEffort: boolean
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const updates = [];
+  const accounts = [];
+  for (const { recordPDA, nonceOffset } of records) {
+    const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+    updates.push({
+      xnmAmount: new anchor.BN(1_000),
+      xblkAmount: zero,
+      xuniAmount: zero,
+      nativeAmount: zero,
+      expectedNonce: nonce.addn(nonceOffset),
+    });
+    accounts.push(
+      { pubkey: recordPDA, isSigner: false, isWritable: true },
+      {
+        pubkey: await recordBlacklistPDA(recordPDA),
+        isSigner: false,
+        isWritable: false,
+      }
+    );
+  }
+  await program.methods
+    .batchUpdateRecords(updates, bestEffort, Array(16).fill(0))
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts(accounts)
+    .rpc();
+}
+
+async function batchTouch(
+  records: PublicKey[],
+  isWritable: boolean = true
+): Promise<void> {
+  await program.methods
+    .batchTouchRecords()
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts(
+      records.map((pubkey) => ({ pubkey, isSigner: false, isWritable }))
+    )
+    .rpc();
+}
+
+async function xnmOf(recordPDA: PublicKey): Promise<string> {
+  const record = await program.account.airdropRecordV2.fetch(recordPDA);
+  return record.xnmAirdropped.toString();
+}
+
+describe.skipIf(!hasValidator)('batch instruction on-chain tests', () => {
+  let goodPDA: PublicKey;
+  let stalePDA: PublicKey;
+
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('batch_update_records', () => {
+    beforeAll(async () => {
+      goodPDA = await initializeRecord(randomEthAddress());
+      stalePDA = await initializeRecord(randomEthAddress());
+    });
+
+    it('should abort the whole batch on a failing entry', async () => {
+      await expect(
+        batchCredit(
+          [
+            { recordPDA: goodPDA, nonceOffset: 0 },
+            { recordPDA: stalePDA, nonceOffset: 1 },
+          ],
+          false
+        )
+      ).rejects.toThrow(/NonceMismatch/);
+
+      expect(await xnmOf(goodPDA)).toBe('0');
+    });
+
+    it('should skip a failing entry in best-effort mode', async () => {
+      await batchCredit(
+        [
+          { recordPDA: goodPDA, nonceOffset: 0 },
+          { recordPDA: stalePDA, nonceOffset: 1 },
+        ],
+        true
+      );
+
+      expect(await xnmOf(goodPDA)).toBe('1000');
+      expect(await xnmOf(stalePDA)).toBe('0');
+    });
+  });
+
+  describe('batch_touch_records', () => {
+    it('should move last_updated without changing balances', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const before = await program.account.airdropRecordV2.fetch(recordPDA);
+
+      // Let the validator clock advance past the creation timestamp
+      await new Promise((resolve) => setTimeout(resolve, 2_000));
+      await batchTouch([recordPDA]);
+
+      const after = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(after.lastUpdated.gt(before.lastUpdated)).toBe(true);
+      expect(after.xnmAirdropped.toString()).toBe('0');
+      expect(after.nonce.toString()).toBe(before.nonce.toString());
+    });
+
+    it('should reject a read-only record', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(batchTouch([recordPDA], false)).rejects.toThrow(
+        /InvalidRecordAccount/
+      );
+    });
+  });
+});
@@ -0,0 +1,210 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  PROGRAM_ID,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  deriveRecordPDA,
+  initializeRecord,
+  credit,
+  deriveRegistryPDA,
+  recordRegistryPDA,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+async function initializeAndUpdate(
+  ethAddress: string,
+  solWallet: PublicKey
+): Promise<PublicKey> {
+  const ethBytes = Buffer.from(ethAddress);
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  await program.methods
+    .initializeAndUpdateV2(
+      Array.from(ethBytes),
+      new anchor.BN(1_000),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      solWallet,
+      []
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      operator: null,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+async function setRecordWallet(
+  recordPDA: PublicKey,
+  wallet: PublicKey
+): Promise<void> {
+  await program.methods
+    .setRecordWallet()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      wallet,
+      allowedWallet: null,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, wallet)[0],
+      ethRegistry: await recordRegistryPDA(recordPDA),
+    })
+    .rpc();
+}
+
+/** Credit one record through batch_update_records with `blacklistPDA` */
+async function batchCredit(
+  recordPDA: PublicKey,
+  blacklistPDA: PublicKey
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .batchUpdateRecords(
+      [
+        {
+          xnmAmount: new anchor.BN(1_000),
+          xblkAmount: zero,
+          xuniAmount: zero,
+          nativeAmount: zero,
+          expectedNonce: nonce,
+        },
+      ],
+      false,
+      Array(16).fill(0)
+    )
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts([
+      { pubkey: recordPDA, isSigner: false, isWritable: true },
+      { pubkey: blacklistPDA, isSigner: false, isWritable: false },
+    ])
+    .rpc();
+}
+
+async function blacklist(wallet: PublicKey): Promise<PublicKey> {
+  const [blacklistPDA] = deriveBlacklistPDA(PROGRAM_ID, wallet);
+  await program.methods
+    .blacklistWallet(wallet)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      blacklist: blacklistPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return blacklistPDA;
+}
+
+describe.skipIf(!hasValidator)('wallet blacklist on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite(false);
+  });
+
+  describe('initialize_record_v2', () => {
+    it('should reject a blacklisted wallet', async () => {
+      const wallet = Keypair.generate().publicKey;
+      await blacklist(wallet);
+
+      await expect(
+        initializeRecord(randomEthAddress(), wallet)
+      ).rejects.toThrow(/WalletBlacklisted/);
+    });
+  });
+
+  describe('initialize_and_update_v2', () => {
+    it('should reject a blacklisted wallet', async () => {
+      const wallet = Keypair.generate().publicKey;
+      await blacklist(wallet);
+
+      await expect(
+        initializeAndUpdate(randomEthAddress(), wallet)
+      ).rejects.toThrow(/WalletBlacklisted/);
+    });
+  });
+
+  describe('set_record_wallet', () => {
+    it('should refuse to link a blacklisted wallet', async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeAndUpdate(
+        randomEthAddress(),
+        Keypair.generate().publicKey
+      );
+      await blacklist(wallet);
+
+      await expect(setRecordWallet(recordPDA, wallet)).rejects.toThrow(
+        /WalletBlacklisted/
+      );
+    });
+  });
+
+  describe('update_record_v2', () => {
+    it('should reject credits to a blacklisted wallet', async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeAndUpdate(randomEthAddress(), wallet);
+      await blacklist(wallet);
+
+      await expect(credit(recordPDA)).rejects.toThrow(/WalletBlacklisted/);
+    });
+
+    it('should accept credits again after unblacklisting', async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeAndUpdate(randomEthAddress(), wallet);
+      const blacklistPDA = await blacklist(wallet);
+      await program.methods
+        .unblacklistWallet()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          blacklist: blacklistPDA,
+        })
+        .rpc();
+
+      await credit(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('2000');
+    });
+  });
+
+  describe('batch_update_records', () => {
+    it('should reject credits to a blacklisted wallet', async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeAndUpdate(randomEthAddress(), wallet);
+      const blacklistPDA = await blacklist(wallet);
+
+      await expect(batchCredit(recordPDA, blacklistPDA)).rejects.toThrow(
+        /WalletBlacklisted/
+      );
+    });
+
+    it("should reject an account other than the wallet's entry", async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeAndUpdate(randomEthAddress(), wallet);
+      await blacklist(wallet);
+      const [otherPDA] = deriveBlacklistPDA(
+        PROGRAM_ID,
+        Keypair.generate().publicKey
+      );
+
+      await expect(batchCredit(recordPDA, otherPDA)).rejects.toThrow(
+        /InvalidBlacklistAccount/
+      );
+    });
+  });
+});
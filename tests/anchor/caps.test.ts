@@ -0,0 +1,89 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  credit,
+} from './helpers';
+
+const U64_MAX = '18446744073709551615';
+
+async function remainingBudget() {
+  return program.methods
+    .remainingBudget()
+    .accounts({ state: statePDA })
+    .view();
+}
+
+describe.skipIf(!hasValidator)('token cap on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('set_token_caps', () => {
+    beforeAll(async () => {
+      // Leave room for 1,500 more XNM across the program
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      const cap = state.xnmAirdropped.addn(1_500);
+      const zero = new anchor.BN(0);
+      await setConfig('setTokenCaps', cap, zero, zero, zero);
+    });
+
+    afterAll(async () => {
+      const zero = new anchor.BN(0);
+      await setConfig('setTokenCaps', zero, zero, zero, zero);
+    });
+
+    it('should report the room left under each cap', async () => {
+      const remaining = await remainingBudget();
+
+      expect(remaining.xnm.toString()).toBe('1500');
+      expect(remaining.xblk.toString()).toBe(U64_MAX);
+    });
+
+    it('should reject a credit that would pass the cap', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+
+      await expect(credit(recordPDA, 1_000)).rejects.toThrow(
+        /TokenCapExceeded/
+      );
+      expect((await remainingBudget()).xnm.toString()).toBe('500');
+    });
+  });
+
+  describe('set_max_lifetime_per_record', () => {
+    beforeAll(async () => {
+      await setConfig('setMaxLifetimePerRecord', new anchor.BN(1_500));
+    });
+
+    afterAll(async () => {
+      await setConfig('setMaxLifetimePerRecord', new anchor.BN(U64_MAX));
+    });
+
+    it('should reject a credit past the record cap', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+
+      await expect(credit(recordPDA, 1_000)).rejects.toThrow(
+        /LifetimeCapExceeded/
+      );
+    });
+
+    it('should apply the cap to each record separately', async () => {
+      const first = await initializeRecord(randomEthAddress());
+      const second = await initializeRecord(randomEthAddress());
+      await credit(first, 1_500);
+
+      await credit(second, 1_500);
+
+      const record = await program.account.airdropRecordV2.fetch(second);
+      expect(record.xnmAirdropped.toString()).toBe('1500');
+    });
+  });
+});
@@ -0,0 +1,295 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  createMint,
+  createAssociatedTokenAccount,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  provider,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  deriveRecordPDA,
+  deriveRegistryPDA,
+  initializeRecord,
+  recordBlacklistPDA,
+  recordRegistryPDA,
+  credit,
+  grantClaim,
+  PROGRAM_ID,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+/** A wallet with a granted record and an XNM token account to claim into */
+interface Claimant {
+  wallet: Keypair;
+  recordPDA: PublicKey;
+  tokenAccount: PublicKey;
+}
+
+async function selfCloseRecord({ wallet, recordPDA }: Claimant): Promise<void> {
+  await program.methods
+    .selfCloseRecord()
+    .accounts({
+      wallet: wallet.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      ethRegistry: await recordRegistryPDA(recordPDA),
+    })
+    .signers([wallet])
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('claim on-chain tests', () => {
+  let mint: PublicKey;
+  let vault: PublicKey;
+
+  async function tokenAccountFor(wallet: PublicKey): Promise<PublicKey> {
+    return createAssociatedTokenAccount(
+      provider.connection,
+      authority.payer,
+      mint,
+      wallet
+    );
+  }
+
+  async function newClaimant(xnm: number = 1_000): Promise<Claimant> {
+    const wallet = Keypair.generate();
+    const recordPDA = await initializeRecord(
+      randomEthAddress(),
+      wallet.publicKey
+    );
+    await grantClaim(recordPDA, xnm);
+    const tokenAccount = await tokenAccountFor(wallet.publicKey);
+    return { wallet, recordPDA, tokenAccount };
+  }
+
+  async function claim({
+    wallet,
+    recordPDA,
+    tokenAccount,
+  }: Claimant): Promise<void> {
+    await program.methods
+      .claim({ xnm: {} })
+      .accounts({
+        wallet: wallet.publicKey,
+        state: statePDA,
+        airdropRecord: recordPDA,
+        mint,
+        vault,
+        recipientTokenAccount: tokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        blacklist: deriveBlacklistPDA(PROGRAM_ID, wallet.publicKey)[0],
+      })
+      .signers([wallet])
+      .rpc();
+  }
+
+  /** Unix time on the validator's clock */
+  async function chainTime(): Promise<number> {
+    const slot = await provider.connection.getSlot();
+    return (await provider.connection.getBlockTime(slot)) ?? 0;
+  }
+
+  async function balanceOf(tokenAccount: PublicKey): Promise<string> {
+    const account = await getAccount(provider.connection, tokenAccount);
+    return account.amount.toString();
+  }
+
+  beforeAll(async () => {
+    await setupSuite(false);
+
+    mint = await createMint(
+      provider.connection,
+      authority.payer,
+      authority.publicKey,
+      null,
+      0
+    );
+    // The vault is owned by the state PDA, which is off-curve
+    vault = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        authority.payer,
+        mint,
+        statePDA,
+        true
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      authority.payer,
+      mint,
+      vault,
+      authority.payer,
+      1_000_000
+    );
+    await setConfig('setTokenMint', { xnm: {} }, mint);
+  });
+
+  afterAll(async () => {
+    // Disable claims again for the other suites
+    await setConfig('setTokenMint', { xnm: {} }, PublicKey.default);
+  });
+
+  describe('claim', () => {
+    it('should pay the granted balance into the wallet', async () => {
+      const claimant = await newClaimant(1_000);
+
+      await claim(claimant);
+
+      expect(await balanceOf(claimant.tokenAccount)).toBe('1000');
+      const record = await program.account.airdropRecordV2.fetch(
+        claimant.recordPDA
+      );
+      expect(record.xnmClaimed.toString()).toBe('1000');
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      expect(record.xnmClaimable.toString()).toBe('0');
+    });
+
+    it('should have nothing to claim for an executor credit', async () => {
+      const wallet = Keypair.generate();
+      const recordPDA = await initializeRecord(
+        randomEthAddress(),
+        wallet.publicKey
+      );
+      // update_record_v2 records a transfer the executor already sent
+      await credit(recordPDA, 1_000);
+      const tokenAccount = await tokenAccountFor(wallet.publicKey);
+
+      await expect(claim({ wallet, recordPDA, tokenAccount })).rejects.toThrow(
+        /NothingToClaim/
+      );
+      expect(await balanceOf(tokenAccount)).toBe('0');
+    });
+
+    it('should reject a second claim with nothing new to pay', async () => {
+      const claimant = await newClaimant(1_000);
+      await claim(claimant);
+
+      await expect(claim(claimant)).rejects.toThrow(/NothingToClaim/);
+      expect(await balanceOf(claimant.tokenAccount)).toBe('1000');
+    });
+
+    it('should reject a forfeited record', async () => {
+      const claimant = await newClaimant();
+      await program.methods
+        .forfeitRecord()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          airdropRecord: claimant.recordPDA,
+          airdropRun: null,
+          blacklist: await recordBlacklistPDA(claimant.recordPDA),
+        })
+        .rpc();
+
+      await expect(claim(claimant)).rejects.toThrow(/RecordForfeited/);
+    });
+
+    it('should reject a claim past the deadline', async () => {
+      const wallet = Keypair.generate();
+      const ethAddress = randomEthAddress();
+      const [recordPDA] = deriveRecordPDA(ethAddress);
+      const zero = new anchor.BN(0);
+      // Deadlines must be in the future at creation, so set a close one
+      // and wait for the chain to pass it
+      const deadline = (await chainTime()) + 2;
+      await program.methods
+        .initializeFull(
+          Array.from(Buffer.from(ethAddress)),
+          wallet.publicKey,
+          { xnm: new anchor.BN(1_000), xblk: zero, xuni: zero, native: zero },
+          Array(32).fill(0),
+          new anchor.BN(deadline)
+        )
+        .accounts({
+          authority: authority.publicKey,
+          payer: authority.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          airdropRun: runPDA,
+          systemProgram: SystemProgram.programId,
+          blacklist: deriveBlacklistPDA(PROGRAM_ID, wallet.publicKey)[0],
+          ethRegistry: deriveRegistryPDA(ethAddress)[0],
+        })
+        .rpc();
+      const tokenAccount = await tokenAccountFor(wallet.publicKey);
+      while ((await chainTime()) <= deadline) {
+        await new Promise((resolve) => setTimeout(resolve, 500));
+      }
+
+      await expect(claim({ wallet, recordPDA, tokenAccount })).rejects.toThrow(
+        /ClaimDeadlinePassed/
+      );
+    });
+
+    it('should reject a blacklisted wallet', async () => {
+      const claimant = await newClaimant();
+      await program.methods
+        .blacklistWallet(claimant.wallet.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          blacklist: deriveBlacklistPDA(
+            PROGRAM_ID,
+            claimant.wallet.publicKey
+          )[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      await expect(claim(claimant)).rejects.toThrow(/WalletBlacklisted/);
+      expect(await balanceOf(claimant.tokenAccount)).toBe('0');
+    });
+  });
+
+  describe('self_close_record', () => {
+    it('should reject a self-close while disabled', async () => {
+      const claimant = await newClaimant();
+      await claim(claimant);
+
+      await expect(selfCloseRecord(claimant)).rejects.toThrow(
+        /SelfCloseDisabled/
+      );
+    });
+
+    it('should reject an unclaimed record', async () => {
+      const claimant = await newClaimant();
+      await setConfig('setAllowSelfClose', true);
+      try {
+        await expect(selfCloseRecord(claimant)).rejects.toThrow(/NotClaimed/);
+      } finally {
+        await setConfig('setAllowSelfClose', false);
+      }
+    });
+
+    it('should close a claimed record and refund its wallet', async () => {
+      const claimant = await newClaimant();
+      await claim(claimant);
+      const { recordPDA, wallet } = claimant;
+      const rent = await provider.connection.getBalance(recordPDA);
+
+      await setConfig('setAllowSelfClose', true);
+      try {
+        await selfCloseRecord(claimant);
+      } finally {
+        await setConfig('setAllowSelfClose', false);
+      }
+
+      expect(await provider.connection.getAccountInfo(recordPDA)).toBeNull();
+      const refund = await provider.connection.getBalance(wallet.publicKey);
+      expect(refund).toBeGreaterThanOrEqual(rent);
+    });
+  });
+});
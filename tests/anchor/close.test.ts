@@ -0,0 +1,69 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordRegistryPDA,
+  credit,
+  emittedEvents,
+} from './helpers';
+
+async function closeRecord(
+  recordPDA: PublicKey,
+  reason: { forfeit: {} } | { duplicate: {} }
+): Promise<string> {
+  return program.methods
+    .closeRecordV2(reason)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      ethRegistry: await recordRegistryPDA(recordPDA),
+    })
+    .rpc({ commitment: 'confirmed' });
+}
+
+async function recordClosedEvent(signature: string) {
+  return (await emittedEvents(signature)).find(
+    (e) => e.name === 'recordClosed'
+  );
+}
+
+describe.skipIf(!hasValidator)('close_record_v2 on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('close_record_v2', () => {
+    it('should emit the reason and the final balances', async () => {
+      const ethAddress = randomEthAddress();
+      const recordPDA = await initializeRecord(ethAddress);
+      await credit(recordPDA, 2_500);
+
+      const signature = await closeRecord(recordPDA, { forfeit: {} });
+
+      const event = await recordClosedEvent(signature);
+      expect(event?.data.reason).toEqual({ forfeit: {} });
+      expect(String(event?.data.record)).toBe(recordPDA.toBase58());
+      expect(String(event?.data.xnmAirdropped)).toBe('2500');
+      expect(String(event?.data.xblkAirdropped)).toBe('0');
+      expect(Buffer.from(event?.data.ethAddress as number[]).toString()).toBe(
+        ethAddress
+      );
+    });
+
+    it('should emit whichever reason the caller passes', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      const signature = await closeRecord(recordPDA, { duplicate: {} });
+
+      const event = await recordClosedEvent(signature);
+      expect(event?.data.reason).toEqual({ duplicate: {} });
+    });
+  });
+});
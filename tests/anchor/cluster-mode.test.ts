@@ -0,0 +1,49 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import {
+  hasValidator,
+  program,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+} from './helpers';
+
+const CLUSTER_MODE_DEVNET = 0;
+const CLUSTER_MODE_MAINNET = 1;
+
+describe.skipIf(!hasValidator)('cluster_mode on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('set_cluster_mode', () => {
+    it('should reject an unknown mode', async () => {
+      await expect(setConfig('setClusterMode', 2)).rejects.toThrow(
+        /InvalidClusterMode/
+      );
+    });
+  });
+
+  describe('mainnet mode', () => {
+    beforeAll(async () => {
+      await setConfig('setClusterMode', CLUSTER_MODE_MAINNET);
+    });
+
+    afterAll(async () => {
+      await setConfig('setClusterMode', CLUSTER_MODE_DEVNET);
+    });
+
+    it('should reject a placeholder eth_address', async () => {
+      await expect(initializeRecord('0x' + '1'.repeat(40))).rejects.toThrow(
+        /TestAddressRejected/
+      );
+    });
+
+    it('should accept a real eth_address', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('0');
+    });
+  });
+});
@@ -0,0 +1,117 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  createRun,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+  credit,
+  runId,
+  deriveRecordPDA,
+  deriveRegistryPDA,
+  PROGRAM_ID,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+describe.skipIf(!hasValidator)('created_at on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('initialize_record_v2', () => {
+    it('should record run 0 when created outside a run', async () => {
+      const ethAddress = randomEthAddress();
+      const [recordPDA] = deriveRecordPDA(ethAddress);
+      const solWallet = Keypair.generate().publicKey;
+
+      await program.methods
+        .initializeRecordV2(
+          Array.from(Buffer.from(ethAddress)),
+          solWallet,
+          new anchor.BN(0),
+          null
+        )
+        .accounts({
+          authority: authority.publicKey,
+          payer: authority.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          airdropRun: null,
+          systemProgram: SystemProgram.programId,
+          blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+          ethRegistry: deriveRegistryPDA(ethAddress)[0],
+        })
+        .rpc();
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.createdInRun.toString()).toBe('0');
+    });
+  });
+
+  describe('update_record_v2', () => {
+    it('should move last_updated but leave created_at alone', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const created = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(created.createdAt.toString()).toBe(
+        created.lastUpdated.toString()
+      );
+
+      // Let the validator clock advance past the creation timestamp
+      await new Promise((resolve) => setTimeout(resolve, 2_000));
+      await program.methods
+        .updateRecordV2(
+          new anchor.BN(1_000),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          false,
+          Array(16).fill(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          airdropRun: null,
+          operator: null,
+          blacklist: await recordBlacklistPDA(recordPDA),
+        })
+        .rpc();
+
+      const updated = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(updated.createdAt.toString()).toBe(created.createdAt.toString());
+      expect(updated.lastUpdated.gt(created.lastUpdated)).toBe(true);
+    });
+
+    it('should keep created_in_run when a later run credits', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const creatingRun = runId;
+
+      await createRun();
+      await credit(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.createdInRun.toString()).toBe(creatingRun.toString());
+      expect(record.lastCreditedRun.toString()).toBe(runId.toString());
+    });
+
+    it('should stamp only the tokens that were credited', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await credit(recordPDA, 1_000);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmUpdatedAt.toString()).toBe(
+        record.lastUpdated.toString()
+      );
+      expect(record.xblkUpdatedAt.toString()).toBe('0');
+      expect(record.nativeUpdatedAt.toString()).toBe('0');
+    });
+  });
+});
@@ -0,0 +1,86 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  createRun,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+/** Credit 1,000 XNM to a record in the latest run */
+async function creditRecord(
+  recordPDA: PublicKey,
+  allowRecredit: boolean
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordV2(
+      new anchor.BN(1_000),
+      zero,
+      zero,
+      zero,
+      allowRecredit,
+      Array(16).fill(0),
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      operator: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('per-run dedup on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+    await setConfig('setDedupPerRun', true);
+  });
+
+  afterAll(async () => {
+    await setConfig('setDedupPerRun', false);
+  });
+
+  describe('update_record_v2', () => {
+    it('should reject a second credit in the same run', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await creditRecord(recordPDA, false);
+
+      await expect(creditRecord(recordPDA, false)).rejects.toThrow(
+        /AlreadyCreditedThisRun/
+      );
+    });
+
+    it('should accept a second credit with allow_recredit', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await creditRecord(recordPDA, false);
+
+      await creditRecord(recordPDA, true);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('2000');
+    });
+
+    it('should accept a credit in the next run', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await creditRecord(recordPDA, false);
+
+      await createRun();
+      await creditRecord(recordPDA, false);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('2000');
+    });
+  });
+});
@@ -0,0 +1,80 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupProgram,
+  setConfig,
+  createRun,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+async function creditInRun(
+  recordPDA: PublicKey,
+  runPDA: PublicKey,
+  xnm: number
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordAndRun(
+      { xnm: new anchor.BN(xnm), xblk: zero, xuni: zero, native: zero },
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('distribution window on-chain tests', () => {
+  beforeAll(async () => {
+    await setupProgram();
+
+    // A window that closed long ago
+    await setConfig(
+      'setDistributionWindow',
+      new anchor.BN(1),
+      new anchor.BN(2)
+    );
+  });
+
+  afterAll(async () => {
+    // Leave the window unbounded for the other suites
+    await setConfig(
+      'setDistributionWindow',
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+  });
+
+  describe('credits outside the window', () => {
+    it('should reject a credit in a real run', async () => {
+      const runPDA = await createRun(false);
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(creditInRun(recordPDA, runPDA, 1_000)).rejects.toThrow(
+        /DistributionWindowClosed/
+      );
+    });
+
+    it('should let a dry run rehearse', async () => {
+      const runPDA = await createRun(true);
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await creditInRun(recordPDA, runPDA, 0);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.totalRecipients).toBe(1);
+    });
+  });
+});
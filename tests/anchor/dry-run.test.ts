@@ -0,0 +1,101 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, SystemProgram, Keypair } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  deriveRecordPDA,
+  initializeRecord,
+  PROGRAM_ID,
+  recordBlacklistPDA,
+  deriveRegistryPDA,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+async function initializeAndUpdate(
+  ethAddress: string,
+  xnmAmount: number
+): Promise<PublicKey> {
+  const ethBytes = Buffer.from(ethAddress);
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  const solWallet = Keypair.generate().publicKey;
+  const zero = new anchor.BN(0);
+  await program.methods
+    .initializeAndUpdateV2(
+      Array.from(ethBytes),
+      new anchor.BN(xnmAmount),
+      zero,
+      zero,
+      zero,
+      zero,
+      solWallet,
+      []
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      operator: null,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+describe.skipIf(!hasValidator)('dry run write guard on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('initialize_and_update_v2', () => {
+    it('should reject a non-zero amount in a dry run', async () => {
+      await expect(
+        initializeAndUpdate(randomEthAddress(), 1_000)
+      ).rejects.toThrow(/DryRunWriteBlocked/);
+    });
+
+    it('should allow a zero-amount call in a dry run', async () => {
+      const recordPDA = await initializeAndUpdate(randomEthAddress(), 0);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('0');
+    });
+  });
+
+  describe('update_record_v2', () => {
+    it('should reject a non-zero credit linked to a dry run', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(
+        program.methods
+          .updateRecordV2(
+            new anchor.BN(1_000),
+            new anchor.BN(0),
+            new anchor.BN(0),
+            new anchor.BN(0),
+            false,
+            Array(16).fill(0),
+            new anchor.BN(0)
+          )
+          .accounts({
+            authority: authority.publicKey,
+            state: statePDA,
+            airdropRecord: recordPDA,
+            airdropRun: runPDA,
+            operator: null,
+            blacklist: await recordBlacklistPDA(recordPDA),
+          })
+          .rpc()
+      ).rejects.toThrow(/DryRunWriteBlocked/);
+    });
+  });
+});
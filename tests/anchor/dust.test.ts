@@ -0,0 +1,100 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  provider,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordRegistryPDA,
+  credit,
+} from './helpers';
+
+const THRESHOLD = 5_000;
+
+async function findDust(accounts: PublicKey[]): Promise<number> {
+  return program.methods
+    .findDust(new anchor.BN(THRESHOLD))
+    .accounts({ state: statePDA })
+    .remainingAccounts(
+      accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+    )
+    .view();
+}
+
+async function batchCloseRecords(records: PublicKey[]): Promise<void> {
+  const accounts = [];
+  for (const recordPDA of records) {
+    accounts.push(
+      { pubkey: recordPDA, isSigner: false, isWritable: true },
+      {
+        pubkey: await recordRegistryPDA(recordPDA),
+        isSigner: false,
+        isWritable: true,
+      }
+    );
+  }
+  await program.methods
+    .batchCloseRecords(new anchor.BN(THRESHOLD))
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts(accounts)
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('dust cleanup on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  /** A record credited with `xnm` XNM */
+  async function recordWith(xnm: number): Promise<PublicKey> {
+    const recordPDA = await initializeRecord(randomEthAddress());
+    if (xnm > 0) {
+      await credit(recordPDA, xnm);
+    }
+    return recordPDA;
+  }
+
+  describe('find_dust', () => {
+    it('should flag only records below the threshold', async () => {
+      const dust = await recordWith(1_000);
+      const funded = await recordWith(10_000);
+
+      // runPDA is not a record and is left unset
+      const bitmap = await findDust([funded, dust, runPDA]);
+      expect(bitmap).toBe(0b010);
+    });
+  });
+
+  describe('batch_close_records', () => {
+    it('should close dust records and their registry entries', async () => {
+      const dust = await recordWith(0);
+      const registryPDA = await recordRegistryPDA(dust);
+      const before = await program.account.globalStateV2.fetch(statePDA);
+
+      await batchCloseRecords([dust]);
+
+      expect(await provider.connection.getAccountInfo(dust)).toBeNull();
+      expect(await provider.connection.getAccountInfo(registryPDA)).toBeNull();
+      const after = await program.account.globalStateV2.fetch(statePDA);
+      expect(after.recordCount.toString()).toBe(
+        before.recordCount.subn(1).toString()
+      );
+    });
+
+    it('should reject the batch if any record is not dust', async () => {
+      const dust = await recordWith(0);
+      const funded = await recordWith(10_000);
+
+      await expect(batchCloseRecords([dust, funded])).rejects.toThrow(
+        /NotDust/
+      );
+      expect(await provider.connection.getAccountInfo(dust)).not.toBeNull();
+    });
+  });
+});
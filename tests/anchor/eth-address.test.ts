@@ -0,0 +1,107 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordRegistryPDA,
+} from './helpers';
+
+describe.skipIf(!hasValidator)('eth_address validation on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('initialize_record_v2', () => {
+    it('should accept a 0x-prefixed hex address in mixed case', async () => {
+      const hex = randomEthAddress().slice(2);
+      const ethAddress = '0x' + hex.slice(0, 20).toUpperCase() + hex.slice(20);
+      const recordPDA = await initializeRecord(ethAddress);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(Buffer.from(record.ethAddress).toString()).toBe(ethAddress);
+    });
+
+    it('should reject an address without the 0x prefix', async () => {
+      const ethAddress = '00' + randomEthAddress().slice(2);
+
+      await expect(initializeRecord(ethAddress)).rejects.toThrow(
+        /InvalidEthAddress/
+      );
+    });
+
+    it('should reject a non-hex character in the middle', async () => {
+      const valid = randomEthAddress();
+      const ethAddress = valid.slice(0, 21) + 'g' + valid.slice(22);
+
+      await expect(initializeRecord(ethAddress)).rejects.toThrow(
+        /InvalidEthAddress/
+      );
+    });
+  });
+
+  describe('eth registry', () => {
+    it('should reject the same address in another case', async () => {
+      const lower = randomEthAddress();
+      await initializeRecord(lower);
+
+      const upper = '0x' + lower.slice(2).toUpperCase();
+      await expect(initializeRecord(upper)).rejects.toThrow(
+        /DuplicateEthAddress/
+      );
+    });
+
+    it('should free the address when the record is closed', async () => {
+      const lower = randomEthAddress();
+      const recordPDA = await initializeRecord(lower);
+      await program.methods
+        .closeRecordV2({ cleanup: {} })
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          ethRegistry: await recordRegistryPDA(recordPDA),
+        })
+        .rpc();
+
+      const upper = '0x' + lower.slice(2).toUpperCase();
+      const recordPDAUpper = await initializeRecord(upper);
+
+      const record = await program.account.airdropRecordV2.fetch(
+        recordPDAUpper
+      );
+      expect(Buffer.from(record.ethAddress).toString()).toBe(upper);
+    });
+  });
+
+  describe('assert_eth_unique', () => {
+    async function assertEthUnique(
+      recordA: PublicKey,
+      recordB: PublicKey
+    ): Promise<void> {
+      await program.methods
+        .assertEthUnique()
+        .accounts({ recordA, recordB })
+        .rpc();
+    }
+
+    it('should pass two records with different addresses', async () => {
+      const recordA = await initializeRecord(randomEthAddress());
+      const recordB = await initializeRecord(randomEthAddress());
+
+      await expect(assertEthUnique(recordA, recordB)).resolves.toBeUndefined();
+    });
+
+    it('should pass the same record given twice', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(
+        assertEthUnique(recordPDA, recordPDA)
+      ).resolves.toBeUndefined();
+    });
+  });
+});
@@ -0,0 +1,134 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, SystemProgram, Keypair } from '@solana/web3.js';
+import { keccak_256 } from '@noble/hashes/sha3';
+import { secp256k1 } from '@noble/curves/secp256k1';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  deriveRecordPDA,
+  PROGRAM_ID,
+  deriveRegistryPDA,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+import { ethProofMessage } from '../../src/onchain/client';
+
+/** ETH address (lowercase hex) controlled by a secp256k1 private key */
+function ethAddressOf(privateKey: Uint8Array): string {
+  const pubkey = secp256k1.getPublicKey(privateKey, false).subarray(1);
+  return '0x' + Buffer.from(keccak_256(pubkey).subarray(12)).toString('hex');
+}
+
+/** 65-byte r || s || v personal_sign signature over `message` */
+function personalSign(privateKey: Uint8Array, message: Buffer): Buffer {
+  const prefix = `\x19Ethereum Signed Message:\n${message.length}`;
+  const hash = keccak_256(Buffer.concat([Buffer.from(prefix), message]));
+  const signature = secp256k1.sign(hash, privateKey);
+  return Buffer.concat([
+    Buffer.from(signature.toCompactRawBytes()),
+    Buffer.from([27 + signature.recovery]),
+  ]);
+}
+
+async function initializeRecord(
+  ethAddress: string,
+  signature: Buffer | null,
+  solWallet: PublicKey
+): Promise<PublicKey> {
+  const ethBytes = Buffer.from(ethAddress);
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  await program.methods
+    .initializeRecordV2(
+      Array.from(ethBytes),
+      solWallet,
+      new anchor.BN(0),
+      signature ? Array.from(signature) : null
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+describe.skipIf(!hasValidator)('ETH ownership proof on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  afterAll(async () => {
+    // Leave the gate off for the other suites
+    await setConfig('setRequireEthProof', false);
+  });
+
+  describe('initialize_record_v2 with eth_signature', () => {
+    it('should accept a signature from the ETH address', async () => {
+      const privateKey = secp256k1.utils.randomPrivateKey();
+      const ethAddress = ethAddressOf(privateKey);
+      const solWallet = Keypair.generate().publicKey;
+      const message = ethProofMessage(PROGRAM_ID, ethAddress, solWallet);
+
+      const recordPDA = await initializeRecord(
+        ethAddress,
+        personalSign(privateKey, message),
+        solWallet
+      );
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(Buffer.from(record.ethAddress).toString()).toBe(ethAddress);
+      expect(record.solWallet.toBase58()).toBe(solWallet.toBase58());
+    });
+
+    it('should reject a signature from another address', async () => {
+      const signer = secp256k1.utils.randomPrivateKey();
+      const ethAddress = ethAddressOf(secp256k1.utils.randomPrivateKey());
+      const solWallet = Keypair.generate().publicKey;
+      const message = ethProofMessage(PROGRAM_ID, ethAddress, solWallet);
+
+      await expect(
+        initializeRecord(ethAddress, personalSign(signer, message), solWallet)
+      ).rejects.toThrow(/EthSignatureInvalid/);
+    });
+
+    it('should reject a signature linking another wallet', async () => {
+      const privateKey = secp256k1.utils.randomPrivateKey();
+      const ethAddress = ethAddressOf(privateKey);
+      const signedFor = Keypair.generate().publicKey;
+      const message = ethProofMessage(PROGRAM_ID, ethAddress, signedFor);
+
+      await expect(
+        initializeRecord(
+          ethAddress,
+          personalSign(privateKey, message),
+          Keypair.generate().publicKey
+        )
+      ).rejects.toThrow(/EthSignatureInvalid/);
+    });
+
+    it('should let the authority seed records without a proof', async () => {
+      await setConfig('setRequireEthProof', true);
+
+      const recordPDA = await initializeRecord(
+        randomEthAddress(),
+        null,
+        Keypair.generate().publicKey
+      );
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.version).toBeGreaterThan(0);
+    });
+  });
+});
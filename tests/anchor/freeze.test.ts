@@ -0,0 +1,53 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  credit,
+} from './helpers';
+
+async function setFreeze(
+  recordPDA: PublicKey,
+  frozen: boolean
+): Promise<void> {
+  await program.methods
+    .setRecordFreeze(frozen)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('record freeze on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('set_record_freeze', () => {
+    it('should block credits to a frozen record', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setFreeze(recordPDA, true);
+
+      await expect(credit(recordPDA)).rejects.toThrow(/RecordFrozen/);
+    });
+
+    it('should accept credits again after unfreezing', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setFreeze(recordPDA, true);
+      await setFreeze(recordPDA, false);
+
+      await credit(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.frozen).toBe(false);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+  });
+});
@@ -0,0 +1,82 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  provider,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+} from './helpers';
+
+async function growRecord(recordPDA: PublicKey): Promise<void> {
+  await program.methods
+    .growRecord()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('account resize on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('grow_record', () => {
+    it('should leave a current-size record untouched', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const before = await provider.connection.getAccountInfo(recordPDA);
+
+      await growRecord(recordPDA);
+
+      const after = await provider.connection.getAccountInfo(recordPDA);
+      expect(after?.data.equals(before!.data)).toBe(true);
+      expect(after?.lamports).toBe(before?.lamports);
+    });
+
+    it('should reject an account that is not a record', async () => {
+      await expect(growRecord(runPDA)).rejects.toThrow(/InvalidRecordAccount/);
+    });
+  });
+
+  describe('grow_state', () => {
+    it('should leave a current-size GlobalState untouched', async () => {
+      const before = await provider.connection.getAccountInfo(statePDA);
+
+      await program.methods
+        .growState()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const after = await provider.connection.getAccountInfo(statePDA);
+      expect(after?.data.length).toBe(before?.data.length);
+    });
+
+    it('should reject a signer other than the stored authority', async () => {
+      const impostor = Keypair.generate();
+
+      await expect(
+        program.methods
+          .growState()
+          .accounts({
+            authority: impostor.publicKey,
+            state: statePDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([impostor])
+          .rpc()
+      ).rejects.toThrow(/Unauthorized/);
+    });
+  });
+});
@@ -0,0 +1,266 @@
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  deriveAirdropRunPDA,
+  deriveBlacklistPDA,
+  deriveGlobalStatePDA,
+} from '../../src/onchain/pda';
+
+// Skip suites when not running under anchor test (no local validator)
+export const hasValidator = !!process.env.ANCHOR_PROVIDER_URL;
+
+export const PROGRAM_ID = new PublicKey(
+  'xen8pjUWEnRbm1eML9CGtHvmmQfruXMKUybqGjn3chv'
+);
+
+export const [statePDA] = deriveGlobalStatePDA(PROGRAM_ID);
+
+// Lazily initialized by setupProgram to avoid crashing when
+// ANCHOR_PROVIDER_URL is missing
+export let anchor: typeof import('@coral-xyz/anchor');
+export let provider: import('@coral-xyz/anchor').AnchorProvider;
+export let program: import('@coral-xyz/anchor').Program<
+  import('../../target/types/xenblocks_airdrop_tracker').XenblocksAirdropTracker
+>;
+export let authority: import('@coral-xyz/anchor').Wallet;
+/** Run created by the last createRun call; new records are created in it */
+export let runId: bigint;
+export let runPDA: PublicKey;
+
+/**
+ * Load the program from the workspace IDL and initialize GlobalStateV2 if
+ * this is the first suite to run against the validator
+ */
+export async function setupProgram(): Promise<void> {
+  anchor = await import('@coral-xyz/anchor');
+  const IDL = (
+    await import('../../target/idl/xenblocks_airdrop_tracker.json')
+  ).default;
+
+  provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  program = new anchor.Program(
+    IDL as anchor.Idl,
+    provider
+  ) as unknown as typeof program;
+
+  authority = provider.wallet as import('@coral-xyz/anchor').Wallet;
+
+  const stateAccount = await provider.connection.getAccountInfo(statePDA);
+  if (!stateAccount) {
+    await program.methods
+      .initializeStateV2()
+      .accounts({
+        authority: authority.publicKey,
+        state: statePDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+}
+
+/**
+ * Create the next run and make it the one records are created in. A dry run
+ * is enough for suites that don't credit amounts.
+ */
+export async function createRun(dryRun: boolean = true): Promise<PublicKey> {
+  const state = await program.account.globalStateV2.fetch(statePDA);
+  runId = BigInt(state.runCounter.toString()) + 1n;
+  [runPDA] = deriveAirdropRunPDA(PROGRAM_ID, runId);
+  await program.methods
+    .createRunV2(dryRun, new anchor.BN(0), 0, new anchor.BN(0), null)
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return runPDA;
+}
+
+/**
+ * Shared suite setup: load the program and create the run new records are
+ * created in. Dry runs block non-zero credits, so suites that credit amounts
+ * pass `dryRun = false`.
+ */
+export async function setupSuite(dryRun: boolean = true): Promise<void> {
+  await setupProgram();
+  await createRun(dryRun);
+}
+
+type Methods = typeof program.methods;
+
+/**
+ * Call an instruction that only takes the authority and GlobalState, such
+ * as a config setter: `setConfig('setPause', true)`
+ */
+export async function setConfig<M extends keyof Methods>(
+  method: M,
+  ...args: Parameters<Methods[M]>
+): Promise<void> {
+  const call = program.methods[method] as (
+    ...args: Parameters<Methods[M]>
+  ) => ReturnType<Methods['setPause']>;
+  await call(...args)
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .rpc();
+}
+
+/** A fresh, well-formed address so reruns don't collide with old records */
+export function randomEthAddress(): string {
+  const bytes = Keypair.generate().publicKey.toBytes().subarray(0, 20);
+  return '0x' + Buffer.from(bytes).toString('hex');
+}
+
+/**
+ * Record PDA for `ethAddress` exactly as given. Unlike
+ * deriveAirdropRecordPDA this does not lowercase, so suites can create
+ * records for mixed-case or malformed addresses.
+ */
+export function deriveRecordPDA(ethAddress: string): [PublicKey, number] {
+  const ethBytes = Buffer.from(ethAddress);
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('airdrop_record_v2'),
+      ethBytes.subarray(0, 21),
+      ethBytes.subarray(21, 42),
+    ],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * EthRegistry PDA for `ethAddress`. Unlike deriveEthRegistryPDA this never
+ * throws: like eth_address_bytes on-chain, it decodes a non-hex digit as 0,
+ * so suites can submit malformed addresses.
+ */
+export function deriveRegistryPDA(ethAddress: string): [PublicKey, number] {
+  const addressBytes = Buffer.alloc(20);
+  for (let i = 0; i < 20; i++) {
+    const digits = ethAddress.slice(2 + 2 * i, 4 + 2 * i);
+    const high = parseInt(digits[0], 16) || 0;
+    const low = parseInt(digits[1], 16) || 0;
+    addressBytes[i] = (high << 4) | low;
+  }
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('eth'), addressBytes],
+    PROGRAM_ID
+  );
+}
+
+/** EthRegistry PDA of the address a record was created for */
+export async function recordRegistryPDA(
+  recordPDA: PublicKey
+): Promise<PublicKey> {
+  const { ethAddress } = await program.account.airdropRecordV2.fetch(recordPDA);
+  return deriveRegistryPDA(Buffer.from(ethAddress).toString())[0];
+}
+
+/** Blacklist PDA of the wallet currently linked to a record */
+export async function recordBlacklistPDA(
+  recordPDA: PublicKey
+): Promise<PublicKey> {
+  const { solWallet } = await program.account.airdropRecordV2.fetch(recordPDA);
+  return deriveBlacklistPDA(PROGRAM_ID, solWallet)[0];
+}
+
+/**
+ * Create an empty record for `ethAddress` in the current run, linked to
+ * `solWallet` (a fresh wallet by default). `allowedWallet` is solWallet's
+ * AllowedWallet PDA, needed while the wallet allowlist is enabled.
+ */
+export async function initializeRecord(
+  ethAddress: string,
+  solWallet: PublicKey = Keypair.generate().publicKey,
+  allowedWallet: PublicKey | null = null
+): Promise<PublicKey> {
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  await program.methods
+    .initializeRecordV2(
+      Array.from(Buffer.from(ethAddress)),
+      solWallet,
+      new anchor.BN(0),
+      null
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      allowedWallet,
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+/**
+ * Credit `xnmAmount` XNM to a record with update_record_v2, quoting its
+ * current nonce
+ */
+export async function credit(
+  recordPDA: PublicKey,
+  xnmAmount: number = 1_000
+): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordV2(
+      new anchor.BN(xnmAmount),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      true,
+      Array(16).fill(0),
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      operator: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+/**
+ * Grant `xnmAmount` XNM for the record's wallet to collect with claim,
+ * quoting its current nonce
+ */
+export async function grantClaim(
+  recordPDA: PublicKey,
+  xnmAmount: number = 1_000
+): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .grantClaim({ xnm: {} }, new anchor.BN(xnmAmount), nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+/**
+ * Events emitted by a transaction sent with `.rpc({ commitment: 'confirmed'
+ * })`, decoded from its program logs
+ */
+export async function emittedEvents(
+  signature: string
+): Promise<{ name: string; data: Record<string, unknown> }[]> {
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: 'confirmed',
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(PROGRAM_ID, program.coder);
+  return [...parser.parseLogs(tx?.meta?.logMessages ?? [])];
+}
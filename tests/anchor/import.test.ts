@@ -0,0 +1,83 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  deriveRecordPDA,
+  deriveRegistryPDA,
+  initializeRecord,
+  PROGRAM_ID,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+async function importRecord(
+  ethAddress: string,
+  solWallet: PublicKey,
+  xnmAmount: number,
+  xblkAmount: number
+): Promise<PublicKey> {
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  await program.methods
+    .importExternalRecord(
+      solWallet,
+      Array.from(Buffer.from(ethAddress)),
+      new anchor.BN(xnmAmount),
+      new anchor.BN(xblkAmount)
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      systemProgram: SystemProgram.programId,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+describe.skipIf(!hasValidator)('import_external_record on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('import_external_record', () => {
+    it('should create an imported record counted in the totals', async () => {
+      const solWallet = Keypair.generate().publicKey;
+      const before = await program.account.globalStateV2.fetch(statePDA);
+
+      const recordPDA = await importRecord(
+        randomEthAddress(),
+        solWallet,
+        1_000,
+        2_000
+      );
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.imported).toBe(true);
+      expect(record.solWallet.equals(solWallet)).toBe(true);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      expect(record.xblkAirdropped.toString()).toBe('2000');
+      const after = await program.account.globalStateV2.fetch(statePDA);
+      expect(after.xnmAirdropped.sub(before.xnmAirdropped).toString()).toBe(
+        '1000'
+      );
+      expect(after.recordCount.sub(before.recordCount).toString()).toBe('1');
+    });
+
+    it('should reject an address registered in another case', async () => {
+      const lower = randomEthAddress();
+      await initializeRecord(lower);
+
+      const upper = '0x' + lower.slice(2).toUpperCase();
+      await expect(
+        importRecord(upper, Keypair.generate().publicKey, 1_000, 0)
+      ).rejects.toThrow(/DuplicateEthAddress/);
+    });
+  });
+});
@@ -0,0 +1,86 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runId,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  deriveRecordPDA,
+  deriveRegistryPDA,
+  PROGRAM_ID,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+const TAG = Array(32).fill(7);
+
+/** Create a record crediting 1,000 XNM with `TAG` and `claimDeadline` */
+async function initializeFull(
+  ethAddress: string,
+  claimDeadline: number
+): Promise<PublicKey> {
+  const zero = new anchor.BN(0);
+  const solWallet = Keypair.generate().publicKey;
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  await program.methods
+    .initializeFull(
+      Array.from(Buffer.from(ethAddress)),
+      solWallet,
+      { xnm: new anchor.BN(1_000), xblk: zero, xuni: zero, native: zero },
+      TAG,
+      new anchor.BN(claimDeadline)
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+describe.skipIf(!hasValidator)('initialize_full on-chain tests', () => {
+  const inOneHour = () => Math.floor(Date.now() / 1000) + 3_600;
+
+  beforeAll(async () => {
+    await setupSuite(false);
+  });
+
+  describe('initialize_full', () => {
+    it('should set amounts, tag, deadline and run at creation', async () => {
+      const deadline = inOneHour();
+      const recordPDA = await initializeFull(randomEthAddress(), deadline);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      expect(record.tag).toEqual(TAG);
+      expect(record.claimDeadline.toNumber()).toBe(deadline);
+      expect(record.createdInRun.toString()).toBe(runId.toString());
+    });
+
+    it('should reject a deadline in the past', async () => {
+      const deadline = Math.floor(Date.now() / 1000) - 3_600;
+
+      await expect(
+        initializeFull(randomEthAddress(), deadline)
+      ).rejects.toThrow(/InvalidClaimDeadline/);
+    });
+
+    it('should reject a malformed eth_address', async () => {
+      const ethAddress = '00' + randomEthAddress().slice(2);
+
+      await expect(initializeFull(ethAddress, inOneHour())).rejects.toThrow(
+        /InvalidEthAddress/
+      );
+    });
+  });
+});
@@ -0,0 +1,82 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  credit,
+  recordBlacklistPDA,
+} from './helpers';
+
+async function distributeByScore(recordPDA: PublicKey): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .distributeByScore({ xnm: {} }, new anchor.BN(10_000), nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('min update interval on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+    await setConfig('setMinUpdateInterval', new anchor.BN(3_600));
+  });
+
+  afterAll(async () => {
+    await setConfig('setMinUpdateInterval', new anchor.BN(0));
+  });
+
+  describe('check_update_interval', () => {
+    it('should accept the first credit to a record', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await credit(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.lastCreditedAt.toString()).toBe(
+        record.lastUpdated.toString()
+      );
+    });
+
+    it('should reject a second credit within the interval', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA);
+
+      await expect(credit(recordPDA)).rejects.toThrow(/UpdateTooSoon/);
+    });
+
+    it('should apply to distribute_by_score', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA);
+
+      await expect(distributeByScore(recordPDA)).rejects.toThrow(
+        /UpdateTooSoon/
+      );
+    });
+
+    it('should not count a touch as a credit', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await program.methods
+        .batchTouchRecords()
+        .accounts({ authority: authority.publicKey, state: statePDA })
+        .remainingAccounts([
+          { pubkey: recordPDA, isSigner: false, isWritable: true },
+        ])
+        .rpc();
+
+      await credit(recordPDA);
+    });
+  });
+});
@@ -0,0 +1,73 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+async function creditLumpSum(
+  recordPDA: PublicKey,
+  total: number
+): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .creditLumpSum(new anchor.BN(total), true, Array(16).fill(0), nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('credit_lump_sum on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('set_split_bps', () => {
+    it('should reject ratios that do not sum to 10000', async () => {
+      await expect(
+        setConfig('setSplitBps', 5_000, 2_500, 2_500, 1)
+      ).rejects.toThrow(/RatioMisconfigured/);
+    });
+  });
+
+  describe('credit_lump_sum', () => {
+    it('should split the total by the configured ratios', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setConfig('setSplitBps', 4_000, 3_000, 2_000, 1_000);
+
+      await creditLumpSum(recordPDA, 10_000);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('4000');
+      expect(record.xblkAirdropped.toString()).toBe('3000');
+      expect(record.xuniAirdropped.toString()).toBe('2000');
+      expect(record.nativeAirdropped.toString()).toBe('1000');
+    });
+
+    it('should round the remainder into XNM', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setConfig('setSplitBps', 1, 3_333, 3_333, 3_333);
+
+      await creditLumpSum(recordPDA, 10);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1');
+      expect(record.xblkAirdropped.toString()).toBe('3');
+      expect(record.xuniAirdropped.toString()).toBe('3');
+      expect(record.nativeAirdropped.toString()).toBe('3');
+    });
+  });
+});
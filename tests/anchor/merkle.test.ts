@@ -0,0 +1,172 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { keccak_256 } from '@noble/hashes/sha3';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  deriveRecordPDA,
+  PROGRAM_ID,
+  deriveRegistryPDA,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+const ZERO_ROOT = Array(32).fill(0);
+
+/** Sorted-pair keccak256, matching verify_merkle_proof on-chain */
+function hashPair(a: Buffer, b: Buffer): Buffer {
+  const [lo, hi] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+  return Buffer.from(keccak_256(Buffer.concat([lo, hi])));
+}
+
+/** keccak256(sol_wallet || eth_address || amount as u64 big-endian) */
+function allocationLeaf(
+  solWallet: PublicKey,
+  ethAddress: string,
+  amount: bigint
+): Buffer {
+  const amountBuffer = Buffer.alloc(8);
+  amountBuffer.writeBigUInt64BE(amount);
+  return Buffer.from(
+    keccak_256(
+      Buffer.concat([
+        solWallet.toBuffer(),
+        Buffer.from(ethAddress),
+        amountBuffer,
+      ])
+    )
+  );
+}
+
+interface Allocation {
+  solWallet: PublicKey;
+  ethAddress: string;
+  amount: bigint;
+}
+
+async function initializeAndUpdate(
+  allocation: Allocation,
+  proof: Buffer[]
+): Promise<PublicKey> {
+  const ethBytes = Buffer.from(allocation.ethAddress);
+  const [recordPDA] = deriveRecordPDA(allocation.ethAddress);
+  await program.methods
+    .initializeAndUpdateV2(
+      Array.from(ethBytes),
+      new anchor.BN(allocation.amount.toString()),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      allocation.solWallet,
+      proof.map((node) => Array.from(node))
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, allocation.solWallet)[0],
+      ethRegistry: deriveRegistryPDA(allocation.ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+describe.skipIf(!hasValidator)('merkle root on-chain tests', () => {
+  // Three leaves: root = hash(hash(l0, l1), l2)
+  const allocations: Allocation[] = [1_000n, 2_000n, 3_000n].map(
+    (amount) => ({
+      solWallet: Keypair.generate().publicKey,
+      ethAddress: randomEthAddress(),
+      amount,
+    })
+  );
+  const leaves = allocations.map((a) =>
+    allocationLeaf(a.solWallet, a.ethAddress, a.amount)
+  );
+  const left = hashPair(leaves[0], leaves[1]);
+  const root = hashPair(left, leaves[2]);
+  const proofs = [[leaves[1], leaves[2]], [leaves[0], leaves[2]], [left]];
+
+  beforeAll(async () => {
+    await setupSuite(false);
+
+    await setConfig('setAllocationRoot', Array.from(root));
+  });
+
+  afterAll(async () => {
+    // Leave both roots unset for the other suites
+    await setConfig('setAllocationRoot', ZERO_ROOT);
+    await setConfig('setMerkleRoot', ZERO_ROOT);
+  });
+
+  describe('initialize_and_update_v2', () => {
+    it('should reject an amount that is not in the tree', async () => {
+      const allocation = {
+        ...allocations[0],
+        amount: allocations[0].amount + 1n,
+      };
+
+      await expect(
+        initializeAndUpdate(allocation, proofs[0])
+      ).rejects.toThrow(/InvalidProof/);
+    });
+
+    it("should reject a leaf proved with another leaf's proof", async () => {
+      await expect(
+        initializeAndUpdate(allocations[2], proofs[1])
+      ).rejects.toThrow(/InvalidProof/);
+    });
+
+    it('should accept every leaf of the tree with its proof', async () => {
+      for (let i = 0; i < allocations.length; i++) {
+        const recordPDA = await initializeAndUpdate(allocations[i], proofs[i]);
+
+        const record = await program.account.airdropRecordV2.fetch(recordPDA);
+        expect(record.xnmAirdropped.toString()).toBe(
+          allocations[i].amount.toString()
+        );
+        expect(record.solWallet.equals(allocations[i].solWallet)).toBe(true);
+      }
+    });
+  });
+
+  describe('batch_verify_membership', () => {
+    it('should check eth_address leaves, not allocation leaves', async () => {
+      // Eligibility tree over the first two records created above
+      const eligibility = allocations
+        .slice(0, 2)
+        .map((a) => Buffer.from(keccak_256(Buffer.from(a.ethAddress))));
+      await setConfig(
+        'setMerkleRoot',
+        Array.from(hashPair(eligibility[0], eligibility[1]))
+      );
+
+      const verified = await program.methods
+        .batchVerifyMembership([
+          [Array.from(eligibility[1])],
+          [Array.from(eligibility[0])],
+          [Array.from(eligibility[0])],
+        ])
+        .accounts({ state: statePDA })
+        .remainingAccounts(
+          allocations.map((a) => ({
+            pubkey: deriveRecordPDA(a.ethAddress)[0],
+            isSigner: false,
+            isWritable: false,
+          }))
+        )
+        .view();
+      expect(verified).toBe(0b011);
+    });
+  });
+});
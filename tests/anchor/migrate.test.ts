@@ -0,0 +1,115 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  emittedEvents,
+} from './helpers';
+
+describe.skipIf(!hasValidator)('record migration on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  async function migrate(recordPDA: PublicKey): Promise<void> {
+    await program.methods
+      .migrateRecord()
+      .accounts({
+        authority: authority.publicKey,
+        state: statePDA,
+        airdropRecord: recordPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  async function batchStampVersion(
+    records: PublicKey[],
+    isWritable: boolean = true
+  ): Promise<string> {
+    return program.methods
+      .batchStampVersion()
+      .accounts({
+        authority: authority.publicKey,
+        state: statePDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        records.map((pubkey) => ({ pubkey, isSigner: false, isWritable }))
+      )
+      .rpc({ commitment: 'confirmed' });
+  }
+
+  describe('migrate_record', () => {
+    it('should leave a migrated record untouched when run twice', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await program.methods
+        .updateRecordV2(
+          new anchor.BN(1_000),
+          new anchor.BN(2_000),
+          new anchor.BN(3_000),
+          new anchor.BN(4_000),
+          false,
+          Array(16).fill(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          airdropRun: null,
+        })
+        .rpc();
+      const before = await program.account.airdropRecordV2.fetch(recordPDA);
+
+      await migrate(recordPDA);
+      await migrate(recordPDA);
+
+      const after = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(after.version).toBe(2);
+      expect(after.xnmAirdropped.toString()).toBe('1000');
+      expect(after.xblkAirdropped.toString()).toBe('2000');
+      expect(after.xuniAirdropped.toString()).toBe('3000');
+      expect(after.nativeAirdropped.toString()).toBe('4000');
+      expect(after.xnmClaimed.toString()).toBe('0');
+      expect(after.nativeClaimed.toString()).toBe('0');
+      expect(after.lastUpdated.toString()).toBe(
+        before.lastUpdated.toString()
+      );
+    });
+  });
+
+  describe('batch_stamp_version', () => {
+    it('should skip records already at the current version', async () => {
+      const records = [
+        await initializeRecord(randomEthAddress()),
+        await initializeRecord(randomEthAddress()),
+      ];
+      const before = await program.account.airdropRecordV2.fetch(records[0]);
+
+      const signature = await batchStampVersion(records);
+
+      const event = (await emittedEvents(signature)).find(
+        (e) => e.name === 'recordsStamped'
+      );
+      expect(event?.data.count).toBe(0);
+      expect(event?.data.version).toBe(2);
+      const after = await program.account.airdropRecordV2.fetch(records[0]);
+      expect(after.version).toBe(before.version);
+    });
+
+    it('should reject a read-only record', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(batchStampVersion([recordPDA], false)).rejects.toThrow(
+        /InvalidRecordAccount/
+      );
+    });
+  });
+});
@@ -0,0 +1,193 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, Keypair } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+async function correctRecord(
+  recordPDA: PublicKey,
+  coSigners: Keypair[]
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .correctRecord(zero, zero, zero, zero, nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .remainingAccounts(
+      coSigners.map((signer) => ({
+        pubkey: signer.publicKey,
+        isSigner: true,
+        isWritable: false,
+      }))
+    )
+    .signers(coSigners)
+    .rpc();
+}
+
+async function setSigners(
+  signers: PublicKey[],
+  threshold: number,
+  coSigners: Keypair[] = []
+): Promise<void> {
+  await program.methods
+    .setSigners(signers, threshold)
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts(
+      coSigners.map((signer) => ({
+        pubkey: signer.publicKey,
+        isSigner: true,
+        isWritable: false,
+      }))
+    )
+    .signers(coSigners)
+    .rpc();
+}
+
+const RETIRE_CONFIRMATION = Array.from(Buffer.from('RETIRE_XENBLOCKS'));
+
+function retireProgramBuilder(
+  coSigners: Keypair[],
+  confirmation: number[] = RETIRE_CONFIRMATION
+) {
+  return program.methods
+    .retireProgram(confirmation)
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts(
+      coSigners.map((signer) => ({
+        pubkey: signer.publicKey,
+        isSigner: true,
+        isWritable: false,
+      }))
+    )
+    .signers(coSigners);
+}
+
+/** Call retire_program with `coSigners`, expecting a rejection. */
+async function retireProgram(
+  coSigners: Keypair[],
+  confirmation: number[] = RETIRE_CONFIRMATION
+): Promise<void> {
+  await retireProgramBuilder(coSigners, confirmation).rpc();
+}
+
+/**
+ * Simulate retire_program with `coSigners`. Accepted calls are only
+ * simulated, since a committed retire would end every other suite.
+ */
+async function simulateRetireProgram(coSigners: Keypair[]): Promise<void> {
+  await retireProgramBuilder(coSigners).simulate();
+}
+
+describe.skipIf(!hasValidator)('multisig threshold on-chain tests', () => {
+  const coSigners = [Keypair.generate(), Keypair.generate()];
+  let recordPDA: PublicKey;
+
+  beforeAll(async () => {
+    await setupSuite();
+
+    recordPDA = await initializeRecord(randomEthAddress());
+    await setSigners(coSigners.map((signer) => signer.publicKey), 2);
+  });
+
+  afterAll(async () => {
+    // Leave the authority-only path for the other suites
+    await setSigners([], 0, coSigners);
+  });
+
+  describe('correct_record', () => {
+    it('should reject a call below the threshold', async () => {
+      await expect(
+        correctRecord(recordPDA, coSigners.slice(0, 1))
+      ).rejects.toThrow(/Unauthorized/);
+    });
+
+    it('should reject a call without co-signers', async () => {
+      await expect(correctRecord(recordPDA, [])).rejects.toThrow(
+        /Unauthorized/
+      );
+    });
+
+    it('should accept a call that meets the threshold', async () => {
+      await correctRecord(recordPDA, coSigners);
+    });
+  });
+
+  describe('set_signers', () => {
+    it('should reject a lone authority lowering the threshold', async () => {
+      await expect(
+        setSigners(coSigners.map((signer) => signer.publicKey), 1)
+      ).rejects.toThrow(/Unauthorized/);
+    });
+
+    it('should accept a change that meets the threshold', async () => {
+      await setSigners(
+        coSigners.map((signer) => signer.publicKey),
+        2,
+        coSigners
+      );
+    });
+  });
+
+  describe('retire_program', () => {
+    it('should reject a call below the threshold', async () => {
+      await expect(retireProgram(coSigners.slice(0, 1))).rejects.toThrow(
+        /InsufficientSignersForRetire/
+      );
+    });
+
+    it('should accept a call that meets the threshold', async () => {
+      await simulateRetireProgram(coSigners);
+    });
+
+    it('should require two co-signers under a threshold of 1', async () => {
+      await setSigners(
+        coSigners.map((signer) => signer.publicKey),
+        1,
+        coSigners
+      );
+      try {
+        await expect(retireProgram(coSigners.slice(0, 1))).rejects.toThrow(
+          /InsufficientSignersForRetire/
+        );
+        await simulateRetireProgram(coSigners);
+      } finally {
+        await setSigners(
+          coSigners.map((signer) => signer.publicKey),
+          2,
+          coSigners
+        );
+      }
+    });
+
+    it('should need only the confirmation without a signer set', async () => {
+      await setSigners([], 0, coSigners);
+      try {
+        await simulateRetireProgram([]);
+      } finally {
+        await setSigners(coSigners.map((signer) => signer.publicKey), 2);
+      }
+    });
+
+    it('should reject a wrong confirmation', async () => {
+      const confirmation = Array.from(Buffer.from('RETIRE_XENBLOCKZ'));
+
+      await expect(retireProgram(coSigners, confirmation)).rejects.toThrow(
+        /RetireNotConfirmed/
+      );
+    });
+  });
+});
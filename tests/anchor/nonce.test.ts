@@ -0,0 +1,156 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+async function credit(
+  recordPDA: PublicKey,
+  expectedNonce: number
+): Promise<void> {
+  await program.methods
+    .updateRecordV2(
+      new anchor.BN(1_000),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      true,
+      Array(16).fill(0),
+      new anchor.BN(expectedNonce)
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      operator: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+async function batchCredit(
+  recordPDA: PublicKey,
+  expectedNonce: number
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  await program.methods
+    .batchUpdateRecords(
+      [
+        {
+          xnmAmount: new anchor.BN(1_000),
+          xblkAmount: zero,
+          xuniAmount: zero,
+          nativeAmount: zero,
+          expectedNonce: new anchor.BN(expectedNonce),
+        },
+      ],
+      false,
+      Array(16).fill(0)
+    )
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts([
+      { pubkey: recordPDA, isSigner: false, isWritable: true },
+      {
+        pubkey: await recordBlacklistPDA(recordPDA),
+        isSigner: false,
+        isWritable: false,
+      },
+    ])
+    .rpc();
+}
+
+async function correctRecord(
+  recordPDA: PublicKey,
+  expectedNonce: number
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  await program.methods
+    .correctRecord(
+      new anchor.BN(100),
+      zero,
+      zero,
+      zero,
+      new anchor.BN(expectedNonce)
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('record nonce on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('update_record_v2 expected_nonce', () => {
+    it('should increment the nonce on each credit', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 0);
+      await credit(recordPDA, 1);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.nonce.toString()).toBe('2');
+      expect(record.xnmAirdropped.toString()).toBe('2000');
+    });
+
+    it('should reject a resubmitted update', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 0);
+
+      await expect(credit(recordPDA, 0)).rejects.toThrow(/NonceMismatch/);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+  });
+
+  describe('batch_update_records expected_nonce', () => {
+    it('should credit an entry quoting the current nonce', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await batchCredit(recordPDA, 0);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.nonce.toString()).toBe('1');
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+
+    it('should reject a resubmitted entry', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await batchCredit(recordPDA, 0);
+
+      await expect(batchCredit(recordPDA, 0)).rejects.toThrow(
+        /NonceMismatch/
+      );
+    });
+  });
+
+  describe('correct_record expected_nonce', () => {
+    it('should advance the nonce and reject a replay', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 0);
+      await correctRecord(recordPDA, 1);
+
+      await expect(correctRecord(recordPDA, 1)).rejects.toThrow(
+        /NonceMismatch/
+      );
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.nonce.toString()).toBe('2');
+      expect(record.xnmAirdropped.toString()).toBe('900');
+    });
+  });
+});
@@ -0,0 +1,200 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  PROGRAM_ID,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  deriveRecordPDA,
+  recordBlacklistPDA,
+  deriveRegistryPDA,
+} from './helpers';
+import { deriveBlacklistPDA, deriveOperatorPDA } from '../../src/onchain/pda';
+
+async function creditAs(
+  signer: Keypair,
+  recordPDA: PublicKey,
+  operatorPDA: PublicKey | null
+): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordV2(
+      new anchor.BN(1_000),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      true,
+      Array(16).fill(0),
+      nonce
+    )
+    .accounts({
+      authority: signer.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      operator: operatorPDA,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .signers([signer])
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('operator on-chain tests', () => {
+  const operator = Keypair.generate();
+  const [operatorPDA] = deriveOperatorPDA(PROGRAM_ID, operator.publicKey);
+  let recordPDA: PublicKey;
+
+  beforeAll(async () => {
+    await setupSuite();
+
+    recordPDA = await initializeRecord(randomEthAddress());
+    await program.methods
+      .addOperator(operator.publicKey)
+      .accounts({
+        authority: authority.publicKey,
+        state: statePDA,
+        operator: operatorPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  describe('update_record_v2', () => {
+    it('should accept a credit signed by an operator', async () => {
+      await creditAs(operator, recordPDA, operatorPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+
+    it('should reject a key that is not an operator', async () => {
+      await expect(
+        creditAs(Keypair.generate(), recordPDA, null)
+      ).rejects.toThrow(/Unauthorized/);
+    });
+  });
+
+  describe('initialize_and_update_v2', () => {
+    it('should let an operator create and credit a record', async () => {
+      const ethAddress = randomEthAddress();
+      const ethBytes = Buffer.from(ethAddress);
+      const [newRecordPDA] = deriveRecordPDA(ethAddress);
+      const solWallet = Keypair.generate().publicKey;
+      await program.methods
+        .initializeAndUpdateV2(
+          Array.from(ethBytes),
+          new anchor.BN(500),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          solWallet,
+          []
+        )
+        .accounts({
+          authority: operator.publicKey,
+          payer: authority.publicKey,
+          state: statePDA,
+          airdropRecord: newRecordPDA,
+          airdropRun: runPDA,
+          systemProgram: SystemProgram.programId,
+          operator: operatorPDA,
+          blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+          ethRegistry: deriveRegistryPDA(ethAddress)[0],
+        })
+        .signers([operator])
+        .rpc();
+
+      const record = await program.account.airdropRecordV2.fetch(newRecordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('500');
+    });
+  });
+
+  describe('authority-only record instructions', () => {
+    it('should reject an operator creating an empty record', async () => {
+      const ethAddress = randomEthAddress();
+      const ethBytes = Buffer.from(ethAddress);
+      const [newRecordPDA] = deriveRecordPDA(ethAddress);
+      const solWallet = Keypair.generate().publicKey;
+      await expect(
+        program.methods
+          .initializeRecordV2(
+            Array.from(ethBytes),
+            solWallet,
+            new anchor.BN(0),
+            null
+          )
+          .accounts({
+            authority: operator.publicKey,
+            payer: authority.publicKey,
+            state: statePDA,
+            airdropRecord: newRecordPDA,
+            airdropRun: runPDA,
+            systemProgram: SystemProgram.programId,
+            blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+            ethRegistry: deriveRegistryPDA(ethAddress)[0],
+          })
+          .signers([operator])
+          .rpc()
+      ).rejects.toThrow(/Unauthorized/);
+    });
+
+    it('should reject an operator calling update_record_struct', async () => {
+      const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+      const zero = new anchor.BN(0);
+      await expect(
+        program.methods
+          .updateRecordStruct(
+            { xnm: new anchor.BN(1), xblk: zero, xuni: zero, native: zero },
+            true,
+            Array(16).fill(0),
+            nonce
+          )
+          .accounts({
+            authority: operator.publicKey,
+            state: statePDA,
+            airdropRecord: recordPDA,
+            airdropRun: null,
+            blacklist: await recordBlacklistPDA(recordPDA),
+          })
+          .signers([operator])
+          .rpc()
+      ).rejects.toThrow(/Unauthorized/);
+    });
+  });
+
+  describe('set_pause', () => {
+    it('should still require the authority', async () => {
+      await expect(
+        program.methods
+          .setPause(true)
+          .accounts({ authority: operator.publicKey, state: statePDA })
+          .signers([operator])
+          .rpc()
+      ).rejects.toThrow(/Unauthorized/);
+    });
+  });
+
+  describe('remove_operator', () => {
+    it('should reject writes from a revoked operator', async () => {
+      await program.methods
+        .removeOperator()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          operator: operatorPDA,
+        })
+        .rpc();
+
+      await expect(creditAs(operator, recordPDA, null)).rejects.toThrow(
+        /Unauthorized/
+      );
+    });
+  });
+});
@@ -0,0 +1,129 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+/** A record whose wallet prefers to receive XBLK only */
+async function initializeXblkOnlyRecord(): Promise<PublicKey> {
+  const wallet = Keypair.generate();
+  const recordPDA = await initializeRecord(
+    randomEthAddress(),
+    wallet.publicKey
+  );
+  await program.methods
+    .setPreference({ xblk: {} })
+    .accounts({ wallet: wallet.publicKey, airdropRecord: recordPDA })
+    .signers([wallet])
+    .rpc();
+  return recordPDA;
+}
+
+/** Credit 1,000 XNM and 2,000 XBLK to a record */
+async function creditXnmAndXblk(recordPDA: PublicKey): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordV2(
+      new anchor.BN(1_000),
+      new anchor.BN(2_000),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      true,
+      Array(16).fill(0),
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      operator: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+/** Credit 1,000 XNM and 2,000 XBLK to a record through batch_update_records */
+async function batchCreditXnmAndXblk(recordPDA: PublicKey): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .batchUpdateRecords(
+      [
+        {
+          xnmAmount: new anchor.BN(1_000),
+          xblkAmount: new anchor.BN(2_000),
+          xuniAmount: new anchor.BN(0),
+          nativeAmount: new anchor.BN(0),
+          expectedNonce: nonce,
+        },
+      ],
+      false,
+      Array(16).fill(0)
+    )
+    .accounts({ authority: authority.publicKey, state: statePDA })
+    .remainingAccounts([
+      { pubkey: recordPDA, isSigner: false, isWritable: true },
+      {
+        pubkey: await recordBlacklistPDA(recordPDA),
+        isSigner: false,
+        isWritable: false,
+      },
+    ])
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('preferred token on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('set_preference', () => {
+    it('should credit only the preferred token while respected', async () => {
+      const recordPDA = await initializeXblkOnlyRecord();
+      await setConfig('setRespectPreference', true);
+      try {
+        await creditXnmAndXblk(recordPDA);
+      } finally {
+        await setConfig('setRespectPreference', false);
+      }
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.preferredToken).toEqual({ xblk: {} });
+      expect(record.xnmAirdropped.toString()).toBe('0');
+      expect(record.xblkAirdropped.toString()).toBe('2000');
+    });
+
+    it('should apply the preference to batch credits', async () => {
+      const recordPDA = await initializeXblkOnlyRecord();
+      await setConfig('setRespectPreference', true);
+      try {
+        await batchCreditXnmAndXblk(recordPDA);
+      } finally {
+        await setConfig('setRespectPreference', false);
+      }
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('0');
+      expect(record.xblkAirdropped.toString()).toBe('2000');
+    });
+
+    it('should credit every token while not respected', async () => {
+      const recordPDA = await initializeXblkOnlyRecord();
+
+      await creditXnmAndXblk(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      expect(record.xblkAirdropped.toString()).toBe('2000');
+    });
+  });
+});
@@ -0,0 +1,142 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  program,
+  provider,
+  authority,
+  setupSuite,
+  setConfig,
+  anchor,
+  randomEthAddress,
+  initializeRecord,
+  PROGRAM_ID,
+} from './helpers';
+
+const [accumulatorPDA] = PublicKey.findProgramAddressSync(
+  [Buffer.from('reconcile')],
+  PROGRAM_ID
+);
+
+async function beginReconcile(): Promise<void> {
+  await program.methods
+    .beginReconcile()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      accumulator: accumulatorPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+async function reconcileTotals(records: PublicKey[]): Promise<void> {
+  await program.methods
+    .reconcileTotals()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      accumulator: accumulatorPDA,
+    })
+    .remainingAccounts(
+      records.map((pubkey) => ({
+        pubkey,
+        isSigner: false,
+        isWritable: false,
+      }))
+    )
+    .rpc();
+}
+
+async function finishReconcile(): Promise<void> {
+  await program.methods
+    .finishReconcile()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      accumulator: accumulatorPDA,
+    })
+    .rpc();
+}
+
+/** Every record account, in the ascending key order reconcile_totals needs */
+async function allRecords(): Promise<{ pubkey: PublicKey; xnm: bigint }[]> {
+  const accounts = await provider.connection.getProgramAccounts(PROGRAM_ID, {
+    filters: [{ memcmp: program.coder.accounts.memcmp('airdropRecordV2') }],
+  });
+  return accounts
+    .map(({ pubkey, account }) => ({
+      pubkey,
+      // xnm_airdropped follows the discriminator and eth_address in every
+      // layout
+      xnm: account.data.readBigUInt64LE(8 + 42),
+    }))
+    .sort((a, b) => Buffer.compare(a.pubkey.toBuffer(), b.pubkey.toBuffer()));
+}
+
+async function cancelReconcile(): Promise<void> {
+  await program.methods
+    .cancelReconcile()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      accumulator: accumulatorPDA,
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('totals reconciliation on-chain tests', () => {
+  let recordPDA: PublicKey;
+
+  beforeAll(async () => {
+    await setupSuite();
+    await initializeRecord(randomEthAddress());
+    recordPDA = await initializeRecord(randomEthAddress());
+  });
+
+  describe('finish_reconcile', () => {
+    it('should reject a scan that missed records', async () => {
+      await beginReconcile();
+      try {
+        await reconcileTotals([recordPDA]);
+
+        await expect(finishReconcile()).rejects.toThrow(
+          /ReconcileIncomplete/
+        );
+      } finally {
+        await cancelReconcile();
+      }
+    });
+
+    it('should rebuild the count and totals of a grown state', async () => {
+      // grow_state leaves the appended record_count zero-filled
+      await setConfig('reconcileRecordCount', new anchor.BN(0));
+      const records = await allRecords();
+
+      await beginReconcile();
+      for (let i = 0; i < records.length; i += 20) {
+        await reconcileTotals(
+          records.slice(i, i + 20).map(({ pubkey }) => pubkey)
+        );
+      }
+      await finishReconcile();
+
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.recordCount.toString()).toBe(String(records.length));
+      const total = records.reduce((sum, { xnm }) => sum + xnm, 0n);
+      expect(state.xnmAirdropped.toString()).toBe(total.toString());
+    });
+  });
+
+  describe('cancel_reconcile', () => {
+    it('should close the accumulator so a new scan can begin', async () => {
+      await beginReconcile();
+      await cancelReconcile();
+
+      expect(
+        await provider.connection.getAccountInfo(accumulatorPDA)
+      ).toBeNull();
+    });
+  });
+});
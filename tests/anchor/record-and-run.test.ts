@@ -0,0 +1,83 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupProgram,
+  createRun,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+/** Credit 1,000 XNM to a record and to `runPDA`'s totals */
+async function updateRecordAndRun(
+  recordPDA: PublicKey,
+  runPDA: PublicKey
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordAndRun(
+      { xnm: new anchor.BN(1_000), xblk: zero, xuni: zero, native: zero },
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('update_record_and_run on-chain tests', () => {
+  beforeAll(async () => {
+    await setupProgram();
+  });
+
+  describe('update_record_and_run', () => {
+    it('should credit the record and the run together', async () => {
+      const runPDA = await createRun(false);
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await updateRecordAndRun(recordPDA, runPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.totalRecipients).toBe(1);
+      expect(run.totalXnmAmount.toString()).toBe('1000');
+    });
+
+    it('should reject a finalized run', async () => {
+      const runPDA = await createRun(false);
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await program.methods
+        .finalizeRun()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          airdropRun: runPDA,
+        })
+        .rpc();
+
+      await expect(updateRecordAndRun(recordPDA, runPDA)).rejects.toThrow(
+        /RunClosed/
+      );
+    });
+
+    it('should reject amounts in a dry run', async () => {
+      const runPDA = await createRun();
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(updateRecordAndRun(recordPDA, runPDA)).rejects.toThrow(
+        /DryRunWriteBlocked/
+      );
+    });
+  });
+});
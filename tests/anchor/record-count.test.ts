@@ -0,0 +1,68 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  recordRegistryPDA,
+} from './helpers';
+
+async function recordCount(): Promise<bigint> {
+  const state = await program.account.globalStateV2.fetch(statePDA);
+  return BigInt(state.recordCount.toString());
+}
+
+describe.skipIf(!hasValidator)('record_count on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('record creation and close_record_v2', () => {
+    it('should count a record while it exists', async () => {
+      const before = await recordCount();
+
+      const recordPDA: PublicKey = await initializeRecord(randomEthAddress());
+      expect(await recordCount()).toBe(before + 1n);
+
+      await program.methods
+        .closeRecordV2({ cleanup: {} })
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          ethRegistry: await recordRegistryPDA(recordPDA),
+        })
+        .rpc();
+      expect(await recordCount()).toBe(before);
+    });
+  });
+
+  describe('reconcile_record_count', () => {
+    it('should reject a count above the sanity ceiling', async () => {
+      await expect(
+        setConfig('reconcileRecordCount', new anchor.BN(100_000_001))
+      ).rejects.toThrow(/RecordCountTooHigh/);
+    });
+
+    it('should raise a zero-filled count after grow_state', async () => {
+      const count = await recordCount();
+      await setConfig('reconcileRecordCount', new anchor.BN(0));
+
+      await setConfig('reconcileRecordCount', new anchor.BN(count.toString()));
+      expect(await recordCount()).toBe(count);
+    });
+
+    it('should accept the current count', async () => {
+      const count = await recordCount();
+
+      await setConfig('reconcileRecordCount', new anchor.BN(count.toString()));
+      expect(await recordCount()).toBe(count);
+    });
+  });
+});
@@ -0,0 +1,79 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+  recordRegistryPDA,
+  credit,
+} from './helpers';
+
+async function setLock(
+  recordPDA: PublicKey,
+  lockedUntil: number
+): Promise<void> {
+  await program.methods
+    .setLock(new anchor.BN(lockedUntil))
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('record time lock on-chain tests', () => {
+  const inOneHour = () => Math.floor(Date.now() / 1000) + 3_600;
+
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('set_lock', () => {
+    it('should reject a credit while locked', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setLock(recordPDA, inOneHour());
+
+      await expect(credit(recordPDA)).rejects.toThrow(/RecordTimeLocked/);
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('0');
+    });
+
+    it('should reject a close while locked', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setLock(recordPDA, inOneHour());
+
+      await expect(
+        program.methods
+          .closeRecordV2({ cleanup: {} })
+          .accounts({
+            authority: authority.publicKey,
+            state: statePDA,
+            airdropRecord: recordPDA,
+            ethRegistry: await recordRegistryPDA(recordPDA),
+          })
+          .rpc()
+      ).rejects.toThrow(/RecordTimeLocked/);
+    });
+
+    it('should accept a credit once released', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setLock(recordPDA, inOneHour());
+
+      await setLock(recordPDA, 0);
+      await credit(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.lockedUntil.toNumber()).toBe(0);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+  });
+});
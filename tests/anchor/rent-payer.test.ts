@@ -0,0 +1,95 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  provider,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  deriveRecordPDA,
+  deriveRegistryPDA,
+  PROGRAM_ID,
+} from './helpers';
+import { deriveAirdropRunPDA, deriveBlacklistPDA } from '../../src/onchain/pda';
+
+describe.skipIf(!hasValidator)('rent payer on-chain tests', () => {
+  const payer = Keypair.generate();
+
+  async function balanceOf(account: Keypair): Promise<number> {
+    return provider.connection.getBalance(account.publicKey);
+  }
+
+  beforeAll(async () => {
+    await setupSuite();
+
+    // Fund the payer; the authority stays the fee payer
+    const sig = await provider.connection.requestAirdrop(
+      payer.publicKey,
+      1_000_000_000
+    );
+    await provider.connection.confirmTransaction(sig);
+  });
+
+  describe('initialize_record_v2', () => {
+    it('should charge the record rent to the payer', async () => {
+      const ethAddress = randomEthAddress();
+      const solWallet = Keypair.generate().publicKey;
+      const [recordPDA] = deriveRecordPDA(ethAddress);
+      const [registryPDA] = deriveRegistryPDA(ethAddress);
+      const before = await balanceOf(payer);
+
+      await program.methods
+        .initializeRecordV2(
+          Array.from(Buffer.from(ethAddress)),
+          solWallet,
+          new anchor.BN(0),
+          null
+        )
+        .accounts({
+          authority: authority.publicKey,
+          payer: payer.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          airdropRun: runPDA,
+          systemProgram: SystemProgram.programId,
+          blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+          ethRegistry: registryPDA,
+        })
+        .signers([payer])
+        .rpc();
+
+      const rent =
+        (await provider.connection.getBalance(recordPDA)) +
+        (await provider.connection.getBalance(registryPDA));
+      expect(before - (await balanceOf(payer))).toBe(rent);
+    });
+  });
+
+  describe('create_run_v2', () => {
+    it('should charge the run rent to the payer', async () => {
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      const runId = BigInt(state.runCounter.toString()) + 1n;
+      const [newRunPDA] = deriveAirdropRunPDA(PROGRAM_ID, runId);
+      const before = await balanceOf(payer);
+
+      await program.methods
+        .createRunV2(true, new anchor.BN(0), 0, new anchor.BN(0), null)
+        .accounts({
+          authority: authority.publicKey,
+          payer: payer.publicKey,
+          state: statePDA,
+          airdropRun: newRunPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc();
+
+      const rent = await provider.connection.getBalance(newRunPDA);
+      expect(before - (await balanceOf(payer))).toBe(rent);
+    });
+  });
+});
@@ -0,0 +1,81 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  createRun,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+  credit,
+} from './helpers';
+
+async function repairRecord(
+  recordPDA: PublicKey,
+  xnmAmount: number
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .repairRecord(new anchor.BN(xnmAmount), zero, zero, zero, nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('repair_record on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite(false);
+  });
+
+  describe('repair_record', () => {
+    it('should populate a record left empty', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await repairRecord(recordPDA, 1_000);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.totalXnmAmount.toString()).toBe('1000');
+    });
+
+    it('should refuse to overwrite a populated record', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+
+      await expect(repairRecord(recordPDA, 5_000)).rejects.toThrow(
+        /RecordAlreadyPopulated/
+      );
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+
+    it('should reject an all-zero repair', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(repairRecord(recordPDA, 0)).rejects.toThrow(
+        /ZeroAmountUpdate/
+      );
+    });
+
+    it('should reject a record created in another run', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await createRun(false);
+
+      await expect(repairRecord(recordPDA, 1_000)).rejects.toThrow(
+        /NotCreatedInRun/
+      );
+    });
+  });
+});
@@ -0,0 +1,101 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+  credit,
+} from './helpers';
+
+async function rescindLastUpdate(recordPDA: PublicKey): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .rescindLastUpdate(nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+async function correctRecord(
+  recordPDA: PublicKey,
+  xnmSub: number
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .correctRecord(new anchor.BN(xnmSub), zero, zero, zero, nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('rescind and correct on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('rescind_last_update', () => {
+    it('should undo only the most recent credit', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+      await credit(recordPDA, 300);
+
+      await rescindLastUpdate(recordPDA);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      expect(record.lastDeltaXnm.toString()).toBe('0');
+    });
+
+    it('should reject a second rescind', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA);
+      await rescindLastUpdate(recordPDA);
+
+      await expect(rescindLastUpdate(recordPDA)).rejects.toThrow(
+        /NoUpdateToRescind/
+      );
+    });
+  });
+
+  describe('correct_record', () => {
+    it('should subtract the correction from the record', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+
+      await correctRecord(recordPDA, 400);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('600');
+    });
+
+    it('should leave nothing to rescind afterwards', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+      await correctRecord(recordPDA, 1_000);
+
+      await expect(rescindLastUpdate(recordPDA)).rejects.toThrow(
+        /NoUpdateToRescind/
+      );
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('0');
+    });
+  });
+});
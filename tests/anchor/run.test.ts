@@ -0,0 +1,564 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupProgram,
+  setConfig,
+  createRun,
+  emittedEvents,
+  PROGRAM_ID,
+} from './helpers';
+import { deriveAirdropRunPDA } from '../../src/onchain/pda';
+
+async function recordRunFees(
+  runPDA: PublicKey,
+  lamports: number
+): Promise<string> {
+  return program.methods
+    .recordRunFees(new anchor.BN(lamports))
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+    })
+    .rpc({ commitment: 'confirmed' });
+}
+
+/** Create the next run with create_run_with_budget and return its PDA */
+async function createRunWithBudget(
+  dryRun: boolean,
+  budget: number,
+  label: number[] | null = null
+): Promise<PublicKey> {
+  const state = await program.account.globalStateV2.fetch(statePDA);
+  const runId = BigInt(state.runCounter.toString()) + 1n;
+  const [runPDA] = deriveAirdropRunPDA(PROGRAM_ID, runId);
+  await program.methods
+    .createRunWithBudget(
+      dryRun,
+      new anchor.BN(0),
+      new anchor.BN(budget),
+      label,
+      null,
+      null
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return runPDA;
+}
+
+/** Create the next real run with create_run_v2 at `snapshotBlock` */
+async function createRunAtSnapshot(snapshotBlock: number): Promise<PublicKey> {
+  const state = await program.account.globalStateV2.fetch(statePDA);
+  const runId = BigInt(state.runCounter.toString()) + 1n;
+  const [runPDA] = deriveAirdropRunPDA(PROGRAM_ID, runId);
+  await program.methods
+    .createRunV2(false, new anchor.BN(snapshotBlock), 0, new anchor.BN(0), null)
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return runPDA;
+}
+
+/** update_run_totals_v2 reporting XNM and XBLK totals for one recipient */
+async function updateRunTotals(
+  runPDA: PublicKey,
+  totalAmount: number,
+  xnmAmount: number,
+  xblkAmount: number,
+  autoFinalize: boolean = false
+): Promise<string> {
+  return program.methods
+    .updateRunTotalsV2(
+      1,
+      new anchor.BN(totalAmount),
+      new anchor.BN(xnmAmount),
+      new anchor.BN(xblkAmount),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      autoFinalize
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+    })
+    .rpc({ commitment: 'confirmed' });
+}
+
+async function runInfo(runId: bigint) {
+  return program.methods
+    .runInfo(new anchor.BN(runId.toString()))
+    .accounts({ airdropRun: deriveAirdropRunPDA(PROGRAM_ID, runId)[0] })
+    .view();
+}
+
+async function setRunDate(
+  runPDA: PublicKey,
+  previousRunPDA: PublicKey | null,
+  runDate: number,
+  nextRunPDA: PublicKey | null = null
+): Promise<void> {
+  await program.methods
+    .setRunDate(new anchor.BN(runDate))
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+      previousRun: previousRunPDA,
+      nextRun: nextRunPDA,
+    })
+    .rpc();
+}
+
+async function finalizeRun(runPDA: PublicKey): Promise<string> {
+  return program.methods
+    .finalizeRun()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+    })
+    .rpc({ commitment: 'confirmed' });
+}
+
+async function setRunReference(runPDA: PublicKey): Promise<void> {
+  await program.methods
+    .setRunReference(Array(32).fill(9))
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('run lifecycle on-chain tests', () => {
+  beforeAll(async () => {
+    await setupProgram();
+  });
+
+  describe('create_run_v2', () => {
+    it('should store and emit the reference hash', async () => {
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      const runId = BigInt(state.runCounter.toString()) + 1n;
+      const [runPDA] = deriveAirdropRunPDA(PROGRAM_ID, runId);
+      const referenceHash = Array(32).fill(7);
+
+      const signature = await program.methods
+        .createRunV2(
+          true,
+          new anchor.BN(0),
+          0,
+          new anchor.BN(0),
+          referenceHash
+        )
+        .accounts({
+          authority: authority.publicKey,
+          payer: authority.publicKey,
+          state: statePDA,
+          airdropRun: runPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.referenceHash).toEqual(referenceHash);
+      const event = (await emittedEvents(signature)).find(
+        (e) => e.name === 'runCreated'
+      );
+      expect(event?.data.referenceHash).toEqual(referenceHash);
+    });
+  });
+
+  describe('create_run_with_budget', () => {
+    it('should create the run with its budget and label', async () => {
+      const label = Array(32).fill(1);
+      const runPDA = await createRunWithBudget(false, 50_000, label);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.budget.toString()).toBe('50000');
+      expect(run.label).toEqual(label);
+      expect(run.dryRun).toBe(false);
+    });
+
+    it('should reject a real run without a budget', async () => {
+      await expect(createRunWithBudget(false, 0)).rejects.toThrow(/ZeroBudget/);
+    });
+
+    it('should let a dry run go without a budget', async () => {
+      const runPDA = await createRunWithBudget(true, 0);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.budget.toString()).toBe('0');
+    });
+  });
+
+  describe('latest_run', () => {
+    it('should point at the run created last', async () => {
+      const first = await createRun();
+      let state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.latestRun.equals(first)).toBe(true);
+
+      const second = await createRunWithBudget(true, 0);
+      state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.latestRun.equals(second)).toBe(true);
+    });
+  });
+
+  describe('update_run_totals_v2', () => {
+    it('should reject a total below the per-token sum', async () => {
+      const runPDA = await createRun();
+
+      await expect(
+        updateRunTotals(runPDA, 2_999, 1_000, 2_000)
+      ).rejects.toThrow(/InconsistentRunTotals/);
+    });
+
+    it('should accept a total covering the per-token sum', async () => {
+      const runPDA = await createRun();
+      await updateRunTotals(runPDA, 3_000, 1_000, 2_000);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.totalAmount.toString()).toBe('3000');
+      expect(run.totalXblkAmount.toString()).toBe('2000');
+    });
+
+    it('should accept a legacy total without per-token amounts', async () => {
+      const runPDA = await createRun();
+      await updateRunTotals(runPDA, 3_000, 0, 0);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.totalAmount.toString()).toBe('3000');
+    });
+
+    it('should leave the run active without auto_finalize', async () => {
+      const runPDA = await createRun();
+      await updateRunTotals(runPDA, 1_000, 0, 0);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.status).toEqual({ active: {} });
+      await updateRunTotals(runPDA, 2_000, 0, 0);
+    });
+
+    it('should complete the run with auto_finalize', async () => {
+      const runPDA = await createRun();
+      const signature = await updateRunTotals(runPDA, 1_000, 0, 0, true);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.status).toEqual({ completed: {} });
+      expect(run.totalAmount.toString()).toBe('1000');
+      const event = (await emittedEvents(signature)).find(
+        (e) => e.name === 'runFinalized'
+      );
+      expect(String(event?.data.runId)).toBe(run.runId.toString());
+    });
+
+    it('should reject totals for a finalized run', async () => {
+      const runPDA = await createRun();
+      await updateRunTotals(runPDA, 1_000, 0, 0, true);
+
+      await expect(updateRunTotals(runPDA, 2_000, 0, 0)).rejects.toThrow(
+        /RunClosed/
+      );
+    });
+  });
+
+  describe('require_reference', () => {
+    beforeAll(async () => {
+      await setConfig('setRequireReference', true);
+    });
+
+    afterAll(async () => {
+      await setConfig('setRequireReference', false);
+    });
+
+    it('should reject totals for a real run without a reference', async () => {
+      const runPDA = await createRun(false);
+
+      await expect(updateRunTotals(runPDA, 1_000, 0, 0)).rejects.toThrow(
+        /MissingReference/
+      );
+    });
+
+    it('should accept totals once the reference is set', async () => {
+      const runPDA = await createRun(false);
+      await setRunReference(runPDA);
+
+      await updateRunTotals(runPDA, 1_000, 0, 0);
+    });
+
+    it('should reject a reference change on a finalized run', async () => {
+      const runPDA = await createRun();
+      await finalizeRun(runPDA);
+
+      await expect(setRunReference(runPDA)).rejects.toThrow(/RunClosed/);
+    });
+
+    it('should not require a reference on a dry run', async () => {
+      const runPDA = await createRun();
+
+      await updateRunTotals(runPDA, 1_000, 0, 0);
+    });
+  });
+
+  describe('run_info', () => {
+    it('should report an existing run', async () => {
+      const runPDA = await createRun();
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+
+      const info = await runInfo(BigInt(run.runId.toString()));
+      expect(info.pda.equals(runPDA)).toBe(true);
+      expect(info.exists).toBe(true);
+      expect(info.dryRun).toBe(true);
+      expect(info.status).toEqual({ active: {} });
+    });
+
+    it('should report a run that was never created', async () => {
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      const runId = BigInt(state.runCounter.toString()) + 1_000n;
+      const [runPDA] = deriveAirdropRunPDA(PROGRAM_ID, runId);
+
+      const info = await runInfo(runId);
+      expect(info.pda.equals(runPDA)).toBe(true);
+      expect(info.exists).toBe(false);
+    });
+  });
+
+  describe('get_run_token_totals', () => {
+    it('should report the per-token totals of a run', async () => {
+      const runPDA = await createRun();
+      await updateRunTotals(runPDA, 3_000, 1_000, 2_000);
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+
+      const totals = await program.methods
+        .getRunTokenTotals(run.runId)
+        .accounts({ airdropRun: runPDA })
+        .view();
+      expect(totals.xnmTotal.toString()).toBe('1000');
+      expect(totals.xblkTotal.toString()).toBe('2000');
+      expect(totals.xuniTotal.toString()).toBe('0');
+      expect(totals.recipients).toBe(1);
+      expect(totals.status).toEqual({ active: {} });
+    });
+  });
+
+  describe('set_min_interval_between_runs', () => {
+    beforeAll(async () => {
+      // Start the cooldown from a real run
+      await createRun(false);
+      await setConfig('setMinIntervalBetweenRuns', new anchor.BN(3_600));
+    });
+
+    afterAll(async () => {
+      await setConfig('setMinIntervalBetweenRuns', new anchor.BN(0));
+    });
+
+    it('should reject a real run during the cooldown', async () => {
+      await expect(createRun(false)).rejects.toThrow(/RunCooldownActive/);
+    });
+
+    it('should let dry runs through during the cooldown', async () => {
+      const runPDA = await createRun();
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.dryRun).toBe(true);
+    });
+
+    it('should reject a negative interval', async () => {
+      await expect(
+        setConfig('setMinIntervalBetweenRuns', new anchor.BN(-1))
+      ).rejects.toThrow(/InvalidInterval/);
+    });
+  });
+
+  describe('set_max_runs', () => {
+    afterAll(async () => {
+      await setConfig('setMaxRuns', new anchor.BN(0), false);
+    });
+
+    it('should reject a run past the cap', async () => {
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      await setConfig('setMaxRuns', state.runCounter, false);
+
+      await expect(createRun()).rejects.toThrow(/MaxRunsReached/);
+    });
+
+    it('should let dry runs past the cap when exempt', async () => {
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      await setConfig('setMaxRuns', state.runCounter, true);
+
+      await expect(createRun(false)).rejects.toThrow(/MaxRunsReached/);
+      const runPDA = await createRun();
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.dryRun).toBe(true);
+    });
+  });
+
+  describe('require_snapshot_block', () => {
+    beforeAll(async () => {
+      await setConfig('setRequireSnapshotBlock', true);
+    });
+
+    afterAll(async () => {
+      await setConfig('setRequireSnapshotBlock', false);
+    });
+
+    it('should reject a real run without a snapshot block', async () => {
+      await expect(createRunAtSnapshot(0)).rejects.toThrow(
+        /SnapshotBlockRequired/
+      );
+    });
+
+    it('should store the snapshot block of a real run', async () => {
+      const runPDA = await createRunAtSnapshot(19_000_000);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.snapshotBlock.toString()).toBe('19000000');
+    });
+
+    it('should not require a snapshot block for a dry run', async () => {
+      const runPDA = await createRun();
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.snapshotBlock.toString()).toBe('0');
+    });
+  });
+
+  describe('set_run_date', () => {
+    let earliestPDA: PublicKey;
+    let previousPDA: PublicKey;
+    let latestPDA: PublicKey;
+    let previousDate: number;
+
+    beforeAll(async () => {
+      earliestPDA = await createRun();
+      previousPDA = await createRun();
+      latestPDA = await createRun();
+      const previous = await program.account.airdropRunV2.fetch(previousPDA);
+      previousDate = previous.runDate.toNumber();
+      await setConfig('setEnforceMonotonicRunDates', true);
+    });
+
+    afterAll(async () => {
+      await setConfig('setEnforceMonotonicRunDates', false);
+    });
+
+    it('should reject a date before the previous run', async () => {
+      await expect(
+        setRunDate(latestPDA, previousPDA, previousDate - 1)
+      ).rejects.toThrow(/NonMonotonicRunDate/);
+    });
+
+    it('should reject moving an earlier run without the next run', async () => {
+      await expect(
+        setRunDate(previousPDA, earliestPDA, previousDate)
+      ).rejects.toThrow(/NonMonotonicRunDate/);
+    });
+
+    it('should reject moving an earlier run past the next run', async () => {
+      const latest = await program.account.airdropRunV2.fetch(latestPDA);
+
+      await expect(
+        setRunDate(
+          previousPDA,
+          earliestPDA,
+          latest.runDate.toNumber() + 1,
+          latestPDA
+        )
+      ).rejects.toThrow(/NonMonotonicRunDate/);
+    });
+
+    it('should move an earlier run between its neighbours', async () => {
+      await setRunDate(previousPDA, earliestPDA, previousDate, latestPDA);
+
+      const run = await program.account.airdropRunV2.fetch(previousPDA);
+      expect(run.runDate.toNumber()).toBe(previousDate);
+    });
+
+    it('should reject a date in the future', async () => {
+      const tomorrow = Math.floor(Date.now() / 1000) + 86_400;
+
+      await expect(
+        setRunDate(latestPDA, previousPDA, tomorrow)
+      ).rejects.toThrow(/RunDateInFuture/);
+    });
+
+    it('should move the latest run and the latest run date', async () => {
+      await setRunDate(latestPDA, previousPDA, previousDate);
+
+      const run = await program.account.airdropRunV2.fetch(latestPDA);
+      expect(run.runDate.toNumber()).toBe(previousDate);
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.latestRunDate.toNumber()).toBe(previousDate);
+    });
+
+    it('should allow any date while not enforced', async () => {
+      await setConfig('setEnforceMonotonicRunDates', false);
+      try {
+        await setRunDate(previousPDA, null, previousDate - 86_400);
+      } finally {
+        await setConfig('setEnforceMonotonicRunDates', true);
+      }
+
+      const run = await program.account.airdropRunV2.fetch(previousPDA);
+      expect(run.runDate.toNumber()).toBe(previousDate - 86_400);
+    });
+  });
+
+  describe('record_run_fees', () => {
+    it('should store the fees and emit RunFeesRecorded', async () => {
+      const runPDA = await createRun();
+      const signature = await recordRunFees(runPDA, 5_000);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.feesSpent.toString()).toBe('5000');
+      const event = (await emittedEvents(signature)).find(
+        (e) => e.name === 'runFeesRecorded'
+      );
+      expect(String(event?.data.feesSpent)).toBe('5000');
+    });
+
+    it('should reject fees on a finalized run', async () => {
+      const runPDA = await createRun();
+      await finalizeRun(runPDA);
+
+      await expect(recordRunFees(runPDA, 5_000)).rejects.toThrow(/RunClosed/);
+    });
+  });
+
+  describe('finalize_run', () => {
+    it('should emit RunFinalized with the recorded fees', async () => {
+      const runPDA = await createRun();
+      await recordRunFees(runPDA, 7_500);
+      const signature = await finalizeRun(runPDA);
+
+      const event = (await emittedEvents(signature)).find(
+        (e) => e.name === 'runFinalized'
+      );
+      expect(String(event?.data.feesSpent)).toBe('7500');
+    });
+
+    it('should reject a run that is already finalized', async () => {
+      const runPDA = await createRun();
+      await finalizeRun(runPDA);
+
+      await expect(finalizeRun(runPDA)).rejects.toThrow(/RunClosed/);
+    });
+  });
+});
@@ -0,0 +1,90 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  randomEthAddress,
+  deriveRecordPDA,
+  deriveRegistryPDA,
+  recordBlacklistPDA,
+  PROGRAM_ID,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+/** Create a record with an eligibility score in the current run */
+async function initializeScoredRecord(score: number): Promise<PublicKey> {
+  const ethAddress = randomEthAddress();
+  const solWallet = Keypair.generate().publicKey;
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  await program.methods
+    .initializeRecordV2(
+      Array.from(Buffer.from(ethAddress)),
+      solWallet,
+      new anchor.BN(score),
+      null
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+async function distributeByScore(
+  recordPDA: PublicKey,
+  rateBps: number
+): Promise<void> {
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .distributeByScore({ xblk: {} }, new anchor.BN(rateBps), nonce)
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('distribute_by_score on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('distribute_by_score', () => {
+    it('should credit the score times the rate in one token', async () => {
+      const recordPDA = await initializeScoredRecord(5_000);
+
+      // 20% of the score
+      await distributeByScore(recordPDA, 2_000);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.score.toString()).toBe('5000');
+      expect(record.scoreAmount.toString()).toBe('1000');
+      expect(record.xblkAirdropped.toString()).toBe('1000');
+      expect(record.xnmAirdropped.toString()).toBe('0');
+    });
+
+    it('should round the amount down', async () => {
+      const recordPDA = await initializeScoredRecord(3);
+
+      await distributeByScore(recordPDA, 5_000);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.scoreAmount.toString()).toBe('1');
+    });
+  });
+});
@@ -0,0 +1,70 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  program,
+  authority,
+  setupSuite,
+  randomEthAddress,
+  initializeRecord,
+  credit,
+  PROGRAM_ID,
+} from './helpers';
+
+function deriveSeasonPDA(season: number): PublicKey {
+  const seasonBytes = Buffer.alloc(2);
+  seasonBytes.writeUInt16LE(season);
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('season'), seasonBytes],
+    PROGRAM_ID
+  )[0];
+}
+
+describe.skipIf(!hasValidator)('season on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('advance_season', () => {
+    it('should snapshot the season totals and reset them', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+      const before = await program.account.globalStateV2.fetch(statePDA);
+      const summaryPDA = deriveSeasonPDA(before.currentSeason);
+
+      await program.methods
+        .advanceSeason()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          seasonSummary: summaryPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const summary = await program.account.seasonSummary.fetch(summaryPDA);
+      expect(summary.season).toBe(before.currentSeason);
+      expect(summary.xnmAirdropped.toString()).toBe(
+        before.seasonXnmAirdropped.toString()
+      );
+      const after = await program.account.globalStateV2.fetch(statePDA);
+      expect(after.currentSeason).toBe(before.currentSeason + 1);
+      expect(after.seasonXnmAirdropped.toString()).toBe('0');
+      expect(after.xnmAirdropped.toString()).toBe(
+        before.xnmAirdropped.toString()
+      );
+    });
+
+    it('should count new credits toward the new season only', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await credit(recordPDA, 1_000);
+
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      expect(state.seasonXnmAirdropped.toString()).toBe('1000');
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.lastCreditedSeason).toBe(state.currentSeason);
+    });
+  });
+});
@@ -0,0 +1,189 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupProgram,
+  createRun,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+  PROGRAM_ID,
+} from './helpers';
+
+/** Create the next sub-run of `runPDA` and return its PDA */
+async function createSubrun(runPDA: PublicKey): Promise<PublicKey> {
+  const run = await program.account.airdropRunV2.fetch(runPDA);
+  const runId = Buffer.alloc(8);
+  runId.writeBigUInt64LE(BigInt(run.runId.toString()));
+  const index = Buffer.alloc(2);
+  index.writeUInt16LE(run.subrunCount);
+  const [subrunPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from('subrun'), runId, index],
+    PROGRAM_ID
+  );
+  await program.methods
+    .createSubrun(Array(32).fill(0))
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+      subrun: subrunPDA,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return subrunPDA;
+}
+
+async function creditSubrun(
+  recordPDA: PublicKey,
+  runPDA: PublicKey,
+  subrunPDA: PublicKey,
+  xnm: number
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordAndSubrun(
+      { xnm: new anchor.BN(xnm), xblk: zero, xuni: zero, native: zero },
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      subrun: subrunPDA,
+      airdropRun: runPDA,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+/** Finalize `runPDA`, passing `subruns` to be rolled up in index order */
+async function finalizeRun(
+  runPDA: PublicKey,
+  subruns: PublicKey[]
+): Promise<void> {
+  await program.methods
+    .finalizeRun()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRun: runPDA,
+    })
+    .remainingAccounts(
+      subruns.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+    )
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('sub-run on-chain tests', () => {
+  beforeAll(async () => {
+    await setupProgram();
+  });
+
+  describe('update_record_and_subrun', () => {
+    it('should attribute the credit to the sub-run', async () => {
+      const runPDA = await createRun(false);
+      const subrunPDA = await createSubrun(runPDA);
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await creditSubrun(recordPDA, runPDA, subrunPDA, 1_000);
+
+      const subrun = await program.account.subRun.fetch(subrunPDA);
+      expect(subrun.totalXnmAmount.toString()).toBe('1000');
+      expect(subrun.totalRecipients).toBe(1);
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+
+    it('should reject a credit once the parent run is finalized', async () => {
+      const runPDA = await createRun(false);
+      const subrunPDA = await createSubrun(runPDA);
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await finalizeRun(runPDA, [subrunPDA]);
+
+      await expect(
+        creditSubrun(recordPDA, runPDA, subrunPDA, 1_000)
+      ).rejects.toThrow(/RunClosed/);
+    });
+
+    it('should reject amounts under a dry-run parent', async () => {
+      const runPDA = await createRun(true);
+      const subrunPDA = await createSubrun(runPDA);
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(
+        creditSubrun(recordPDA, runPDA, subrunPDA, 1_000)
+      ).rejects.toThrow(/DryRunWriteBlocked/);
+    });
+
+    it("should reject a run that isn't the sub-run's parent", async () => {
+      const runPDA = await createRun(false);
+      const subrunPDA = await createSubrun(runPDA);
+      const otherRunPDA = await createRun(false);
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(
+        creditSubrun(recordPDA, otherRunPDA, subrunPDA, 1_000)
+      ).rejects.toThrow(/ConstraintSeeds/);
+    });
+  });
+
+  describe('update_record_and_run', () => {
+    it('should reject a run that has sub-runs', async () => {
+      const runPDA = await createRun(false);
+      await createSubrun(runPDA);
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const zero = new anchor.BN(0);
+      const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+
+      await expect(
+        program.methods
+          .updateRecordAndRun(
+            {
+              xnm: new anchor.BN(1_000),
+              xblk: zero,
+              xuni: zero,
+              native: zero,
+            },
+            nonce
+          )
+          .accounts({
+            authority: authority.publicKey,
+            state: statePDA,
+            airdropRecord: recordPDA,
+            airdropRun: runPDA,
+            blacklist: await recordBlacklistPDA(recordPDA),
+          })
+          .rpc()
+      ).rejects.toThrow(/RunHasSubRuns/);
+    });
+  });
+
+  describe('finalize_run', () => {
+    it('should roll up the sub-runs before completing', async () => {
+      const runPDA = await createRun(false);
+      const subrunPDA = await createSubrun(runPDA);
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await creditSubrun(recordPDA, runPDA, subrunPDA, 1_000);
+
+      await finalizeRun(runPDA, [subrunPDA]);
+
+      const run = await program.account.airdropRunV2.fetch(runPDA);
+      expect(run.status).toEqual({ completed: {} });
+      expect(run.totalXnmAmount.toString()).toBe('1000');
+      expect(run.totalRecipients).toBe(1);
+    });
+
+    it('should reject a run finalized without its sub-runs', async () => {
+      const runPDA = await createRun(false);
+      await createSubrun(runPDA);
+
+      await expect(finalizeRun(runPDA, [])).rejects.toThrow(/SubRunMismatch/);
+    });
+  });
+});
@@ -0,0 +1,82 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, Keypair } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  provider,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+  recordRegistryPDA,
+  grantClaim,
+} from './helpers';
+
+async function forfeit(recordPDA: PublicKey): Promise<void> {
+  await program.methods
+    .forfeitRecord()
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('close_and_sweep on-chain tests', () => {
+  const treasury = Keypair.generate().publicKey;
+  let previousTreasury: PublicKey;
+
+  async function closeAndSweep(recordPDA: PublicKey): Promise<void> {
+    await program.methods
+      .closeAndSweep()
+      .accounts({
+        authority: authority.publicKey,
+        state: statePDA,
+        airdropRecord: recordPDA,
+        treasury,
+        ethRegistry: await recordRegistryPDA(recordPDA),
+      })
+      .rpc();
+  }
+
+  beforeAll(async () => {
+    await setupSuite();
+
+    const state = await program.account.globalStateV2.fetch(statePDA);
+    previousTreasury = state.treasury;
+    await setConfig('setTreasury', treasury);
+  });
+
+  afterAll(async () => {
+    await setConfig('setTreasury', previousTreasury);
+  });
+
+  describe('close_and_sweep', () => {
+    it('should reject a record that is not forfeited', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await expect(closeAndSweep(recordPDA)).rejects.toThrow(/NotForfeited/);
+    });
+
+    it('should tally the record and pay its rent to the treasury', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await grantClaim(recordPDA, 1_000);
+      await forfeit(recordPDA);
+      const before = await program.account.globalStateV2.fetch(statePDA);
+
+      await closeAndSweep(recordPDA);
+
+      expect(await provider.connection.getAccountInfo(recordPDA)).toBeNull();
+      expect(await provider.connection.getBalance(treasury)).toBeGreaterThan(0);
+      const after = await program.account.globalStateV2.fetch(statePDA);
+      expect(after.sweptXnm.sub(before.sweptXnm).toString()).toBe('1000');
+      expect(before.recordCount.sub(after.recordCount).toString()).toBe('1');
+    });
+  });
+});
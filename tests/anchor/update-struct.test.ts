@@ -0,0 +1,141 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  program,
+  authority,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  recordBlacklistPDA,
+} from './helpers';
+
+async function updateRecordStruct(
+  recordPDA: PublicKey,
+  amounts: { xnm: number; xblk: number; xuni: number; native: number },
+  expectedNonce: number
+): Promise<void> {
+  await program.methods
+    .updateRecordStruct(
+      {
+        xnm: new anchor.BN(amounts.xnm),
+        xblk: new anchor.BN(amounts.xblk),
+        xuni: new anchor.BN(amounts.xuni),
+        native: new anchor.BN(amounts.native),
+      },
+      true,
+      Array(16).fill(0),
+      new anchor.BN(expectedNonce)
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+/** Credit a whole-token XNM amount, quoting the record's current nonce */
+async function updateRecordWhole(
+  recordPDA: PublicKey,
+  xnm: string
+): Promise<void> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  await program.methods
+    .updateRecordWhole(
+      { xnm: new anchor.BN(xnm), xblk: zero, xuni: zero, native: zero },
+      true,
+      Array(16).fill(0),
+      nonce
+    )
+    .accounts({
+      authority: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: null,
+      blacklist: await recordBlacklistPDA(recordPDA),
+    })
+    .rpc();
+}
+
+describe.skipIf(!hasValidator)('update_record_struct on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('update_record_struct', () => {
+    it('should credit each named amount to its token', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await updateRecordStruct(
+        recordPDA,
+        { xnm: 1_000, xblk: 2_000, xuni: 3_000, native: 4_000 },
+        0
+      );
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+      expect(record.xblkAirdropped.toString()).toBe('2000');
+      expect(record.xuniAirdropped.toString()).toBe('3000');
+      expect(record.nativeAirdropped.toString()).toBe('4000');
+    });
+
+    it('should reject a stale nonce', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const amounts = { xnm: 1_000, xblk: 0, xuni: 0, native: 0 };
+      await updateRecordStruct(recordPDA, amounts, 0);
+
+      await expect(updateRecordStruct(recordPDA, amounts, 0)).rejects.toThrow(
+        /NonceMismatch/
+      );
+    });
+  });
+
+  describe('update_record_whole', () => {
+    it('should scale whole tokens by the configured decimals', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setConfig('setDecimals', 2);
+      try {
+        await updateRecordWhole(recordPDA, '5');
+      } finally {
+        await setConfig('setDecimals', 9);
+      }
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('500');
+    });
+
+    it('should refuse to scale while decimals are unset', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setConfig('setDecimals', 0);
+      try {
+        await expect(updateRecordWhole(recordPDA, '5')).rejects.toThrow(
+          /DecimalsNotSet/
+        );
+      } finally {
+        await setConfig('setDecimals', 9);
+      }
+    });
+
+    it('should reject decimals above 18', async () => {
+      await expect(setConfig('setDecimals', 19)).rejects.toThrow(
+        /InvalidDecimals/
+      );
+    });
+
+    it('should reject an amount that overflows once scaled', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      // 10^13 whole tokens at 9 decimals exceeds u64
+      await expect(
+        updateRecordWhole(recordPDA, '10000000000000')
+      ).rejects.toThrow(/Overflow/);
+    });
+  });
+});
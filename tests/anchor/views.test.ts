@@ -0,0 +1,296 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  hasValidator,
+  statePDA,
+  anchor,
+  provider,
+  program,
+  authority,
+  PROGRAM_ID,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  credit,
+  recordBlacklistPDA,
+  emittedEvents,
+} from './helpers';
+import { deriveOperatorPDA } from '../../src/onchain/pda';
+
+async function sumByEth(ethAddress: string, records: PublicKey[]) {
+  return program.methods
+    .sumByEth(Array.from(Buffer.from(ethAddress)))
+    .accounts({ state: statePDA })
+    .remainingAccounts(
+      records.map((pubkey) => ({
+        pubkey,
+        isSigner: false,
+        isWritable: false,
+      }))
+    )
+    .view();
+}
+
+async function getAdminState(operators: PublicKey[]) {
+  return program.methods
+    .getAdminState()
+    .accounts({ state: statePDA })
+    .remainingAccounts(
+      operators.map((pubkey) => ({
+        pubkey,
+        isSigner: false,
+        isWritable: false,
+      }))
+    )
+    .view();
+}
+
+async function simulateBatch(
+  recordPDA: PublicKey,
+  isWritable: boolean
+): Promise<number> {
+  const zero = new anchor.BN(0);
+  const { nonce } = await program.account.airdropRecordV2.fetch(recordPDA);
+  return program.methods
+    .simulateBatch([
+      {
+        xnmAmount: new anchor.BN(1_000),
+        xblkAmount: zero,
+        xuniAmount: zero,
+        nativeAmount: zero,
+        expectedNonce: nonce,
+      },
+    ])
+    .accounts({ state: statePDA })
+    .remainingAccounts([
+      { pubkey: recordPDA, isSigner: false, isWritable },
+      {
+        pubkey: await recordBlacklistPDA(recordPDA),
+        isSigner: false,
+        isWritable: false,
+      },
+    ])
+    .view();
+}
+
+describe.skipIf(!hasValidator)('read-only view on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('get_config', () => {
+    it('should mirror the configuration in GlobalState', async () => {
+      await setConfig('setPause', true);
+      try {
+        const config = await program.methods
+          .getConfig()
+          .accounts({ state: statePDA })
+          .view();
+        const state = await program.account.globalStateV2.fetch(statePDA);
+        expect(config.viewVersion).toBe(1);
+        expect(config.stateVersion).toBe(state.version);
+        expect(config.authority.equals(state.authority)).toBe(true);
+        expect(config.paused).toBe(true);
+        expect(config.maxTotalXnm.toString()).toBe(
+          state.maxTotalXnm.toString()
+        );
+        expect(config.distributionEnd.toString()).toBe(
+          state.distributionEnd.toString()
+        );
+        expect(config.threshold).toBe(state.threshold);
+      } finally {
+        await setConfig('setPause', false);
+      }
+    });
+  });
+
+  describe('get_limits', () => {
+    it('should mirror the caps and intervals in GlobalState', async () => {
+      const limits = await program.methods
+        .getLimits()
+        .accounts({ state: statePDA })
+        .view();
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      expect(limits.maxRuns.toString()).toBe(state.maxRuns.toString());
+      expect(limits.minUpdateInterval.toString()).toBe(
+        state.minUpdateInterval.toString()
+      );
+      expect(limits.splitBps[0]).toBe(state.splitBpsXnm);
+    });
+  });
+
+  describe('checkpoint', () => {
+    it('should emit the program-wide counters', async () => {
+      const signature = await program.methods
+        .checkpoint()
+        .accounts({ state: statePDA })
+        .rpc({ commitment: 'confirmed' });
+
+      const state = await program.account.globalStateV2.fetch(statePDA);
+      const event = (await emittedEvents(signature)).find(
+        (e) => e.name === 'programCheckpoint'
+      );
+      expect(event?.data.version).toBe(state.version);
+      expect(String(event?.data.runCounter)).toBe(state.runCounter.toString());
+      expect(String(event?.data.recordCount)).toBe(
+        state.recordCount.toString()
+      );
+      expect(String(event?.data.xnmAirdropped)).toBe(
+        state.xnmAirdropped.toString()
+      );
+    });
+  });
+
+  describe('sum_by_eth', () => {
+    it("should sum the address's records", async () => {
+      const ethAddress = randomEthAddress();
+      const recordPDA = await initializeRecord(ethAddress);
+      await credit(recordPDA, 1_000);
+
+      const totals = await sumByEth(ethAddress, [recordPDA]);
+      expect(totals.records.toString()).toBe('1');
+      expect(totals.xnm.toString()).toBe('1000');
+    });
+
+    it('should reject a record passed twice', async () => {
+      const ethAddress = randomEthAddress();
+      const recordPDA = await initializeRecord(ethAddress);
+      await credit(recordPDA, 1_000);
+
+      await expect(
+        sumByEth(ethAddress, [recordPDA, recordPDA])
+      ).rejects.toThrow(/DuplicateAccount/);
+    });
+  });
+
+  describe('assert_reserved_clean', () => {
+    it('should pass a current record with credits', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await credit(recordPDA, 1_000);
+
+      await program.methods
+        .assertReservedClean()
+        .accounts({ airdropRecord: recordPDA })
+        .rpc();
+    });
+  });
+
+  describe('record_grand_total', () => {
+    it('should sum every token on the record', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await program.methods
+        .updateRecordV2(
+          new anchor.BN(1_000),
+          new anchor.BN(2_000),
+          new anchor.BN(3_000),
+          new anchor.BN(4_000),
+          true,
+          Array(16).fill(0),
+          new anchor.BN(0)
+        )
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          airdropRecord: recordPDA,
+          airdropRun: null,
+          operator: null,
+          blacklist: await recordBlacklistPDA(recordPDA),
+        })
+        .rpc();
+
+      const total = await program.methods
+        .recordGrandTotal()
+        .accounts({ airdropRecord: recordPDA })
+        .view();
+      expect(total.toString()).toBe('10000');
+    });
+  });
+
+  describe('rent_for_record', () => {
+    it("should match a new record's balance", async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      const account = await provider.connection.getAccountInfo(recordPDA);
+
+      const rent = await program.methods
+        .rentForRecord()
+        .accounts({ state: statePDA })
+        .view();
+      expect(rent.toString()).toBe(String(account?.lamports));
+    });
+  });
+
+  describe('simulate_batch', () => {
+    it('should pass an entry the real batch would apply', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      expect(await simulateBatch(recordPDA, true)).toBe(0b1);
+    });
+
+    it('should fail a read-only record like the real batch', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      expect(await simulateBatch(recordPDA, false)).toBe(0);
+    });
+  });
+
+  describe('health_check', () => {
+    it('should report the pause flag from GlobalState', async () => {
+      await setConfig('setPause', true);
+      try {
+        const health = await program.methods
+          .healthCheck()
+          .accounts({ state: statePDA })
+          .view();
+        expect(health.paused).toBe(true);
+        expect(health.retired).toBe(false);
+      } finally {
+        await setConfig('setPause', false);
+      }
+    });
+  });
+
+  describe('get_admin_state', () => {
+    const operator = Keypair.generate().publicKey;
+    const [operatorPDA] = deriveOperatorPDA(PROGRAM_ID, operator);
+
+    beforeAll(async () => {
+      await program.methods
+        .addOperator(operator)
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          operator: operatorPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    });
+
+    afterAll(async () => {
+      await program.methods
+        .removeOperator()
+        .accounts({
+          authority: authority.publicKey,
+          state: statePDA,
+          operator: operatorPDA,
+        })
+        .rpc();
+    });
+
+    it('should list the authority and every operator', async () => {
+      const operators = await program.account.operator.all();
+
+      const view = await getAdminState(operators.map((o) => o.publicKey));
+      const expected = [
+        authority.publicKey,
+        ...operators.map((o) => o.account.operator),
+      ];
+      expect(view.operatorCount).toBe(expected.length);
+      expect(view.operators.map(String)).toEqual(expected.map(String));
+    });
+
+    it('should reject an incomplete operator list', async () => {
+      await expect(getAdminState([])).rejects.toThrow(/OperatorSetMismatch/);
+    });
+  });
+});
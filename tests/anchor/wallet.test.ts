@@ -0,0 +1,160 @@
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  Transaction,
+} from '@solana/web3.js';
+import {
+  hasValidator,
+  PROGRAM_ID,
+  statePDA,
+  anchor,
+  provider,
+  program,
+  authority,
+  runPDA,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  deriveRecordPDA,
+  initializeRecord,
+  deriveRegistryPDA,
+  recordRegistryPDA,
+} from './helpers';
+import { deriveBlacklistPDA } from '../../src/onchain/pda';
+
+async function initializeAndUpdate(
+  ethAddress: string,
+  solWallet: PublicKey
+): Promise<PublicKey> {
+  const ethBytes = Buffer.from(ethAddress);
+  const [recordPDA] = deriveRecordPDA(ethAddress);
+  await program.methods
+    .initializeAndUpdateV2(
+      Array.from(ethBytes),
+      new anchor.BN(1_000),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(0),
+      solWallet,
+      []
+    )
+    .accounts({
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      state: statePDA,
+      airdropRecord: recordPDA,
+      airdropRun: runPDA,
+      systemProgram: SystemProgram.programId,
+      operator: null,
+      blacklist: deriveBlacklistPDA(PROGRAM_ID, solWallet)[0],
+      wallet: solWallet,
+      ethRegistry: deriveRegistryPDA(ethAddress)[0],
+    })
+    .rpc();
+  return recordPDA;
+}
+
+describe.skipIf(!hasValidator)('sol_wallet validation on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite(false);
+  });
+
+  afterAll(async () => {
+    // Leave the owner check off for the other suites
+    await setConfig('setEnforceSystemWallet', false);
+  });
+
+  describe('initialize_and_update_v2', () => {
+    it('should accept an on-curve wallet', async () => {
+      const wallet = Keypair.generate().publicKey;
+      const recordPDA = await initializeAndUpdate(randomEthAddress(), wallet);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+
+    it('should reject an off-curve PDA', async () => {
+      await expect(
+        initializeAndUpdate(randomEthAddress(), statePDA)
+      ).rejects.toThrow(/InvalidWallet\./);
+    });
+
+    it('should reject the default pubkey', async () => {
+      await expect(
+        initializeAndUpdate(randomEthAddress(), PublicKey.default)
+      ).rejects.toThrow(/InvalidWallet\./);
+    });
+
+    it('should reject a program-owned wallet when enforced', async () => {
+      // On-curve, but owned by this program rather than the system program
+      const account = Keypair.generate();
+      const space = 8;
+      const lamports =
+        await provider.connection.getMinimumBalanceForRentExemption(space);
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: authority.publicKey,
+            newAccountPubkey: account.publicKey,
+            lamports,
+            space,
+            programId: PROGRAM_ID,
+          })
+        ),
+        [account]
+      );
+
+      await setConfig('setEnforceSystemWallet', true);
+      await expect(
+        initializeAndUpdate(randomEthAddress(), account.publicKey)
+      ).rejects.toThrow(/InvalidWalletOwner/);
+
+      // A fresh keypair has no account yet, which counts as a wallet
+      await initializeAndUpdate(
+        randomEthAddress(),
+        Keypair.generate().publicKey
+      );
+    });
+  });
+
+  describe('initialize_record_v2', () => {
+    it('should reject an off-curve PDA', async () => {
+      await expect(
+        initializeRecord(randomEthAddress(), statePDA)
+      ).rejects.toThrow(/InvalidWallet\./);
+    });
+
+    it('should reject the default pubkey', async () => {
+      await expect(
+        initializeRecord(randomEthAddress(), PublicKey.default)
+      ).rejects.toThrow(/InvalidWallet\./);
+    });
+  });
+
+  describe('set_record_wallet', () => {
+    it('should reject an off-curve PDA', async () => {
+      const recordPDA = await initializeAndUpdate(
+        randomEthAddress(),
+        Keypair.generate().publicKey
+      );
+
+      await expect(
+        program.methods
+          .setRecordWallet()
+          .accounts({
+            authority: authority.publicKey,
+            state: statePDA,
+            airdropRecord: recordPDA,
+            wallet: statePDA,
+            allowedWallet: null,
+            blacklist: deriveBlacklistPDA(PROGRAM_ID, statePDA)[0],
+            ethRegistry: await recordRegistryPDA(recordPDA),
+          })
+          .rpc()
+      ).rejects.toThrow(/InvalidWallet\./);
+    });
+  });
+});
@@ -0,0 +1,41 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import {
+  hasValidator,
+  program,
+  setupSuite,
+  setConfig,
+  randomEthAddress,
+  initializeRecord,
+  credit,
+} from './helpers';
+
+describe.skipIf(!hasValidator)('zero-amount update on-chain tests', () => {
+  beforeAll(async () => {
+    await setupSuite();
+  });
+
+  describe('set_reject_zero_updates', () => {
+    it('should reject an all-zero update while enabled', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+      await setConfig('setRejectZeroUpdates', true);
+      try {
+        await expect(credit(recordPDA, 0)).rejects.toThrow(/ZeroAmountUpdate/);
+        await credit(recordPDA, 1_000);
+      } finally {
+        await setConfig('setRejectZeroUpdates', false);
+      }
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.xnmAirdropped.toString()).toBe('1000');
+    });
+
+    it('should accept an all-zero update while disabled', async () => {
+      const recordPDA = await initializeRecord(randomEthAddress());
+
+      await credit(recordPDA, 0);
+
+      const record = await program.account.airdropRecordV2.fetch(recordPDA);
+      expect(record.nonce.toString()).toBe('1');
+    });
+  });
+});
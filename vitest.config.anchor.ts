@@ -6,5 +6,8 @@ export default defineConfig({
     environment: 'node',
     include: ['tests/anchor/**/*.test.ts'],
     testTimeout: 30000,
+    // Suites share one GlobalState and toggle its config, so run them one
+    // file at a time
+    fileParallelism: false,
   },
 });